@@ -0,0 +1,258 @@
+//! Validation of IPFS content identifiers in their string form.
+//!
+//! Two forms are accepted: CIDv0 (`Qm...`, base58btc, always a SHA2-256 multihash) and CIDv1
+//! in its canonical lowercase base32 form (`b...`). CIDv1 must name a `dag-pb` or `raw` block
+//! hashed with SHA2-256 or BLAKE2b-256, which are the blocks IPFS stores files in and the
+//! hashes `pallet_media_check` can verify. The CID is checked to be well formed; the content it
+//! refers to is not looked at.
+
+use sp_runtime::RuntimeDebug;
+use sp_std::prelude::*;
+
+/// The longest CID accepted, in characters.
+pub const MAX_CID_LEN: usize = 128;
+
+/// The length of the digests of the accepted hash functions, in bytes.
+pub const DIGEST_LEN: u64 = 32;
+
+const BASE58_ALPHABET: &[u8] = b"123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
+const BASE32_ALPHABET: &[u8] = b"abcdefghijklmnopqrstuvwxyz234567";
+
+/// The multihash prefix of every CIDv0: SHA2-256 with a 32-byte digest.
+const CIDV0_MULTIHASH_PREFIX: [u8; 2] = [0x12, 0x20];
+const CIDV0_LEN: usize = 46;
+
+/// The multihash code of SHA2-256.
+pub const SHA2_256: u64 = 0x12;
+/// The multihash code of BLAKE2b-256.
+pub const BLAKE2B_256: u64 = 0xb220;
+
+/// The multicodec code of a UnixFS DAG node, the block IPFS stores files in by default.
+pub const DAG_PB: u64 = 0x70;
+/// The multicodec code of a raw block, the block IPFS stores small files or leaves in.
+pub const RAW: u64 = 0x55;
+
+/// The hash a CID refers to its content by.
+#[derive(Clone, Eq, PartialEq, RuntimeDebug)]
+pub struct Multihash {
+	/// The multihash code of the hash function.
+	pub code: u64,
+	/// The hash of the content.
+	pub digest: Vec<u8>,
+}
+
+/// Whether `cid` is a well-formed CIDv0 or base32 CIDv1.
+pub fn is_valid(cid: &[u8]) -> bool {
+	multihash(cid).is_some()
+}
+
+/// The multihash of a well-formed CIDv0 or base32 CIDv1.
+///
+/// The digest is the hash of the IPFS block the CID names, which for media added with default
+/// settings is the root of a DAG rather than the file itself.
+pub fn multihash(cid: &[u8]) -> Option<Multihash> {
+	if cid.len() > MAX_CID_LEN {
+		return None
+	}
+	match cid {
+		[b'Q', b'm', ..] if cid.len() == CIDV0_LEN => {
+			let bytes = base58_decode(cid)?;
+			if bytes.len() != 34 || bytes[..2] != CIDV0_MULTIHASH_PREFIX {
+				return None
+			}
+			Some(Multihash { code: SHA2_256, digest: bytes[2..].to_vec() })
+		},
+		[b'b', rest @ ..] => cidv1_multihash(&base32_decode(rest)?),
+		_ => None,
+	}
+}
+
+/// Parses the binary form of a CIDv1: version, content codec and multihash.
+fn cidv1_multihash(bytes: &[u8]) -> Option<Multihash> {
+	let mut input = bytes;
+	let version = read_varint(&mut input)?;
+	let codec = read_varint(&mut input)?;
+	let code = read_varint(&mut input)?;
+	let digest_len = read_varint(&mut input)?;
+
+	let valid = version == 1
+		&& matches!(codec, DAG_PB | RAW)
+		&& matches!(code, SHA2_256 | BLAKE2B_256)
+		&& digest_len == DIGEST_LEN
+		&& input.len() as u64 == digest_len;
+	if !valid {
+		return None
+	}
+	Some(Multihash { code, digest: input.to_vec() })
+}
+
+/// Reads an unsigned LEB128 varint of at most 9 bytes, as used by multiformats.
+fn read_varint(input: &mut &[u8]) -> Option<u64> {
+	let mut value = 0u64;
+	for i in 0..9 {
+		let (&byte, rest) = input.split_first()?;
+		*input = rest;
+		value |= u64::from(byte & 0x7f) << (7 * i);
+		if byte & 0x80 == 0 {
+			// Varints must be minimally encoded.
+			return if i > 0 && byte == 0 { None } else { Some(value) }
+		}
+	}
+	None
+}
+
+fn base58_decode(input: &[u8]) -> Option<Vec<u8>> {
+	let mut bytes: Vec<u8> = Vec::with_capacity(input.len());
+	for c in input {
+		let mut carry = BASE58_ALPHABET.iter().position(|a| a == c)? as u32;
+		for byte in bytes.iter_mut().rev() {
+			carry += u32::from(*byte) * 58;
+			*byte = carry as u8;
+			carry >>= 8;
+		}
+		while carry > 0 {
+			bytes.insert(0, carry as u8);
+			carry >>= 8;
+		}
+	}
+	let leading_zeros = input.iter().take_while(|&&c| c == BASE58_ALPHABET[0]).count();
+	let mut decoded = vec![0; leading_zeros];
+	decoded.extend(bytes);
+	Some(decoded)
+}
+
+/// Decodes unpadded lowercase RFC 4648 base32.
+fn base32_decode(input: &[u8]) -> Option<Vec<u8>> {
+	let mut bytes = Vec::with_capacity(input.len() * 5 / 8);
+	let mut buffer = 0u32;
+	let mut bits = 0;
+	for c in input {
+		buffer = (buffer << 5) | BASE32_ALPHABET.iter().position(|a| a == c)? as u32;
+		bits += 5;
+		if bits >= 8 {
+			bits -= 8;
+			bytes.push((buffer >> bits) as u8);
+			buffer &= (1 << bits) - 1;
+		}
+	}
+	// Leftover bits are padding and must be zero.
+	if bits >= 5 || buffer != 0 {
+		return None
+	}
+	Some(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	/// The CIDv0 of the IPFS readme, and the same multihash in CIDv1 form.
+	const CIDV0: &str = "QmYwAPJzv5CZsnA625s3Xf2nemtYgPpHdWEz79ojWnPbdG";
+	const CIDV1_DAG_PB: &str = "bafybeie5nqv6kd3qnfjupgvz34woh3oksc3iau6abmyajn7qvtf6d2ho34";
+	const CIDV1_RAW: &str = "bafkreie5nqv6kd3qnfjupgvz34woh3oksc3iau6abmyajn7qvtf6d2ho34";
+
+	#[test]
+	fn accepts_cids_of_supported_codecs_and_hashes() {
+		let cases: &[(&str, &str, u64)] = &[
+			("CIDv0", CIDV0, SHA2_256),
+			("CIDv1 dag-pb SHA2-256", CIDV1_DAG_PB, SHA2_256),
+			("CIDv1 raw SHA2-256", CIDV1_RAW, SHA2_256),
+			(
+				"CIDv1 raw BLAKE2b-256",
+				"bafk2bzacecowyk7fb5ygsu2htk456lhd5xfjbnuakpaawmaew7ykzs7b5dxn6",
+				BLAKE2B_256,
+			),
+		];
+		for (name, cid, code) in cases {
+			let hash = multihash(cid.as_bytes()).unwrap_or_else(|| panic!("{} rejected", name));
+			assert_eq!(hash.code, *code, "{}", name);
+			assert_eq!(hash.digest.len() as u64, DIGEST_LEN, "{}", name);
+			assert!(is_valid(cid.as_bytes()), "{}", name);
+		}
+	}
+
+	#[test]
+	fn both_versions_carry_the_same_digest() {
+		let v0 = multihash(CIDV0.as_bytes()).unwrap();
+		assert_eq!(multihash(CIDV1_DAG_PB.as_bytes()), Some(v0.clone()));
+		assert_eq!(multihash(CIDV1_RAW.as_bytes()), Some(v0));
+	}
+
+	#[test]
+	fn rejects_malformed_and_unsupported_cids() {
+		let too_long = format!("b{}", "a".repeat(MAX_CID_LEN));
+		let cases: &[(&str, &str)] = &[
+			("empty", ""),
+			("CIDv0 too short", &CIDV0[..45]),
+			("CIDv0 too long", "QmYwAPJzv5CZsnA625s3Xf2nemtYgPpHdWEz79ojWnPbdGa"),
+			(
+				"CIDv0 with a character outside base58",
+				"QmYwAPJzv5CZsnA625s3Xf2nemtYgPpHdWEz79ojWnPbd0",
+			),
+			("CIDv0 without the Qm prefix", "S5biFGNwMeSeeWBCV21xyYZHzHryR8XZhi42JQ9YqGbSdt"),
+			(
+				"uppercase base32",
+				"BAFKREIE5NQV6KD3QNFJUPGVZ34WOH3OKSC3IAU6ABMYAJN7QVTF6D2HO34",
+			),
+			(
+				"base32 padding",
+				"bafkreie5nqv6kd3qnfjupgvz34woh3oksc3iau6abmyajn7qvtf6d2ho34====",
+			),
+			(
+				"base32 with non-zero leftover bits",
+				"bafkreie5nqv6kd3qnfjupgvz34woh3oksc3iau6abmyajn7qvtf6d2ho35",
+			),
+			("other multibase", "zdj7WhuEjrB52m1BisYCtmjH1hSKa7yZ3jEZ9JcXaFRD51wVz"),
+			("CIDv2", "bajkreie5nqv6kd3qnfjupgvz34woh3oksc3iau6abmyajn7qvtf6d2ho34"),
+			(
+				"unsupported codec (json)",
+				"bagaaieratvwcxzipobuvgr42xhpszy7nzkilnactyaftabfx6cwmxypi53pq",
+			),
+			(
+				"unsupported hash (SHA2-512)",
+				"bafkrgqfevpkejdcjkywyfaiv2e5b7thksj7vfngviwjjp6fuhzbnvcjdrpatmjxehxftrxnqqjeisj7msbh3iicxiq4yh2efqulz2ucvdl7ge",
+			),
+			("truncated digest", "bafkreee5nqv6kd3qnfjupgvz34woh3ok"),
+			("trailing bytes", "bafkreie5nqv6kd3qnfjupgvz34woh3oksc3iau6abmyajn7qvtf6d2ho34aa"),
+			(
+				"non-minimal varint",
+				"bahkqaeratvwcxzipobuvgr42xhpszy7nzkilnactyaftabfx6cwmxypi53pq",
+			),
+			("longer than MAX_CID_LEN", too_long.as_str()),
+		];
+		for (name, cid) in cases {
+			assert_eq!(multihash(cid.as_bytes()), None, "{}", name);
+			assert!(!is_valid(cid.as_bytes()), "{}", name);
+		}
+	}
+
+	#[test]
+	fn reads_minimal_varints_only() {
+		let cases: &[(&[u8], Option<u64>)] = &[
+			(&[0x00], Some(0)),
+			(&[0x7f], Some(0x7f)),
+			(&[0x80, 0x01], Some(0x80)),
+			(&[0xa0, 0xe4, 0x02], Some(BLAKE2B_256)),
+			(&[], None),
+			(&[0x80], None),
+			(&[0x80, 0x00], None),
+			(&[0xff; 9], None),
+		];
+		for (bytes, expected) in cases {
+			let mut input = *bytes;
+			assert_eq!(read_varint(&mut input), *expected, "{:02x?}", bytes);
+		}
+	}
+
+	#[test]
+	fn decodes_base58_and_base32() {
+		assert_eq!(base58_decode(b"1112"), Some(vec![0, 0, 0, 1]));
+		assert_eq!(base58_decode(b"5Q"), Some(vec![0xff]));
+		assert_eq!(base58_decode(b"0"), None);
+		assert_eq!(base32_decode(b"my"), Some(b"f".to_vec()));
+		assert_eq!(base32_decode(b"mzxw6"), Some(b"foo".to_vec()));
+		assert_eq!(base32_decode(b"mz"), None);
+		assert_eq!(base32_decode(b"m"), None);
+		assert_eq!(base32_decode(b"MY"), None);
+	}
+}
@@ -0,0 +1,326 @@
+//! # Chiba Studio NFT Metadata
+//!
+//! The metadata standard for Chiba Studio NFT classes and tokens.
+//!
+//! `orml_nft` stores the metadata of classes and tokens as opaque bytes next to the runtime's
+//! `ClassData` and `TokenData`. Under this standard those bytes are the SCALE encoding of a
+//! [`Metadata`]: a name, a description, the IPFS CID of the media and its MIME type, and a list
+//! of attributes. [`parse`] decodes metadata and checks it against the limits below, so the
+//! runtime and its clients agree on what counts as valid.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use codec::{Decode, Encode};
+use sp_runtime::RuntimeDebug;
+use sp_std::prelude::*;
+
+pub mod cid;
+
+/// The longest encoded metadata accepted, in bytes.
+pub const MAX_METADATA_LEN: usize = 4096;
+/// The longest name, in bytes.
+pub const MAX_NAME_LEN: usize = 64;
+/// The longest description, in bytes.
+pub const MAX_DESCRIPTION_LEN: usize = 1024;
+/// The longest MIME type, in bytes.
+pub const MAX_MIME_TYPE_LEN: usize = 64;
+/// The most attributes a class or token may have.
+pub const MAX_ATTRIBUTES: usize = 16;
+/// The longest attribute key, in bytes.
+pub const MAX_ATTRIBUTE_KEY_LEN: usize = 32;
+/// The longest attribute value, in bytes.
+pub const MAX_ATTRIBUTE_VALUE_LEN: usize = 128;
+
+/// The media types the first part of a MIME type may name.
+const MIME_TOP_LEVEL_TYPES: &[&[u8]] =
+	&[b"application", b"audio", b"font", b"image", b"model", b"text", b"video"];
+
+/// The metadata of an NFT class or token.
+///
+/// All text is UTF-8.
+#[derive(Clone, Eq, PartialEq, Encode, Decode, RuntimeDebug)]
+pub struct Metadata {
+	/// A short, non-empty name.
+	pub name: Vec<u8>,
+	/// A longer description, possibly empty.
+	pub description: Vec<u8>,
+	/// The IPFS CID of the media, as a CIDv0 or base32 CIDv1 string.
+	pub media: Vec<u8>,
+	/// The MIME type of the media, e.g. `image/png`.
+	pub mime_type: Vec<u8>,
+	/// Free-form traits, with unique keys.
+	pub attributes: Vec<Attribute>,
+}
+
+/// A named trait of an NFT class or token.
+#[derive(Clone, Eq, PartialEq, Encode, Decode, RuntimeDebug)]
+pub struct Attribute {
+	/// The non-empty name of the trait.
+	pub key: Vec<u8>,
+	/// The value of the trait.
+	pub value: Vec<u8>,
+}
+
+/// Why metadata does not follow the standard.
+#[derive(Clone, Copy, Eq, PartialEq, Encode, Decode, RuntimeDebug)]
+pub enum MetadataError {
+	/// The metadata is longer than `MAX_METADATA_LEN`.
+	TooLong,
+	/// The metadata is not the encoding of a `Metadata`.
+	Undecodable,
+	/// A text field is not valid UTF-8.
+	InvalidUtf8,
+	/// The name is empty or longer than `MAX_NAME_LEN`.
+	InvalidName,
+	/// The description is longer than `MAX_DESCRIPTION_LEN`.
+	DescriptionTooLong,
+	/// The media is not a valid IPFS CID.
+	InvalidCid,
+	/// The MIME type is not of the form `type/subtype`.
+	InvalidMimeType,
+	/// There are more than `MAX_ATTRIBUTES` attributes.
+	TooManyAttributes,
+	/// An attribute key is empty or too long, or a value is too long.
+	InvalidAttribute,
+	/// Two attributes have the same key.
+	DuplicateAttribute,
+}
+
+impl MetadataError {
+	/// A short description of the error, for clients.
+	pub fn as_str(&self) -> &'static str {
+		match self {
+			Self::TooLong => "metadata is too long",
+			Self::Undecodable => "metadata is not encoded according to the standard",
+			Self::InvalidUtf8 => "text is not valid UTF-8",
+			Self::InvalidName => "name is empty or too long",
+			Self::DescriptionTooLong => "description is too long",
+			Self::InvalidCid => "media is not a valid IPFS CID",
+			Self::InvalidMimeType => "MIME type is invalid",
+			Self::TooManyAttributes => "there are too many attributes",
+			Self::InvalidAttribute => "an attribute key or value is invalid",
+			Self::DuplicateAttribute => "two attributes have the same key",
+		}
+	}
+}
+
+/// Decodes metadata stored by `orml_nft` and checks that it follows the standard.
+pub fn parse(bytes: &[u8]) -> Result<Metadata, MetadataError> {
+	if bytes.len() > MAX_METADATA_LEN {
+		return Err(MetadataError::TooLong)
+	}
+	let mut input = bytes;
+	let metadata = Metadata::decode(&mut input).map_err(|_| MetadataError::Undecodable)?;
+	if !input.is_empty() {
+		return Err(MetadataError::Undecodable)
+	}
+	metadata.validate()?;
+	Ok(metadata)
+}
+
+impl Metadata {
+	/// Checks that the metadata follows the standard.
+	pub fn validate(&self) -> Result<(), MetadataError> {
+		if self.name.is_empty() || self.name.len() > MAX_NAME_LEN {
+			return Err(MetadataError::InvalidName)
+		}
+		if self.description.len() > MAX_DESCRIPTION_LEN {
+			return Err(MetadataError::DescriptionTooLong)
+		}
+		ensure_utf8(&self.name)?;
+		ensure_utf8(&self.description)?;
+
+		if !cid::is_valid(&self.media) {
+			return Err(MetadataError::InvalidCid)
+		}
+		if !is_valid_mime_type(&self.mime_type) {
+			return Err(MetadataError::InvalidMimeType)
+		}
+
+		if self.attributes.len() > MAX_ATTRIBUTES {
+			return Err(MetadataError::TooManyAttributes)
+		}
+		for (i, attribute) in self.attributes.iter().enumerate() {
+			if attribute.key.is_empty()
+				|| attribute.key.len() > MAX_ATTRIBUTE_KEY_LEN
+				|| attribute.value.len() > MAX_ATTRIBUTE_VALUE_LEN
+			{
+				return Err(MetadataError::InvalidAttribute)
+			}
+			ensure_utf8(&attribute.key)?;
+			ensure_utf8(&attribute.value)?;
+			if self.attributes[..i].iter().any(|other| other.key == attribute.key) {
+				return Err(MetadataError::DuplicateAttribute)
+			}
+		}
+		Ok(())
+	}
+}
+
+fn ensure_utf8(text: &[u8]) -> Result<(), MetadataError> {
+	sp_std::str::from_utf8(text).map(|_| ()).map_err(|_| MetadataError::InvalidUtf8)
+}
+
+/// Whether `mime_type` is a lowercase `type/subtype` with a registered top-level type. Parameters
+/// such as `; charset=utf-8` are not allowed.
+fn is_valid_mime_type(mime_type: &[u8]) -> bool {
+	if mime_type.len() > MAX_MIME_TYPE_LEN {
+		return false
+	}
+	let mut parts = mime_type.splitn(2, |&c| c == b'/');
+	let (top_level, subtype) = match (parts.next(), parts.next()) {
+		(Some(top_level), Some(subtype)) => (top_level, subtype),
+		_ => return false,
+	};
+	let is_token_char = |c: &u8| {
+		c.is_ascii_lowercase() || c.is_ascii_digit() || b"!#$&-^_.+".contains(c)
+	};
+	MIME_TOP_LEVEL_TYPES.contains(&top_level)
+		&& !subtype.is_empty()
+		&& subtype.iter().all(is_token_char)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn metadata() -> Metadata {
+		Metadata {
+			name: b"Sakura".to_vec(),
+			description: b"A cherry blossom in Chiba.".to_vec(),
+			media: b"QmYwAPJzv5CZsnA625s3Xf2nemtYgPpHdWEz79ojWnPbdG".to_vec(),
+			mime_type: b"image/png".to_vec(),
+			attributes: vec![attribute("season", "spring"), attribute("colour", "pink")],
+		}
+	}
+
+	fn attribute(key: &str, value: &str) -> Attribute {
+		Attribute { key: key.as_bytes().to_vec(), value: value.as_bytes().to_vec() }
+	}
+
+	#[test]
+	fn parses_metadata_that_follows_the_standard() {
+		let cases: &[(&str, fn(&mut Metadata))] = &[
+			("as is", |_| {}),
+			("empty description", |m| m.description.clear()),
+			("no attributes", |m| m.attributes.clear()),
+			("empty attribute value", |m| m.attributes[0].value.clear()),
+			("longest name", |m| m.name = vec![b'a'; MAX_NAME_LEN]),
+			("longest description", |m| m.description = vec![b'a'; MAX_DESCRIPTION_LEN]),
+			("multi-byte UTF-8", |m| m.name = "千葉".as_bytes().to_vec()),
+			("CIDv1 media", |m| {
+				m.media = b"bafkreie5nqv6kd3qnfjupgvz34woh3oksc3iau6abmyajn7qvtf6d2ho34".to_vec()
+			}),
+			("most attributes", |m| {
+				m.attributes = (0..MAX_ATTRIBUTES).map(|i| attribute(&i.to_string(), "")).collect()
+			}),
+			("longest attribute", |m| {
+				m.attributes[0] = Attribute {
+					key: vec![b'k'; MAX_ATTRIBUTE_KEY_LEN],
+					value: vec![b'v'; MAX_ATTRIBUTE_VALUE_LEN],
+				}
+			}),
+		];
+		for (name, change) in cases {
+			let mut expected = metadata();
+			change(&mut expected);
+			assert_eq!(parse(&expected.encode()), Ok(expected), "{}", name);
+		}
+	}
+
+	#[test]
+	fn rejects_metadata_that_breaks_the_standard() {
+		use MetadataError::*;
+		let cases: &[(&str, fn(&mut Metadata), MetadataError)] = &[
+			("empty name", |m| m.name.clear(), InvalidName),
+			("long name", |m| m.name = vec![b'a'; MAX_NAME_LEN + 1], InvalidName),
+			(
+				"long description",
+				|m| m.description = vec![b'a'; MAX_DESCRIPTION_LEN + 1],
+				DescriptionTooLong,
+			),
+			("name not UTF-8", |m| m.name = vec![0xff], InvalidUtf8),
+			("description not UTF-8", |m| m.description = vec![0xc3], InvalidUtf8),
+			("media not a CID", |m| m.media = b"ipfs://sakura.png".to_vec(), InvalidCid),
+			("empty media", |m| m.media.clear(), InvalidCid),
+			("MIME type without subtype", |m| m.mime_type = b"image".to_vec(), InvalidMimeType),
+			(
+				"too many attributes",
+				|m| {
+					m.attributes =
+						(0..=MAX_ATTRIBUTES).map(|i| attribute(&i.to_string(), "")).collect()
+				},
+				TooManyAttributes,
+			),
+			("empty attribute key", |m| m.attributes[0].key.clear(), InvalidAttribute),
+			(
+				"long attribute key",
+				|m| m.attributes[0].key = vec![b'k'; MAX_ATTRIBUTE_KEY_LEN + 1],
+				InvalidAttribute,
+			),
+			(
+				"long attribute value",
+				|m| m.attributes[0].value = vec![b'v'; MAX_ATTRIBUTE_VALUE_LEN + 1],
+				InvalidAttribute,
+			),
+			("attribute value not UTF-8", |m| m.attributes[0].value = vec![0x80], InvalidUtf8),
+			(
+				"duplicate attribute",
+				|m| m.attributes.push(attribute("season", "summer")),
+				DuplicateAttribute,
+			),
+		];
+		for (name, change, error) in cases {
+			let mut metadata = metadata();
+			change(&mut metadata);
+			assert_eq!(metadata.validate(), Err(*error), "{}", name);
+			assert_eq!(parse(&metadata.encode()), Err(*error), "{}", name);
+		}
+	}
+
+	#[test]
+	fn rejects_bytes_that_are_not_exactly_metadata() {
+		let encoded = metadata().encode();
+		let mut trailing = encoded.clone();
+		trailing.push(0);
+		let too_long = vec![0; MAX_METADATA_LEN + 1];
+
+		let cases: &[(&str, &[u8], MetadataError)] = &[
+			("empty", &[], MetadataError::Undecodable),
+			("truncated", &encoded[..encoded.len() - 1], MetadataError::Undecodable),
+			("trailing byte", &trailing[..], MetadataError::Undecodable),
+			("longer than MAX_METADATA_LEN", &too_long[..], MetadataError::TooLong),
+		];
+		for (name, bytes, error) in cases {
+			assert_eq!(parse(bytes), Err(*error), "{}", name);
+		}
+	}
+
+	#[test]
+	fn accepts_lowercase_mime_types_with_a_registered_top_level_type() {
+		let longest = format!("image/{}", "a".repeat(MAX_MIME_TYPE_LEN - "image/".len()));
+		let too_long = format!("{}a", longest);
+		let cases: &[(&str, bool)] = &[
+			("image/png", true),
+			("video/mp4", true),
+			("model/gltf+json", true),
+			("application/vnd.oasis.opendocument.text", true),
+			("audio/x-wav", true),
+			(longest.as_str(), true),
+			("", false),
+			("image", false),
+			("image/", false),
+			("/png", false),
+			("Image/png", false),
+			("image/PNG", false),
+			("example/png", false),
+			("image/png; charset=utf-8", false),
+			("image/svg xml", false),
+			("image/png/x", false),
+			(too_long.as_str(), false),
+		];
+		for (mime_type, valid) in cases {
+			assert_eq!(is_valid_mime_type(mime_type.as_bytes()), *valid, "{}", mime_type);
+		}
+	}
+}
@@ -0,0 +1,122 @@
+//! Runtime API definition for querying Chiba Studio NFT classes and tokens.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+#![allow(clippy::too_many_arguments)]
+#![allow(clippy::unnecessary_mut_passed)]
+
+use codec::{Codec, Decode, Encode};
+use sp_runtime::{traits::NumberFor, RuntimeDebug};
+use sp_std::prelude::*;
+
+pub use chiba_nft_metadata::{Attribute, Metadata, MetadataError};
+
+/// An NFT class together with its owner and metadata.
+#[derive(Clone, Eq, PartialEq, Encode, Decode, RuntimeDebug)]
+pub struct ClassDetails<AccountId, TokenId, ClassData> {
+	/// The account that created and controls the class.
+	pub owner: AccountId,
+	/// Raw class metadata.
+	pub metadata: Vec<u8>,
+	/// The number of tokens of this class currently in existence.
+	pub total_issuance: TokenId,
+	/// Chiba-specific class data.
+	pub data: ClassData,
+}
+
+/// A single NFT together with its owner and metadata.
+#[derive(Clone, Eq, PartialEq, Encode, Decode, RuntimeDebug)]
+pub struct TokenDetails<AccountId, TokenData> {
+	/// The account that holds the token.
+	pub owner: AccountId,
+	/// Raw token metadata.
+	pub metadata: Vec<u8>,
+	/// Chiba-specific token data.
+	pub data: TokenData,
+}
+
+/// A page of the tokens in a class.
+#[derive(Clone, Eq, PartialEq, Encode, Decode, RuntimeDebug)]
+pub struct TokenPage<AccountId, TokenId, TokenData> {
+	/// The tokens found, in ascending ID order.
+	pub tokens: Vec<(TokenId, TokenDetails<AccountId, TokenData>)>,
+	/// The token ID to continue from, or `None` if no token IDs of the class are left.
+	pub next: Option<TokenId>,
+}
+
+/// The number of token IDs `tokens` scans per token requested. Burned tokens leave gaps in a
+/// class's IDs, so a page may hold fewer tokens than requested even when more follow.
+pub const TOKEN_SCAN_FACTOR: u32 = 4;
+
+/// The account currently allowed to use a token.
+#[derive(Clone, Eq, PartialEq, Encode, Decode, RuntimeDebug)]
+pub struct TokenUser<AccountId, BlockNumber> {
+	/// The user of the token: its renter while it is rented, and otherwise its owner.
+	pub user: AccountId,
+	/// The owner of the token, which differs from the holder while it is escrowed for rent.
+	pub owner: AccountId,
+	/// The block in which the rental expires, if the token is rented.
+	pub expires: Option<BlockNumber>,
+}
+
+sp_api::decl_runtime_apis! {
+	/// Queries over the NFT classes and tokens stored by the runtime.
+	///
+	/// Version 2 adds `class_metadata` and `token_metadata`; version 3 adds `token_user`;
+	/// version 4 bounds the IDs scanned by `tokens` and returns where to resume.
+	#[api_version(4)]
+	pub trait ChibaNftApi<AccountId, ClassId, TokenId, ClassData, TokenData> where
+		AccountId: Codec,
+		ClassId: Codec,
+		TokenId: Codec,
+		ClassData: Codec,
+		TokenData: Codec,
+	{
+		/// Returns the class with the given ID, if it exists.
+		fn class(class_id: ClassId) -> Option<ClassDetails<AccountId, TokenId, ClassData>>;
+
+		/// Returns the token with the given class and token ID, if it exists.
+		fn token(class_id: ClassId, token_id: TokenId) -> Option<TokenDetails<AccountId, TokenData>>;
+
+		/// Returns the IDs of all tokens held by `owner`.
+		fn tokens_of(owner: AccountId) -> Vec<(ClassId, TokenId)>;
+
+		/// Returns the IDs of all classes owned by `owner`.
+		fn classes_of(owner: AccountId) -> Vec<ClassId>;
+
+		/// Returns the number of tokens in a class, or `None` if the class does not exist.
+		fn class_token_count(class_id: ClassId) -> Option<TokenId>;
+
+		/// Returns up to `limit` tokens of a class in ascending ID order, starting at `start`.
+		#[changed_in(4)]
+		fn tokens(
+			class_id: ClassId,
+			start: TokenId,
+			limit: u32,
+		) -> Vec<(TokenId, TokenDetails<AccountId, TokenData>)>;
+
+		/// Returns up to `limit` tokens of a class in ascending ID order, starting at `start`,
+		/// after scanning at most `limit * TOKEN_SCAN_FACTOR` token IDs.
+		fn tokens(
+			class_id: ClassId,
+			start: TokenId,
+			limit: u32,
+		) -> TokenPage<AccountId, TokenId, TokenData>;
+
+		/// Returns the metadata of a class parsed according to the Chiba Studio metadata
+		/// standard, or `None` if the class does not exist.
+		fn class_metadata(class_id: ClassId) -> Option<Result<Metadata, MetadataError>>;
+
+		/// Returns the metadata of a token parsed according to the Chiba Studio metadata
+		/// standard, or `None` if the token does not exist.
+		fn token_metadata(
+			class_id: ClassId,
+			token_id: TokenId,
+		) -> Option<Result<Metadata, MetadataError>>;
+
+		/// Returns the current user of a token, or `None` if the token does not exist.
+		fn token_user(
+			class_id: ClassId,
+			token_id: TokenId,
+		) -> Option<TokenUser<AccountId, NumberFor<Block>>>;
+	}
+}
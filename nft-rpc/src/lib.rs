@@ -0,0 +1,412 @@
+//! RPC interface for the Chiba Studio NFT runtime API.
+//!
+//! Results are rendered as plain JSON, with owners as SS58 addresses and metadata both as
+//! hex and (when it is valid UTF-8) as text, so clients do not need a type registry.
+
+use std::sync::Arc;
+use codec::Codec;
+use jsonrpc_core::{Error as RpcError, ErrorCode, Result};
+use jsonrpc_derive::rpc;
+use serde::{Deserialize, Serialize};
+use sp_api::ProvideRuntimeApi;
+use sp_blockchain::HeaderBackend;
+use sp_core::Bytes;
+use sp_runtime::{generic::BlockId, traits::{Block as BlockT, NumberFor}};
+use chiba_nft_rpc_runtime_api::{
+	ClassDetails, Metadata as StandardMetadata, MetadataError, TokenDetails, TokenPage, TokenUser,
+};
+
+pub use chiba_nft_rpc_runtime_api::ChibaNftApi as ChibaNftRuntimeApi;
+
+/// Raw metadata bytes along with their text form, if they are valid UTF-8.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Metadata {
+	pub hex: Bytes,
+	pub text: Option<String>,
+}
+
+impl From<Vec<u8>> for Metadata {
+	fn from(bytes: Vec<u8>) -> Self {
+		Metadata {
+			text: String::from_utf8(bytes.clone()).ok(),
+			hex: bytes.into(),
+		}
+	}
+}
+
+/// Metadata parsed by the runtime according to the Chiba Studio metadata standard.
+///
+/// Metadata that does not follow the standard is reported as invalid, with the reason, rather
+/// than shown partially.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", tag = "status")]
+pub enum RpcMetadata {
+	#[serde(rename_all = "camelCase")]
+	Valid {
+		name: String,
+		description: String,
+		/// The IPFS CID of the media.
+		media: String,
+		mime_type: String,
+		attributes: Vec<RpcAttribute>,
+	},
+	Invalid {
+		reason: String,
+	},
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct RpcAttribute {
+	pub key: String,
+	pub value: String,
+}
+
+impl From<Result<StandardMetadata, MetadataError>> for RpcMetadata {
+	fn from(metadata: Result<StandardMetadata, MetadataError>) -> Self {
+		// The runtime has checked that all text is UTF-8.
+		let text = |bytes: Vec<u8>| String::from_utf8_lossy(&bytes).into_owned();
+		match metadata {
+			Ok(metadata) => RpcMetadata::Valid {
+				name: text(metadata.name),
+				description: text(metadata.description),
+				media: text(metadata.media),
+				mime_type: text(metadata.mime_type),
+				attributes: metadata.attributes.into_iter()
+					.map(|attribute| RpcAttribute {
+						key: text(attribute.key),
+						value: text(attribute.value),
+					})
+					.collect(),
+			},
+			Err(e) => RpcMetadata::Invalid { reason: e.as_str().into() },
+		}
+	}
+}
+
+/// An NFT class as returned over RPC.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcClass<AccountId, ClassId, TokenId, ClassData> {
+	pub class_id: ClassId,
+	pub owner: AccountId,
+	pub metadata: Metadata,
+	pub total_issuance: TokenId,
+	pub data: ClassData,
+}
+
+impl<AccountId, ClassId, TokenId, ClassData> RpcClass<AccountId, ClassId, TokenId, ClassData> {
+	fn new(class_id: ClassId, class: ClassDetails<AccountId, TokenId, ClassData>) -> Self {
+		RpcClass {
+			class_id,
+			owner: class.owner,
+			metadata: class.metadata.into(),
+			total_issuance: class.total_issuance,
+			data: class.data,
+		}
+	}
+}
+
+/// An NFT as returned over RPC.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcToken<AccountId, ClassId, TokenId, TokenData> {
+	pub class_id: ClassId,
+	pub token_id: TokenId,
+	pub owner: AccountId,
+	pub metadata: Metadata,
+	pub data: TokenData,
+}
+
+impl<AccountId, ClassId, TokenId, TokenData> RpcToken<AccountId, ClassId, TokenId, TokenData> {
+	fn new(class_id: ClassId, token_id: TokenId, token: TokenDetails<AccountId, TokenData>) -> Self {
+		RpcToken {
+			class_id,
+			token_id,
+			owner: token.owner,
+			metadata: token.metadata.into(),
+			data: token.data,
+		}
+	}
+}
+
+/// A page of the NFTs in a class as returned over RPC.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcTokenPage<AccountId, ClassId, TokenId, TokenData> {
+	pub tokens: Vec<RpcToken<AccountId, ClassId, TokenId, TokenData>>,
+	/// The token ID to pass as `start` for the next page, or `null` after the last page.
+	pub next: Option<TokenId>,
+}
+
+impl<AccountId, ClassId: Clone, TokenId, TokenData> RpcTokenPage<AccountId, ClassId, TokenId, TokenData> {
+	fn new(class_id: ClassId, page: TokenPage<AccountId, TokenId, TokenData>) -> Self {
+		RpcTokenPage {
+			tokens: page.tokens.into_iter()
+				.map(|(token_id, token)| RpcToken::new(class_id.clone(), token_id, token))
+				.collect(),
+			next: page.next,
+		}
+	}
+}
+
+/// The current user of an NFT as returned over RPC.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcTokenUser<AccountId, BlockNumber> {
+	pub user: AccountId,
+	pub owner: AccountId,
+	/// The block in which the rental expires, or `null` if the token is not rented.
+	pub expires: Option<BlockNumber>,
+}
+
+impl<AccountId, BlockNumber> From<TokenUser<AccountId, BlockNumber>> for RpcTokenUser<AccountId, BlockNumber> {
+	fn from(user: TokenUser<AccountId, BlockNumber>) -> Self {
+		RpcTokenUser { user: user.user, owner: user.owner, expires: user.expires }
+	}
+}
+
+#[rpc]
+pub trait ChibaNftApi<BlockHash, BlockNumber, AccountId, ClassId, TokenId, ClassData, TokenData> {
+	/// Returns a single class.
+	#[rpc(name = "chiba_getClass")]
+	fn class(
+		&self,
+		class_id: ClassId,
+		at: Option<BlockHash>,
+	) -> Result<Option<RpcClass<AccountId, ClassId, TokenId, ClassData>>>;
+
+	/// Returns a single token.
+	#[rpc(name = "chiba_getToken")]
+	fn token(
+		&self,
+		class_id: ClassId,
+		token_id: TokenId,
+		at: Option<BlockHash>,
+	) -> Result<Option<RpcToken<AccountId, ClassId, TokenId, TokenData>>>;
+
+	/// Returns a page of the tokens in a class, in ascending token ID order, with the token ID
+	/// the next page starts at.
+	#[rpc(name = "chiba_getTokens")]
+	fn tokens(
+		&self,
+		class_id: ClassId,
+		start: TokenId,
+		limit: u32,
+		at: Option<BlockHash>,
+	) -> Result<RpcTokenPage<AccountId, ClassId, TokenId, TokenData>>;
+
+	/// Returns all tokens held by an account.
+	#[rpc(name = "chiba_tokensOf")]
+	fn tokens_of(
+		&self,
+		owner: AccountId,
+		at: Option<BlockHash>,
+	) -> Result<Vec<RpcToken<AccountId, ClassId, TokenId, TokenData>>>;
+
+	/// Returns all classes owned by an account.
+	#[rpc(name = "chiba_classesOf")]
+	fn classes_of(
+		&self,
+		owner: AccountId,
+		at: Option<BlockHash>,
+	) -> Result<Vec<RpcClass<AccountId, ClassId, TokenId, ClassData>>>;
+
+	/// Returns the standard metadata of a class.
+	#[rpc(name = "chiba_getClassMetadata")]
+	fn class_metadata(&self, class_id: ClassId, at: Option<BlockHash>) -> Result<Option<RpcMetadata>>;
+
+	/// Returns the standard metadata of a token.
+	#[rpc(name = "chiba_getTokenMetadata")]
+	fn token_metadata(
+		&self,
+		class_id: ClassId,
+		token_id: TokenId,
+		at: Option<BlockHash>,
+	) -> Result<Option<RpcMetadata>>;
+
+	/// Returns the account currently allowed to use a token, which is its renter while it is
+	/// rented.
+	#[rpc(name = "chiba_getTokenUser")]
+	fn token_user(
+		&self,
+		class_id: ClassId,
+		token_id: TokenId,
+		at: Option<BlockHash>,
+	) -> Result<Option<RpcTokenUser<AccountId, BlockNumber>>>;
+}
+
+/// The maximum number of tokens returned by a single `chiba_getTokens` call.
+pub const MAX_PAGE_SIZE: u32 = 1000;
+
+/// A struct that implements the `ChibaNftApi`.
+pub struct ChibaNft<C, B> {
+	client: Arc<C>,
+	_marker: std::marker::PhantomData<B>,
+}
+
+impl<C, B> ChibaNft<C, B> {
+	/// Create new `ChibaNft` with the given reference to the client.
+	pub fn new(client: Arc<C>) -> Self {
+		ChibaNft { client, _marker: Default::default() }
+	}
+}
+
+/// Error type of this RPC api.
+pub enum Error {
+	/// The call to runtime failed.
+	RuntimeError,
+	/// The request was rejected before reaching the runtime.
+	InvalidParams,
+}
+
+impl From<Error> for i64 {
+	fn from(e: Error) -> i64 {
+		match e {
+			Error::RuntimeError => 1,
+			Error::InvalidParams => 2,
+		}
+	}
+}
+
+fn runtime_error(e: impl std::fmt::Debug) -> RpcError {
+	RpcError {
+		code: ErrorCode::ServerError(Error::RuntimeError.into()),
+		message: "Unable to query NFT state.".into(),
+		data: Some(format!("{:?}", e).into()),
+	}
+}
+
+impl<C, Block, AccountId, ClassId, TokenId, ClassData, TokenData>
+	ChibaNftApi<<Block as BlockT>::Hash, NumberFor<Block>, AccountId, ClassId, TokenId, ClassData, TokenData>
+	for ChibaNft<C, Block>
+where
+	Block: BlockT,
+	C: Send + Sync + 'static + ProvideRuntimeApi<Block> + HeaderBackend<Block>,
+	C::Api: ChibaNftRuntimeApi<Block, AccountId, ClassId, TokenId, ClassData, TokenData>,
+	AccountId: Codec + Clone,
+	ClassId: Codec + Clone,
+	TokenId: Codec + Clone,
+	ClassData: Codec,
+	TokenData: Codec,
+{
+	fn class(
+		&self,
+		class_id: ClassId,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> Result<Option<RpcClass<AccountId, ClassId, TokenId, ClassData>>> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+
+		let class = api.class(&at, class_id.clone()).map_err(runtime_error)?;
+		Ok(class.map(|class| RpcClass::new(class_id, class)))
+	}
+
+	fn token(
+		&self,
+		class_id: ClassId,
+		token_id: TokenId,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> Result<Option<RpcToken<AccountId, ClassId, TokenId, TokenData>>> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+
+		let token = api.token(&at, class_id.clone(), token_id.clone()).map_err(runtime_error)?;
+		Ok(token.map(|token| RpcToken::new(class_id, token_id, token)))
+	}
+
+	fn tokens(
+		&self,
+		class_id: ClassId,
+		start: TokenId,
+		limit: u32,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> Result<RpcTokenPage<AccountId, ClassId, TokenId, TokenData>> {
+		if limit == 0 || limit > MAX_PAGE_SIZE {
+			return Err(RpcError {
+				code: ErrorCode::ServerError(Error::InvalidParams.into()),
+				message: format!("Page size must be between 1 and {}.", MAX_PAGE_SIZE),
+				data: None,
+			})
+		}
+
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+
+		let page = api.tokens(&at, class_id.clone(), start, limit).map_err(runtime_error)?;
+		Ok(RpcTokenPage::new(class_id, page))
+	}
+
+	fn tokens_of(
+		&self,
+		owner: AccountId,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> Result<Vec<RpcToken<AccountId, ClassId, TokenId, TokenData>>> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+
+		let mut tokens = Vec::new();
+		for (class_id, token_id) in api.tokens_of(&at, owner).map_err(runtime_error)? {
+			let token = api.token(&at, class_id.clone(), token_id.clone()).map_err(runtime_error)?;
+			if let Some(token) = token {
+				tokens.push(RpcToken::new(class_id, token_id, token));
+			}
+		}
+		Ok(tokens)
+	}
+
+	fn classes_of(
+		&self,
+		owner: AccountId,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> Result<Vec<RpcClass<AccountId, ClassId, TokenId, ClassData>>> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+
+		let mut classes = Vec::new();
+		for class_id in api.classes_of(&at, owner).map_err(runtime_error)? {
+			if let Some(class) = api.class(&at, class_id.clone()).map_err(runtime_error)? {
+				classes.push(RpcClass::new(class_id, class));
+			}
+		}
+		Ok(classes)
+	}
+
+	fn class_metadata(
+		&self,
+		class_id: ClassId,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> Result<Option<RpcMetadata>> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+
+		let metadata = api.class_metadata(&at, class_id).map_err(runtime_error)?;
+		Ok(metadata.map(Into::into))
+	}
+
+	fn token_metadata(
+		&self,
+		class_id: ClassId,
+		token_id: TokenId,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> Result<Option<RpcMetadata>> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+
+		let metadata = api.token_metadata(&at, class_id, token_id).map_err(runtime_error)?;
+		Ok(metadata.map(Into::into))
+	}
+
+	fn token_user(
+		&self,
+		class_id: ClassId,
+		token_id: TokenId,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> Result<Option<RpcTokenUser<AccountId, NumberFor<Block>>>> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+
+		let user = api.token_user(&at, class_id, token_id).map_err(runtime_error)?;
+		Ok(user.map(Into::into))
+	}
+}
@@ -1,7 +1,14 @@
+use std::path::Path;
 use substrate_build_script_utils::{generate_cargo_keys, rerun_if_git_head_changed};
 
 fn main() {
 	generate_cargo_keys();
 
 	rerun_if_git_head_changed();
+
+	// Embed the Chiba Studio chain spec once it has been generated.
+	println!("cargo:rerun-if-changed=res/chiba.json");
+	if Path::new(&std::env::var("CARGO_MANIFEST_DIR").unwrap()).join("res/chiba.json").exists() {
+		println!("cargo:rustc-cfg=embedded_chiba_spec");
+	}
 }
@@ -1,18 +1,91 @@
-use sp_core::{Pair, Public, sr25519};
+use sp_core::{crypto::Ss58Codec, Pair, Public, sr25519};
 use chiba_runtime::{
-	AccountId, AuraConfig, BalancesConfig, GenesisConfig, GrandpaConfig,
-	SudoConfig, SystemConfig, WASM_BINARY, Signature
+	constants::currency::{TOKEN_DECIMALS, TOKEN_SYMBOL, UNITS},
+	opaque::SessionKeys, AccountId, AuraConfig, Balance, BalancesConfig, CouncilConfig,
+	DemocracyConfig, GenesisConfig, GenesisNftClass, GrandpaConfig, NftConfig, SessionConfig,
+	SystemConfig, TechnicalCommitteeConfig, ValidatorSetConfig, WASM_BINARY, Signature
 };
 use sp_consensus_aura::sr25519::AuthorityId as AuraId;
 use sp_finality_grandpa::AuthorityId as GrandpaId;
 use sp_runtime::traits::{Verify, IdentifyAccount};
-use sc_service::ChainType;
+use sc_service::{config::MultiaddrWithPeerId, ChainType, Properties};
+use sc_telemetry::TelemetryEndpoints;
+use sc_chain_spec::{ChainSpecExtension, ChainSpecGroup};
+use serde::{Deserialize, Serialize};
 
 // The URL for the telemetry server.
-// const STAGING_TELEMETRY_URL: &str = "wss://telemetry.polkadot.io/submit/";
+const STAGING_TELEMETRY_URL: &str = "wss://telemetry.polkadot.io/submit/";
+
+/// The public parameters of a long-lived network. Keys and accounts are SS58 encoded.
+struct NetworkKeys {
+	/// Validator accounts with their Aura and GRANDPA keys.
+	validators: &'static [(&'static str, &'static str, &'static str)],
+	/// Council and technical committee members.
+	governance: &'static [&'static str],
+	/// Pre-funded accounts.
+	balances: &'static [(&'static str, Balance)],
+	/// Boot node multiaddresses, including the peer ID.
+	boot_nodes: &'static [&'static str],
+}
+
+/// Keys of the staging testnet, operated by the Chiba Studio team.
+const STAGING_KEYS: NetworkKeys = NetworkKeys {
+	validators: &[],
+	governance: &[],
+	balances: &[],
+	boot_nodes: &[],
+};
+
+/// Keys of the Chiba Studio network at genesis.
+const CHIBA_KEYS: NetworkKeys = NetworkKeys {
+	validators: &[],
+	governance: &[],
+	balances: &[],
+	boot_nodes: &[],
+};
+
+/// Client-side consensus parameters, stored at the top level of the chain spec JSON.
+///
+/// Every field is optional in the JSON file and falls back to the value in `Default`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ChainSpecGroup, ChainSpecExtension)]
+#[serde(default, rename_all = "camelCase")]
+pub struct Extensions {
+	/// How often GRANDPA gossips votes, in milliseconds.
+	pub gossip_duration: u64,
+	/// How many blocks may pass between two GRANDPA justifications.
+	pub justification_period: u32,
+	/// The portion of an Aura slot that may be spent proposing a block.
+	pub block_proposal_slot_portion: f32,
+}
+
+impl Default for Extensions {
+	fn default() -> Self {
+		Self {
+			gossip_duration: 333,
+			justification_period: 512,
+			block_proposal_slot_portion: 2f32 / 3f32,
+		}
+	}
+}
+
+impl Extensions {
+	/// Check that the parameters can be used to run consensus.
+	pub fn validate(&self) -> Result<(), String> {
+		if self.gossip_duration == 0 {
+			return Err("`gossipDuration` must be greater than zero".into())
+		}
+		if self.justification_period == 0 {
+			return Err("`justificationPeriod` must be greater than zero".into())
+		}
+		if !(self.block_proposal_slot_portion > 0.0 && self.block_proposal_slot_portion <= 1.0) {
+			return Err("`blockProposalSlotPortion` must be greater than 0 and at most 1".into())
+		}
+		Ok(())
+	}
+}
 
 /// Specialized `ChainSpec`. This is a specialization of the general Substrate ChainSpec type.
-pub type ChainSpec = sc_service::GenericChainSpec<GenesisConfig>;
+pub type ChainSpec = sc_service::GenericChainSpec<GenesisConfig, Extensions>;
 
 /// Generate a crypto pair from seed.
 pub fn get_from_seed<TPublic: Public>(seed: &str) -> <TPublic::Pair as Pair>::Public {
@@ -30,14 +103,19 @@ pub fn get_account_id_from_seed<TPublic: Public>(seed: &str) -> AccountId where
 	AccountPublic::from(get_from_seed::<TPublic>(seed)).into_account()
 }
 
-/// Generate an Aura authority key.
-pub fn authority_keys_from_seed(s: &str) -> (AuraId, GrandpaId) {
+/// Generate a validator account together with its Aura and GRANDPA keys.
+pub fn authority_keys_from_seed(s: &str) -> (AccountId, AuraId, GrandpaId) {
 	(
+		get_account_id_from_seed::<sr25519::Public>(s),
 		get_from_seed::<AuraId>(s),
 		get_from_seed::<GrandpaId>(s),
 	)
 }
 
+fn session_keys(aura: AuraId, grandpa: GrandpaId) -> SessionKeys {
+	SessionKeys { aura, grandpa }
+}
+
 pub fn development_config() -> Result<ChainSpec, String> {
 	let wasm_binary = WASM_BINARY.ok_or_else(|| "Development wasm binary not available".to_string())?;
 
@@ -49,12 +127,14 @@ pub fn development_config() -> Result<ChainSpec, String> {
 		ChainType::Development,
 		move || testnet_genesis(
 			wasm_binary,
-			// Initial PoA authorities
+			// Initial validators
 			vec![
 				authority_keys_from_seed("Alice"),
 			],
-			// Sudo account
-			get_account_id_from_seed::<sr25519::Public>("Alice"),
+			// Council and technical committee members
+			vec![
+				get_account_id_from_seed::<sr25519::Public>("Alice"),
+			],
 			// Pre-funded accounts
 			vec![
 				get_account_id_from_seed::<sr25519::Public>("Alice"),
@@ -71,9 +151,9 @@ pub fn development_config() -> Result<ChainSpec, String> {
 		// Protocol ID
 		None,
 		// Properties
-		None,
+		Some(chiba_properties()),
 		// Extensions
-		None,
+		Extensions::default(),
 	))
 }
 
@@ -88,13 +168,17 @@ pub fn local_testnet_config() -> Result<ChainSpec, String> {
 		ChainType::Local,
 		move || testnet_genesis(
 			wasm_binary,
-			// Initial PoA authorities
+			// Initial validators
 			vec![
 				authority_keys_from_seed("Alice"),
 				authority_keys_from_seed("Bob"),
 			],
-			// Sudo account
-			get_account_id_from_seed::<sr25519::Public>("Alice"),
+			// Council and technical committee members
+			vec![
+				get_account_id_from_seed::<sr25519::Public>("Alice"),
+				get_account_id_from_seed::<sr25519::Public>("Bob"),
+				get_account_id_from_seed::<sr25519::Public>("Charlie"),
+			],
 			// Pre-funded accounts
 			vec![
 				get_account_id_from_seed::<sr25519::Public>("Alice"),
@@ -119,19 +203,130 @@ pub fn local_testnet_config() -> Result<ChainSpec, String> {
 		// Protocol ID
 		None,
 		// Properties
-		None,
+		Some(chiba_properties()),
 		// Extensions
-		None,
+		Extensions::default(),
+	))
+}
+
+/// The staging testnet, built from the keys in `STAGING_KEYS`.
+pub fn staging_testnet_config() -> Result<ChainSpec, String> {
+	network_config("Chiba Staging", "chiba_staging", ChainType::Live, "chiba-staging", &STAGING_KEYS)
+}
+
+/// The Chiba Studio network, built from the keys in `CHIBA_KEYS`.
+///
+/// Nodes join the network with `chiba_config`; this is only used to regenerate
+/// `res/chiba.json`.
+pub fn chiba_genesis_config() -> Result<ChainSpec, String> {
+	network_config("Chiba Studio", "chiba", ChainType::Live, "chiba", &CHIBA_KEYS)
+}
+
+/// The Chiba Studio network, from the raw chain spec embedded at build time.
+pub fn chiba_config() -> Result<ChainSpec, String> {
+	#[cfg(embedded_chiba_spec)]
+	return ChainSpec::from_json_bytes(&include_bytes!("../res/chiba.json")[..]);
+
+	#[cfg(not(embedded_chiba_spec))]
+	Err("This binary was built without `res/chiba.json`; pass the chain spec file to --chain".into())
+}
+
+/// Token properties shown by wallets and explorers.
+pub fn chiba_properties() -> Properties {
+	let mut properties = Properties::new();
+	properties.insert("tokenSymbol".into(), TOKEN_SYMBOL.into());
+	properties.insert("tokenDecimals".into(), TOKEN_DECIMALS.into());
+	properties.insert("ss58Format".into(), chiba_runtime::SS58Prefix::get().into());
+	properties
+}
+
+fn network_config(
+	name: &str,
+	id: &str,
+	chain_type: ChainType,
+	protocol_id: &str,
+	keys: &NetworkKeys,
+) -> Result<ChainSpec, String> {
+	let wasm_binary = WASM_BINARY.ok_or_else(|| format!("{} wasm binary not available", name))?;
+
+	fn parse<T: Ss58Codec>(what: &str, s: &str) -> Result<T, String> {
+		T::from_ss58check(s).map_err(|e| format!("Invalid {} {}: {:?}", what, s, e))
+	}
+
+	let validators = keys.validators.iter()
+		.map(|(account, aura, grandpa)| Ok((
+			parse::<AccountId>("validator account", account)?,
+			parse::<AuraId>("Aura key", aura)?,
+			parse::<GrandpaId>("GRANDPA key", grandpa)?,
+		)))
+		.collect::<Result<Vec<_>, String>>()?;
+	let governance = keys.governance.iter()
+		.map(|account| parse::<AccountId>("governance member", account))
+		.collect::<Result<Vec<_>, String>>()?;
+	let balances = keys.balances.iter()
+		.map(|(account, balance)| Ok((parse::<AccountId>("account", account)?, *balance)))
+		.collect::<Result<Vec<_>, String>>()?;
+	let boot_nodes = keys.boot_nodes.iter()
+		.map(|addr| addr.parse::<MultiaddrWithPeerId>()
+			.map_err(|e| format!("Invalid boot node {}: {}", addr, e)))
+		.collect::<Result<Vec<_>, String>>()?;
+
+	// The keys are handed over by the network operators and filled in for a release.
+	if validators.is_empty() {
+		return Err(format!("The {} chain spec has no validator keys yet", name))
+	}
+	if governance.is_empty() {
+		return Err(format!("The {} chain spec has no governance members yet", name))
+	}
+	crate::network_spec::ensure_endowed(&validators, &balances)?;
+
+	let telemetry = TelemetryEndpoints::new(vec![(STAGING_TELEMETRY_URL.to_string(), 0)])
+		.map_err(|e| format!("Invalid telemetry endpoint: {:?}", e))?;
+
+	Ok(ChainSpec::from_genesis(
+		name,
+		id,
+		chain_type,
+		move || genesis(
+			wasm_binary,
+			validators.clone(),
+			governance.clone(),
+			balances.clone(),
+			vec![],
+		),
+		boot_nodes,
+		Some(telemetry),
+		Some(protocol_id),
+		Some(chiba_properties()),
+		Extensions::default(),
 	))
 }
 
 /// Configure initial storage state for FRAME modules.
 fn testnet_genesis(
 	wasm_binary: &[u8],
-	initial_authorities: Vec<(AuraId, GrandpaId)>,
-	root_key: AccountId,
+	initial_authorities: Vec<(AccountId, AuraId, GrandpaId)>,
+	governance_members: Vec<AccountId>,
 	endowed_accounts: Vec<AccountId>,
 	_enable_println: bool,
+) -> GenesisConfig {
+	genesis(
+		wasm_binary,
+		initial_authorities,
+		governance_members,
+		// Configure endowed accounts with an initial balance of one million units.
+		endowed_accounts.into_iter().map(|k| (k, 1_000_000 * UNITS)).collect(),
+		vec![],
+	)
+}
+
+/// Build the genesis of a network from its validators, governance, balances and NFTs.
+pub(crate) fn genesis(
+	wasm_binary: &[u8],
+	initial_authorities: Vec<(AccountId, AuraId, GrandpaId)>,
+	governance_members: Vec<AccountId>,
+	balances: Vec<(AccountId, Balance)>,
+	nft_classes: Vec<GenesisNftClass>,
 ) -> GenesisConfig {
 	GenesisConfig {
 		frame_system: SystemConfig {
@@ -140,18 +335,72 @@ fn testnet_genesis(
 			changes_trie_config: Default::default(),
 		},
 		pallet_balances: BalancesConfig {
-			// Configure endowed accounts with initial balance of 1 << 60.
-			balances: endowed_accounts.iter().cloned().map(|k|(k, 1 << 60)).collect(),
+			balances,
 		},
+		pallet_validator_set: ValidatorSetConfig {
+			validators: initial_authorities.iter().map(|x| x.0.clone()).collect(),
+		},
+		pallet_session: SessionConfig {
+			keys: initial_authorities.iter().map(|x| {
+				(x.0.clone(), x.0.clone(), session_keys(x.1.clone(), x.2.clone()))
+			}).collect(),
+		},
+		// Aura and GRANDPA authorities are set by `pallet_session` from the session keys.
 		pallet_aura: AuraConfig {
-			authorities: initial_authorities.iter().map(|x| (x.0.clone())).collect(),
+			authorities: vec![],
 		},
 		pallet_grandpa: GrandpaConfig {
-			authorities: initial_authorities.iter().map(|x| (x.1.clone(), 1)).collect(),
+			authorities: vec![],
+		},
+		pallet_collective_Instance1: CouncilConfig {
+			members: governance_members.clone(),
+			phantom: Default::default(),
+		},
+		pallet_collective_Instance2: TechnicalCommitteeConfig {
+			members: governance_members,
+			phantom: Default::default(),
 		},
-		pallet_sudo: SudoConfig {
-			// Assign network admin rights.
-			key: root_key,
+		pallet_democracy: DemocracyConfig::default(),
+		pallet_treasury: Default::default(),
+		// Classes get IDs in the order they are listed, starting from 0, and so do the tokens
+		// within each class.
+		orml_nft: NftConfig {
+			tokens: nft_classes,
 		},
+		pallet_asset_tx_payment: Default::default(),
+		pallet_storage_versions: Default::default(),
 	}
 }
+
+/// Check that the NFT classes and tokens to be minted at genesis have metadata following
+/// `chiba_nft_metadata`, as the runtime requires of classes and tokens created later.
+pub fn validate_nft_genesis(classes: &[GenesisNftClass]) -> Result<(), String> {
+	for (class_id, (_, metadata, _, tokens)) in classes.iter().enumerate() {
+		chiba_nft_metadata::parse(metadata).map_err(|e| {
+			format!("NFT class {} has invalid metadata: {}", class_id, e.as_str())
+		})?;
+		for (token_id, (_, metadata, _)) in tokens.iter().enumerate() {
+			chiba_nft_metadata::parse(metadata).map_err(|e| format!(
+				"Token {} of NFT class {} has invalid metadata: {}",
+				token_id, class_id, e.as_str(),
+			))?;
+		}
+	}
+	Ok(())
+}
+
+/// Check the genesis of a chain spec before it is built or distributed.
+///
+/// Raw chain specs only carry storage, so there is nothing left to check in them.
+pub fn validate_genesis(spec: &dyn sc_service::ChainSpec) -> Result<(), String> {
+	let json: serde_json::Value = serde_json::from_str(&spec.as_json(false)?)
+		.map_err(|e| format!("Invalid chain spec: {}", e))?;
+
+	if let Some(nft) = json.pointer("/genesis/runtime/ormlNft") {
+		let config: NftConfig = serde_json::from_value(nft.clone())
+			.map_err(|e| format!("Invalid NFT genesis: {}", e))?;
+		validate_nft_genesis(&config.tokens)?;
+	}
+
+	Ok(())
+}
@@ -1,6 +1,37 @@
+use std::str::FromStr;
 use structopt::StructOpt;
 use sc_cli::RunCmd;
 
+/// How blocks are authored when the node is not running Aura + GRANDPA.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Sealing {
+	/// Seal a block as soon as a transaction enters the pool.
+	Instant,
+	/// Seal blocks only when requested through the `engine_*` RPCs.
+	Manual,
+	/// Seal a block every given number of milliseconds.
+	Interval(u64),
+}
+
+impl FromStr for Sealing {
+	type Err = String;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		Ok(match s {
+			"instant" => Self::Instant,
+			"manual" => Self::Manual,
+			millis => {
+				let millis = u64::from_str(millis)
+					.map_err(|_| format!("Unknown sealing mode: {}", s))?;
+				if millis == 0 {
+					return Err("Sealing interval must be greater than zero".into())
+				}
+				Self::Interval(millis)
+			},
+		})
+	}
+}
+
 #[derive(Debug, StructOpt)]
 pub struct Cli {
 	#[structopt(subcommand)]
@@ -8,6 +39,21 @@ pub struct Cli {
 
 	#[structopt(flatten)]
 	pub run: RunCmd,
+
+	/// Replace Aura and GRANDPA with a development sealing engine.
+	///
+	/// Accepts `instant`, `manual` or a block interval in milliseconds. Only allowed on
+	/// development chains, and not with `--light`. The `engine_*` RPCs are unsafe, and are
+	/// only served with `--rpc-methods unsafe` or on local interfaces.
+	#[structopt(long = "sealing")]
+	pub sealing: Option<Sealing>,
+
+	/// The IPFS HTTP gateway the off-chain worker fetches NFT media from.
+	///
+	/// Defaults to a local IPFS daemon at `http://127.0.0.1:8080/ipfs/`. The URL is kept in
+	/// off-chain storage, so it persists across restarts until set again.
+	#[structopt(long = "media-gateway", value_name = "URL")]
+	pub media_gateway: Option<String>,
 }
 
 #[derive(Debug, StructOpt)]
@@ -17,6 +63,10 @@ pub enum Subcommand {
 	/// Build a chain specification.
 	BuildSpec(sc_cli::BuildSpecCmd),
 
+	/// Generate a chain specification from a network description file.
+	#[structopt(name = "generate-spec")]
+	GenerateSpec(crate::network_spec::GenerateSpecCmd),
+
 	/// Validate blocks.
 	CheckBlock(sc_cli::CheckBlockCmd),
 
@@ -38,4 +88,8 @@ pub enum Subcommand {
 	/// The custom benchmark subcommmand benchmarking runtime pallets.
 	#[structopt(name = "benchmark", about = "Benchmark runtime pallets.")]
 	Benchmark(frame_benchmarking_cli::BenchmarkCmd),
+
+	/// Replay runtime upgrade migrations against a state snapshot and run their checks.
+	#[cfg(feature = "try-runtime")]
+	TryRuntime(try_runtime_cli::TryRuntimeCmd),
 }
@@ -1,3 +1,6 @@
 pub mod chain_spec;
+pub mod cli;
+pub mod network_spec;
 pub mod service;
 pub mod rpc;
+pub mod sealing;
@@ -6,7 +6,9 @@ mod chain_spec;
 mod service;
 mod cli;
 mod command;
+mod network_spec;
 mod rpc;
+mod sealing;
 
 fn main() -> sc_cli::Result<()> {
 	command::run()
@@ -0,0 +1,538 @@
+//! The `generate-spec` subcommand, which builds a chain spec from a network description file.
+//!
+//! The description is written in TOML, or in JSON if the file name ends in `.json`. See
+//! `doc/networks.md` for the format.
+
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use codec::Encode;
+use serde::Deserialize;
+use structopt::StructOpt;
+use sc_service::{config::MultiaddrWithPeerId, ChainType, Properties};
+use sc_telemetry::TelemetryEndpoints;
+use sp_consensus_aura::sr25519::AuthorityId as AuraId;
+use sp_core::crypto::Ss58Codec;
+use sp_finality_grandpa::AuthorityId as GrandpaId;
+use chiba_runtime::{AccountId, Balance, GenesisNftClass, WASM_BINARY};
+use crate::chain_spec::{self, ChainSpec, Extensions};
+
+/// The `generate-spec` command.
+#[derive(Debug, StructOpt)]
+pub struct GenerateSpecCmd {
+	/// The network description, in TOML or JSON.
+	#[structopt(long, parse(from_os_str))]
+	pub config: PathBuf,
+
+	/// Output the genesis as raw storage instead of runtime configuration.
+	#[structopt(long)]
+	pub raw: bool,
+
+	/// Write the chain spec to this file instead of standard output.
+	#[structopt(long, short, parse(from_os_str))]
+	pub output: Option<PathBuf>,
+}
+
+impl GenerateSpecCmd {
+	/// Run the command.
+	pub fn run(&self) -> sc_cli::Result<()> {
+		let network = NetworkDescription::load(&self.config)?;
+		let spec = network.into_chain_spec()?;
+		let json = sc_service::ChainSpec::as_json(&spec, self.raw)?;
+
+		match &self.output {
+			Some(path) => fs::write(path, json)?,
+			None => println!("{}", json),
+		}
+		Ok(())
+	}
+}
+
+/// A human-written description of a network.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct NetworkDescription {
+	name: String,
+	id: String,
+	chain_type: ChainType,
+	protocol_id: Option<String>,
+	#[serde(default)]
+	boot_nodes: Vec<String>,
+	#[serde(default)]
+	telemetry: Vec<String>,
+	/// Merged over the default token properties.
+	#[serde(default)]
+	properties: Properties,
+	#[serde(default)]
+	consensus: Extensions,
+	validators: Vec<ValidatorDescription>,
+	/// Council and technical committee members.
+	governance: Vec<String>,
+	#[serde(default)]
+	balances: Vec<BalanceDescription>,
+	#[serde(default)]
+	nft_classes: Vec<NftClassDescription>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ValidatorDescription {
+	account: String,
+	aura: String,
+	grandpa: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct BalanceDescription {
+	account: String,
+	amount: Amount,
+}
+
+/// TOML integers are at most 64 bits, so larger balances are written as strings.
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum Amount {
+	Integer(u64),
+	Text(String),
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct NftClassDescription {
+	owner: String,
+	metadata: MetadataDescription,
+	data: chiba_runtime::ClassData,
+	#[serde(default)]
+	tokens: Vec<NftTokenDescription>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct NftTokenDescription {
+	owner: String,
+	metadata: MetadataDescription,
+	data: chiba_runtime::TokenData,
+}
+
+/// NFT metadata following `chiba_nft_metadata`, either already encoded as a `0x`-prefixed hex
+/// string or written out field by field.
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum MetadataDescription {
+	Encoded(String),
+	Fields(MetadataFields),
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct MetadataFields {
+	name: String,
+	#[serde(default)]
+	description: String,
+	media: String,
+	mime_type: String,
+	#[serde(default)]
+	attributes: Vec<AttributeDescription>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct AttributeDescription {
+	key: String,
+	value: String,
+}
+
+impl NetworkDescription {
+	fn load(path: &Path) -> Result<Self, String> {
+		let contents = fs::read_to_string(path)
+			.map_err(|e| format!("Cannot read {}: {}", path.display(), e))?;
+
+		if path.extension().map_or(false, |ext| ext == "json") {
+			serde_json::from_str(&contents).map_err(|e| format!("Invalid network description: {}", e))
+		} else {
+			toml::from_str(&contents).map_err(|e| format!("Invalid network description: {}", e))
+		}
+	}
+
+	fn into_chain_spec(self) -> Result<ChainSpec, String> {
+		let mut properties = chain_spec::chiba_properties();
+		properties.extend(self.properties);
+		let ss58_format = properties.get("ss58Format")
+			.and_then(|f| f.as_u64())
+			.ok_or_else(|| "`ss58Format` property must be a number".to_string())?;
+		let parse = Parser { ss58_format };
+
+		self.consensus.validate()?;
+
+		let validators = self.validators.iter()
+			.map(|v| Ok((
+				parse.address::<AccountId>("validator account", &v.account)?,
+				parse.key::<AuraId>("Aura key", &v.aura)?,
+				parse.key::<GrandpaId>("GRANDPA key", &v.grandpa)?,
+			)))
+			.collect::<Result<Vec<_>, String>>()?;
+		if validators.is_empty() {
+			return Err("At least one validator is required".into())
+		}
+		ensure_unique("validator account", validators.iter().map(|v| v.0.to_ss58check()))?;
+		ensure_unique("Aura key", validators.iter().map(|v| v.1.to_ss58check()))?;
+		ensure_unique("GRANDPA key", validators.iter().map(|v| v.2.to_ss58check()))?;
+
+		let governance = self.governance.iter()
+			.map(|account| parse.address::<AccountId>("governance member", account))
+			.collect::<Result<Vec<_>, String>>()?;
+		if governance.is_empty() {
+			return Err("At least one governance member is required".into())
+		}
+		ensure_unique("governance member", self.governance.iter().cloned())?;
+
+		let balances = self.balances.iter()
+			.map(|b| Ok((parse.address::<AccountId>("account", &b.account)?, b.amount.to_balance()?)))
+			.collect::<Result<Vec<(AccountId, Balance)>, String>>()?;
+		ensure_unique("balance account", self.balances.iter().map(|b| b.account.clone()))?;
+		ensure_endowed(&validators, &balances)?;
+
+		let nft_classes = self.nft_classes.into_iter()
+			.map(|class| {
+				let tokens = class.tokens.into_iter()
+					.map(|token| Ok((
+						parse.address::<AccountId>("token owner", &token.owner)?,
+						token.metadata.into_bytes()?,
+						token.data,
+					)))
+					.collect::<Result<Vec<_>, String>>()?;
+				Ok((
+					parse.address::<AccountId>("class owner", &class.owner)?,
+					class.metadata.into_bytes()?,
+					class.data,
+					tokens,
+				))
+			})
+			.collect::<Result<Vec<GenesisNftClass>, String>>()?;
+		chain_spec::validate_nft_genesis(&nft_classes)?;
+
+		let boot_nodes = self.boot_nodes.iter()
+			.map(|addr| addr.parse::<MultiaddrWithPeerId>()
+				.map_err(|e| format!("Invalid boot node {}: {}", addr, e)))
+			.collect::<Result<Vec<_>, String>>()?;
+
+		let telemetry = if self.telemetry.is_empty() {
+			None
+		} else {
+			let endpoints = self.telemetry.into_iter().map(|url| (url, 0)).collect();
+			Some(TelemetryEndpoints::new(endpoints)
+				.map_err(|e| format!("Invalid telemetry endpoint: {:?}", e))?)
+		};
+
+		let wasm_binary = WASM_BINARY.ok_or_else(|| "Wasm binary not available".to_string())?;
+		let protocol_id = self.protocol_id;
+		Ok(ChainSpec::from_genesis(
+			&self.name,
+			&self.id,
+			self.chain_type,
+			move || chain_spec::genesis(
+				wasm_binary,
+				validators.clone(),
+				governance.clone(),
+				balances.clone(),
+				nft_classes.clone(),
+			),
+			boot_nodes,
+			telemetry,
+			protocol_id.as_deref(),
+			Some(properties),
+			self.consensus,
+		))
+	}
+}
+
+impl Amount {
+	fn to_balance(&self) -> Result<Balance, String> {
+		match self {
+			Amount::Integer(amount) => Ok(*amount as Balance),
+			Amount::Text(amount) => amount.replace('_', "").parse()
+				.map_err(|_| format!("Invalid balance: {}", amount)),
+		}
+	}
+}
+
+impl MetadataDescription {
+	/// The metadata as stored by `orml_nft`. It is validated with the rest of the NFT genesis.
+	fn into_bytes(self) -> Result<Vec<u8>, String> {
+		match self {
+			MetadataDescription::Encoded(hex) => {
+				if !hex.starts_with("0x") {
+					return Err(format!("Metadata {} is not 0x-prefixed hex", hex))
+				}
+				sp_core::bytes::from_hex(&hex)
+					.map_err(|e| format!("Invalid metadata {}: {}", hex, e))
+			},
+			MetadataDescription::Fields(fields) => Ok(chiba_nft_metadata::Metadata {
+				name: fields.name.into_bytes(),
+				description: fields.description.into_bytes(),
+				media: fields.media.into_bytes(),
+				mime_type: fields.mime_type.into_bytes(),
+				attributes: fields.attributes.into_iter()
+					.map(|a| chiba_nft_metadata::Attribute {
+						key: a.key.into_bytes(),
+						value: a.value.into_bytes(),
+					})
+					.collect(),
+			}.encode()),
+		}
+	}
+}
+
+/// Decodes SS58 strings, checking that account addresses use the network's format.
+struct Parser {
+	ss58_format: u64,
+}
+
+impl Parser {
+	fn address<T: Ss58Codec>(&self, what: &str, s: &str) -> Result<T, String> {
+		let (value, format) = T::from_ss58check_with_version(s)
+			.map_err(|e| format!("Invalid {} {}: {:?}", what, s, e))?;
+		if u8::from(format) as u64 != self.ss58_format {
+			return Err(format!(
+				"{} {} uses SS58 format {}, expected {}",
+				what, s, u8::from(format), self.ss58_format,
+			))
+		}
+		Ok(value)
+	}
+
+	/// Session keys are never shown to users as addresses, so any SS58 format is accepted.
+	fn key<T: Ss58Codec>(&self, what: &str, s: &str) -> Result<T, String> {
+		T::from_ss58check(s).map_err(|e| format!("Invalid {} {}: {:?}", what, s, e))
+	}
+}
+
+fn ensure_unique(what: &str, items: impl Iterator<Item = String>) -> Result<(), String> {
+	let mut seen = BTreeSet::new();
+	for item in items {
+		if !seen.insert(item.clone()) {
+			return Err(format!("Duplicate {} {}", what, item))
+		}
+	}
+	Ok(())
+}
+
+/// Checks that every validator account exists at genesis: `pallet_session` cannot set the
+/// session keys of an account without a balance.
+pub(crate) fn ensure_endowed(
+	validators: &[(AccountId, AuraId, GrandpaId)],
+	balances: &[(AccountId, Balance)],
+) -> Result<(), String> {
+	let existential_deposit = chiba_runtime::ExistentialDeposit::get();
+	for (account, _, _) in validators {
+		let endowed = balances.iter()
+			.any(|(holder, amount)| holder == account && *amount >= existential_deposit);
+		if !endowed {
+			return Err(format!(
+				"Validator account {} needs a balance of at least the existential deposit",
+				account.to_ss58check(),
+			))
+		}
+	}
+	Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use sp_core::crypto::{set_default_ss58_version, Ss58AddressFormat};
+
+	/// Alice's and Bob's sr25519 and ed25519 keys in the Chiba SS58 format.
+	const ALICE: &str = "6mfqoTMHrMeVMyKwjqomUjVomPMJ4AjdCm1VReFtk7Be8wqr";
+	const ALICE_ED25519: &str = "6jy3fHLYSPD1Z7h6LzmLV3KZVCWXG1QMmbRks664DYHTgaMb";
+	const BOB: &str = "6k6gXPB9idebCxqSJuqpjPaqfYLQbdLHhvsANH8Dg8GQN3tT";
+	const BOB_ED25519: &str = "6mcGdYDZPy56bfTakmju5VC6rbXiW5pmN3hiAiLcA6bQuUig";
+	/// Alice's account in the generic Substrate SS58 format.
+	const ALICE_SUBSTRATE: &str = "5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY";
+
+	/// A description with Alice as the only validator and governance member, and `extra`
+	/// appended.
+	fn network(extra: &str) -> String {
+		format!(r#"
+name = "Chiba Test"
+id = "chiba_test"
+chain_type = "Local"
+governance = ["{alice}"]
+
+[[validators]]
+account = "{alice}"
+aura = "{alice}"
+grandpa = "{alice_ed25519}"
+
+[[balances]]
+account = "{alice}"
+amount = "1_000_000_000_000_000"
+{extra}"#, alice = ALICE, alice_ed25519 = ALICE_ED25519, extra = extra)
+	}
+
+	/// Session keys, like addresses, are written in the Chiba format, which `command` makes
+	/// the default.
+	fn use_chiba_format() {
+		set_default_ss58_version(Ss58AddressFormat::Custom(chiba_runtime::SS58Prefix::get()));
+	}
+
+	fn check(toml: &str) -> Result<(), String> {
+		use_chiba_format();
+		let network: NetworkDescription = toml::from_str(toml).map_err(|e| e.to_string())?;
+		network.into_chain_spec().map(|_| ())
+	}
+
+	fn metadata(toml: &str) -> Result<Vec<u8>, String> {
+		#[derive(Deserialize)]
+		struct Wrapper {
+			metadata: MetadataDescription,
+		}
+		let wrapper: Wrapper = toml::from_str(toml).map_err(|e| e.to_string())?;
+		wrapper.metadata.into_bytes()
+	}
+
+	fn sakura() -> chiba_nft_metadata::Metadata {
+		chiba_nft_metadata::Metadata {
+			name: b"Sakura #1".to_vec(),
+			description: vec![],
+			media: b"QmYwAPJzv5CZsnA625s3Xf2nemtYgPpHdWEz79ojWnPbdG".to_vec(),
+			mime_type: b"image/png".to_vec(),
+			attributes: vec![chiba_nft_metadata::Attribute {
+				key: b"season".to_vec(),
+				value: b"spring".to_vec(),
+			}],
+		}
+	}
+
+	#[test]
+	fn addresses_must_use_the_network_format() {
+		let toml = network("").replacen(
+			&format!("governance = [\"{}\"]", ALICE),
+			&format!("governance = [\"{}\"]", ALICE_SUBSTRATE),
+			1,
+		);
+		let error = check(&toml).unwrap_err();
+		assert!(error.contains("uses SS58 format 42, expected 57"), "{}", error);
+
+		let toml = network("[properties]\nss58Format = 42");
+		let error = check(&toml).unwrap_err();
+		assert!(error.contains("uses SS58 format 57, expected 42"), "{}", error);
+	}
+
+	#[test]
+	fn json_descriptions_are_checked_too() {
+		let json = format!(r#"{{
+			"name": "Chiba Test",
+			"id": "chiba_test",
+			"chain_type": "Local",
+			"governance": ["{alice}"],
+			"validators": [{{ "account": "{substrate}", "aura": "{alice}", "grandpa": "{ed25519}" }}]
+		}}"#, alice = ALICE, substrate = ALICE_SUBSTRATE, ed25519 = ALICE_ED25519);
+		use_chiba_format();
+		let network: NetworkDescription = serde_json::from_str(&json).unwrap();
+		let error = network.into_chain_spec().unwrap_err();
+		assert!(error.contains("validator account"), "{}", error);
+		assert!(error.contains("uses SS58 format 42, expected 57"), "{}", error);
+	}
+
+	#[test]
+	fn duplicates_are_rejected() {
+		let validator = |account: &str, aura: &str, grandpa: &str| format!(
+			"[[validators]]\naccount = \"{}\"\naura = \"{}\"\ngrandpa = \"{}\"\n",
+			account, aura, grandpa,
+		);
+		let cases = [
+			(validator(ALICE, BOB, BOB_ED25519), "Duplicate validator account"),
+			(validator(BOB, ALICE, BOB_ED25519), "Duplicate Aura key"),
+			(validator(BOB, BOB, ALICE_ED25519), "Duplicate GRANDPA key"),
+			(format!("[[balances]]\naccount = \"{}\"\namount = 1", ALICE), "Duplicate balance account"),
+		];
+		for (extra, expected) in cases.iter() {
+			let error = check(&network(extra)).unwrap_err();
+			assert!(error.starts_with(expected), "{}: {}", expected, error);
+		}
+
+		let toml = network("").replacen(
+			&format!("governance = [\"{}\"]", ALICE),
+			&format!("governance = [\"{}\", \"{}\"]", ALICE, ALICE),
+			1,
+		);
+		let error = check(&toml).unwrap_err();
+		assert!(error.starts_with("Duplicate governance member"), "{}", error);
+	}
+
+	#[test]
+	fn validators_must_be_endowed() {
+		let bob = format!(
+			"[[validators]]\naccount = \"{}\"\naura = \"{}\"\ngrandpa = \"{}\"\n",
+			BOB, BOB, BOB_ED25519,
+		);
+		let error = check(&network(&bob)).unwrap_err();
+		assert!(error.starts_with(&format!("Validator account {}", BOB)), "{}", error);
+
+		let dust = format!("{}[[balances]]\naccount = \"{}\"\namount = 0", bob, BOB);
+		let error = check(&network(&dust)).unwrap_err();
+		assert!(error.starts_with(&format!("Validator account {}", BOB)), "{}", error);
+
+		let validators = [chain_spec::authority_keys_from_seed("Alice")];
+		let alice = validators[0].0.clone();
+		let existential_deposit = chiba_runtime::ExistentialDeposit::get();
+		assert!(ensure_endowed(&validators, &[(alice.clone(), existential_deposit)]).is_ok());
+		assert!(ensure_endowed(&validators, &[(alice, existential_deposit - 1)]).is_err());
+	}
+
+	#[test]
+	fn amounts_may_contain_underscores() {
+		assert_eq!(Amount::Integer(1_000).to_balance(), Ok(1_000));
+		assert_eq!(Amount::Text("1_000_000".into()).to_balance(), Ok(1_000_000));
+		assert_eq!(
+			Amount::Text("340_282_366_920_938_463_463_374_607_431_768_211_455".into()).to_balance(),
+			Ok(Balance::max_value())
+		);
+		assert!(Amount::Text("1,000".into()).to_balance().is_err());
+		assert!(Amount::Text("-1".into()).to_balance().is_err());
+		assert!(Amount::Text("".into()).to_balance().is_err());
+	}
+
+	#[test]
+	fn unknown_fields_are_rejected() {
+		let cases = [
+			network("").replacen("name =", "validator = []\nname =", 1),
+			network("").replacen("aura =", "babe =", 1),
+			network("").replacen("amount =", "balance =", 1),
+		];
+		for toml in cases.iter() {
+			let error = check(toml).unwrap_err();
+			assert!(error.contains("unknown field"), "{}", error);
+		}
+	}
+
+	#[test]
+	fn metadata_may_be_hex() {
+		let encoded = sakura().encode();
+		let toml = format!("metadata = \"{}\"", sp_core::bytes::to_hex(&encoded, false));
+		assert_eq!(metadata(&toml), Ok(encoded.clone()));
+
+		let unprefixed = format!("metadata = \"{}\"", &sp_core::bytes::to_hex(&encoded, false)[2..]);
+		assert!(metadata(&unprefixed).unwrap_err().contains("0x-prefixed"));
+		assert!(metadata("metadata = \"0xzz\"").unwrap_err().starts_with("Invalid metadata"));
+	}
+
+	#[test]
+	fn metadata_may_be_fields() {
+		let toml = r#"
+[metadata]
+name = "Sakura #1"
+media = "QmYwAPJzv5CZsnA625s3Xf2nemtYgPpHdWEz79ojWnPbdG"
+mime_type = "image/png"
+attributes = [{ key = "season", value = "spring" }]
+"#;
+		assert_eq!(metadata(toml), Ok(sakura().encode()));
+
+		let typo = toml.replacen("mime_type", "mime", 1);
+		assert!(metadata(&typo).is_err());
+		let missing = toml.replacen("name = \"Sakura #1\"\n", "", 1);
+		assert!(metadata(&missing).is_err());
+	}
+}
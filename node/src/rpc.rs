@@ -7,12 +7,16 @@
 
 use std::sync::Arc;
 
-use chiba_runtime::{opaque::Block, AccountId, Balance, Index};
+use chiba_runtime::{
+	opaque::Block, AccountId, Balance, ClassData, ClassId, Hash, Index, TokenData, TokenId,
+};
 use sp_api::ProvideRuntimeApi;
 use sp_blockchain::{Error as BlockChainError, HeaderMetadata, HeaderBackend};
 use sp_block_builder::BlockBuilder;
 pub use sc_rpc_api::DenyUnsafe;
 use sp_transaction_pool::TransactionPool;
+use sc_consensus_manual_seal::EngineCommand;
+use futures::channel::mpsc;
 
 
 /// Full client dependencies.
@@ -23,6 +27,8 @@ pub struct FullDeps<C, P> {
 	pub pool: Arc<P>,
 	/// Whether to deny unsafe calls
 	pub deny_unsafe: DenyUnsafe,
+	/// Channel for sending commands to the manual-seal engine, if it is running.
+	pub command_sink: Option<mpsc::Sender<EngineCommand<Hash>>>,
 }
 
 /// Instantiate all full RPC extensions.
@@ -34,17 +40,21 @@ pub fn create_full<C, P>(
 	C: Send + Sync + 'static,
 	C::Api: substrate_frame_rpc_system::AccountNonceApi<Block, AccountId, Index>,
 	C::Api: pallet_transaction_payment_rpc::TransactionPaymentRuntimeApi<Block, Balance>,
+	C::Api: chiba_nft_rpc::ChibaNftRuntimeApi<Block, AccountId, ClassId, TokenId, ClassData, TokenData>,
 	C::Api: BlockBuilder<Block>,
 	P: TransactionPool + 'static,
 {
 	use substrate_frame_rpc_system::{FullSystem, SystemApi};
 	use pallet_transaction_payment_rpc::{TransactionPayment, TransactionPaymentApi};
+	use sc_consensus_manual_seal::rpc::{ManualSeal, ManualSealApi};
+	use chiba_nft_rpc::{ChibaNft, ChibaNftApi};
 
 	let mut io = jsonrpc_core::IoHandler::default();
 	let FullDeps {
 		client,
 		pool,
 		deny_unsafe,
+		command_sink,
 	} = deps;
 
 	io.extend_with(
@@ -55,10 +65,19 @@ pub fn create_full<C, P>(
 		TransactionPaymentApi::to_delegate(TransactionPayment::new(client.clone()))
 	);
 
-	// Extend this RPC with a custom API by using the following syntax.
-	// `YourRpcStruct` should have a reference to a client, which is needed
-	// to call into the runtime.
-	// `io.extend_with(YourRpcTrait::to_delegate(YourRpcStruct::new(ReferenceToClient, ...)));`
+	io.extend_with(
+		ChibaNftApi::to_delegate(ChibaNft::new(client.clone()))
+	);
+
+	// Anyone who can call `engine_*` can seal and finalize blocks, so the RPCs are only served
+	// where unsafe RPCs are allowed.
+	if let (Some(command_sink), Ok(())) = (command_sink, deny_unsafe.check_if_safe()) {
+		io.extend_with(
+			// We provide the rpc handler with the sending end of the channel to allow the rpc
+			// send EngineCommands to the background block authorship task.
+			ManualSealApi::to_delegate(ManualSeal::new(command_sink))
+		);
+	}
 
 	io
 }
@@ -0,0 +1,88 @@
+//! Helpers for running the node with a manual-seal engine instead of Aura.
+//!
+//! The runtime still contains `pallet_aura`, which expects every block to carry an Aura
+//! pre-runtime digest and a timestamp that falls into a strictly increasing slot. The
+//! types in this module provide both so that sealed blocks import cleanly.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{SystemTime, UNIX_EPOCH};
+use codec::Encode;
+use chiba_runtime::{opaque::Block, SLOT_DURATION};
+use sc_consensus_manual_seal::{ConsensusDataProvider, Error};
+use sp_consensus::BlockImportParams;
+use sp_consensus_aura::AURA_ENGINE_ID;
+use sp_inherents::{InherentData, InherentIdentifier, ProvideInherentData};
+use sp_runtime::{generic::Digest, traits::{Block as BlockT, DigestFor}, DigestItem};
+use sp_timestamp::{InherentError, INHERENT_IDENTIFIER};
+
+/// Provides a timestamp inherent that advances by at least one slot for every block.
+///
+/// Blocks may be sealed much faster than `SLOT_DURATION`, so the wall clock alone would
+/// not move the Aura slot forward.
+pub struct MockTimestampInherentDataProvider {
+	last: AtomicU64,
+}
+
+impl MockTimestampInherentDataProvider {
+	pub fn new() -> Self {
+		Self { last: AtomicU64::new(0) }
+	}
+}
+
+impl ProvideInherentData for MockTimestampInherentDataProvider {
+	fn inherent_identifier(&self) -> &'static InherentIdentifier {
+		&INHERENT_IDENTIFIER
+	}
+
+	fn provide_inherent_data(
+		&self,
+		inherent_data: &mut InherentData,
+	) -> Result<(), sp_inherents::Error> {
+		let now = SystemTime::now().duration_since(UNIX_EPOCH)
+			.map_err(|_| "Current time is before unix epoch")?
+			.as_millis() as u64;
+
+		let mut last = self.last.load(Ordering::SeqCst);
+		let timestamp = loop {
+			let next = now.max(last.saturating_add(SLOT_DURATION));
+			match self.last.compare_exchange(last, next, Ordering::SeqCst, Ordering::SeqCst) {
+				Ok(_) => break next,
+				Err(current) => last = current,
+			}
+		};
+
+		inherent_data.put_data(INHERENT_IDENTIFIER, &timestamp)
+	}
+
+	fn error_to_string(&self, error: &[u8]) -> Option<String> {
+		InherentError::try_from(&INHERENT_IDENTIFIER, error).map(|e| format!("{:?}", e))
+	}
+}
+
+/// Adds the Aura pre-runtime digest matching the block's timestamp inherent.
+pub struct AuraConsensusDataProvider;
+
+impl ConsensusDataProvider<Block> for AuraConsensusDataProvider {
+	type Transaction = sp_api::TransactionFor<crate::service::FullClient, Block>;
+
+	fn create_digest(
+		&self,
+		_parent: &<Block as BlockT>::Header,
+		inherents: &InherentData,
+	) -> Result<DigestFor<Block>, Error> {
+		let timestamp = inherents.get_data::<u64>(&INHERENT_IDENTIFIER)?
+			.ok_or_else(|| Error::StringError("No timestamp inherent data".into()))?;
+		let slot = timestamp / SLOT_DURATION;
+
+		Ok(Digest { logs: vec![DigestItem::PreRuntime(AURA_ENGINE_ID, slot.encode())] })
+	}
+
+	fn append_block_import(
+		&self,
+		_parent: &<Block as BlockT>::Header,
+		_params: &mut BlockImportParams<Block, Self::Transaction>,
+		_inherents: &InherentData,
+	) -> Result<(), Error> {
+		Ok(())
+	}
+}
@@ -2,17 +2,24 @@
 
 use std::sync::Arc;
 use std::time::Duration;
-use sc_client_api::{ExecutorProvider, RemoteBackend};
+use sc_client_api::{Backend, ExecutorProvider, RemoteBackend};
+use sp_core::offchain::{OffchainStorage, STORAGE_PREFIX};
 use chiba_runtime::{self, opaque::Block, RuntimeApi};
-use sc_service::{error::Error as ServiceError, Configuration, TaskManager};
+use sc_service::{error::Error as ServiceError, ChainType, Configuration, TaskManager};
 use sp_inherents::InherentDataProviders;
+use sp_keystore::SyncCryptoStore;
 use sc_executor::native_executor_instance;
 pub use sc_executor::NativeExecutor;
 use sp_consensus_aura::sr25519::AuthorityPair as AuraPair;
 use sc_consensus_aura::{ImportQueueParams, StartAuraParams, SlotProportion};
 use sc_finality_grandpa::SharedVoterState;
-use sc_keystore::LocalKeystore;
+use chiba_remote_keystore::RemoteKeystore;
 use sc_telemetry::{Telemetry, TelemetryWorker};
+use sc_consensus_manual_seal::{EngineCommand, ManualSealParams};
+use futures::{channel::mpsc, prelude::*};
+use crate::chain_spec::Extensions;
+use crate::cli::Sealing;
+use crate::sealing::{AuraConsensusDataProvider, MockTimestampInherentDataProvider};
 
 // Our native executor instance.
 native_executor_instance!(
@@ -22,15 +29,13 @@ native_executor_instance!(
 	frame_benchmarking::benchmarking::HostFunctions,
 );
 
-type FullClient = sc_service::TFullClient<Block, RuntimeApi, Executor>;
+pub(crate) type FullClient = sc_service::TFullClient<Block, RuntimeApi, Executor>;
 type FullBackend = sc_service::TFullBackend<Block>;
 type FullSelectChain = sc_consensus::LongestChain<FullBackend, Block>;
 
-pub fn new_partial(config: &Configuration) -> Result<sc_service::PartialComponents<
-	FullClient, FullBackend, FullSelectChain,
-	sp_consensus::DefaultImportQueue<Block, FullClient>,
-	sc_transaction_pool::FullPool<Block, FullClient>,
-	(
+/// The block import and finality pieces of whichever consensus engine the node runs.
+pub enum ConsensusResult {
+	Aura(
 		sc_consensus_aura::AuraBlockImport<
 			Block,
 			FullClient,
@@ -38,13 +43,20 @@ pub fn new_partial(config: &Configuration) -> Result<sc_service::PartialComponen
 			AuraPair
 		>,
 		sc_finality_grandpa::LinkHalf<Block, FullClient, FullSelectChain>,
-		Option<Telemetry>,
-	)
+	),
+	ManualSeal(Arc<FullClient>),
+}
+
+pub fn new_partial(config: &Configuration, sealing: Option<Sealing>) -> Result<sc_service::PartialComponents<
+	FullClient, FullBackend, FullSelectChain,
+	sp_consensus::DefaultImportQueue<Block, FullClient>,
+	sc_transaction_pool::FullPool<Block, FullClient>,
+	(ConsensusResult, Option<Telemetry>),
 >, ServiceError> {
-	if config.keystore_remote.is_some() {
-		return Err(ServiceError::Other(
-			format!("Remote Keystores are not supported.")))
+	if sealing.is_some() && config.chain_spec.chain_type() != ChainType::Development {
+		return Err(ServiceError::Other("`--sealing` is only allowed on development chains".into()))
 	}
+
 	let inherent_data_providers = InherentDataProviders::new();
 
 	let telemetry = config.telemetry_endpoints.clone()
@@ -79,6 +91,31 @@ pub fn new_partial(config: &Configuration) -> Result<sc_service::PartialComponen
 		client.clone(),
 	);
 
+	if sealing.is_some() {
+		inherent_data_providers
+			.register_provider(MockTimestampInherentDataProvider::new())
+			.map_err(Into::into)
+			.map_err(sp_consensus::error::Error::InherentData)?;
+
+		let import_queue = sc_consensus_manual_seal::import_queue(
+			Box::new(client.clone()),
+			&task_manager.spawn_essential_handle(),
+			config.prometheus_registry(),
+		);
+
+		return Ok(sc_service::PartialComponents {
+			client: client.clone(),
+			backend,
+			task_manager,
+			import_queue,
+			keystore_container,
+			select_chain,
+			transaction_pool,
+			inherent_data_providers,
+			other: (ConsensusResult::ManualSeal(client), telemetry),
+		})
+	}
+
 	let (grandpa_block_import, grandpa_link) = sc_finality_grandpa::block_import(
 		client.clone(),
 		&(client.clone() as Arc<_>),
@@ -114,19 +151,26 @@ pub fn new_partial(config: &Configuration) -> Result<sc_service::PartialComponen
 		select_chain,
 		transaction_pool,
 		inherent_data_providers,
-		other: (aura_block_import, grandpa_link, telemetry),
+		other: (ConsensusResult::Aura(aura_block_import, grandpa_link), telemetry),
 	})
 }
 
-fn remote_keystore(_url: &String) -> Result<Arc<LocalKeystore>, &'static str> {
-	// FIXME: here would the concrete keystore be built,
-	//        must return a concrete type (NOT `LocalKeystore`) that
-	//        implements `CryptoStore` and `SyncCryptoStore`
-	Err("Remote Keystore not supported.")
+fn remote_keystore(url: &String) -> Result<Arc<RemoteKeystore>, String> {
+	RemoteKeystore::open(url).map(Arc::new)
 }
 
 /// Builds a new service for a full client.
-pub fn new_full(mut config: Configuration) -> Result<TaskManager, ServiceError> {
+pub fn new_full(
+	mut config: Configuration,
+	sealing: Option<Sealing>,
+	media_gateway: Option<String>,
+) -> Result<TaskManager, ServiceError> {
+	let consensus_params = Extensions::try_get(&*config.chain_spec)
+		.cloned()
+		.unwrap_or_default();
+	consensus_params.validate()
+		.map_err(|e| ServiceError::Other(format!("Invalid chain spec extensions: {}", e)))?;
+
 	let sc_service::PartialComponents {
 		client,
 		backend,
@@ -136,8 +180,8 @@ pub fn new_full(mut config: Configuration) -> Result<TaskManager, ServiceError>
 		select_chain,
 		transaction_pool,
 		inherent_data_providers,
-		other: (block_import, grandpa_link, mut telemetry),
-	} = new_partial(&config)?;
+		other: (consensus_result, mut telemetry),
+	} = new_partial(&config, sealing)?;
 
 	if let Some(url) = &config.keystore_remote {
 		match remote_keystore(url) {
@@ -149,7 +193,18 @@ pub fn new_full(mut config: Configuration) -> Result<TaskManager, ServiceError>
 		};
 	}
 
-	config.network.extra_sets.push(sc_finality_grandpa::grandpa_peers_set_config());
+	if let Some(seed) = &config.dev_key_seed {
+		// Development validators sign media check reports with the key of their account.
+		SyncCryptoStore::sr25519_generate_new(
+			&*keystore_container.sync_keystore(),
+			chiba_runtime::MEDIA_CHECK_KEY_TYPE,
+			Some(seed.as_str()),
+		).map_err(|e| ServiceError::Other(format!("Cannot add the media check key: {:?}", e)))?;
+	}
+
+	if let ConsensusResult::Aura(..) = consensus_result {
+		config.network.extra_sets.push(sc_finality_grandpa::grandpa_peers_set_config());
+	}
 
 	let (network, network_status_sinks, system_rpc_tx, network_starter) =
 		sc_service::build_network(sc_service::BuildNetworkParams {
@@ -163,6 +218,12 @@ pub fn new_full(mut config: Configuration) -> Result<TaskManager, ServiceError>
 		})?;
 
 	if config.offchain_worker.enabled {
+		if let Some(gateway) = media_gateway {
+			let mut storage = backend.offchain_storage()
+				.ok_or_else(|| ServiceError::Other("Off-chain storage is not available".into()))?;
+			storage.set(STORAGE_PREFIX, chiba_runtime::MEDIA_GATEWAY_KEY, gateway.as_bytes());
+		}
+
 		sc_service::build_offchain_workers(
 			&config, task_manager.spawn_handle(), client.clone(), network.clone(),
 		);
@@ -175,6 +236,13 @@ pub fn new_full(mut config: Configuration) -> Result<TaskManager, ServiceError>
 	let enable_grandpa = !config.disable_grandpa;
 	let prometheus_registry = config.prometheus_registry().cloned();
 
+	// Channel for the `engine_*` RPCs to drive the manual-seal engine.
+	let (command_sink, commands_stream) = mpsc::channel(1000);
+	let command_sink = match consensus_result {
+		ConsensusResult::ManualSeal(_) => Some(command_sink),
+		ConsensusResult::Aura(..) => None,
+	};
+
 	let rpc_extensions_builder = {
 		let client = client.clone();
 		let pool = transaction_pool.clone();
@@ -184,6 +252,7 @@ pub fn new_full(mut config: Configuration) -> Result<TaskManager, ServiceError>
 				client: client.clone(),
 				pool: pool.clone(),
 				deny_unsafe,
+				command_sink: command_sink.clone(),
 			};
 
 			crate::rpc::create_full(deps)
@@ -208,6 +277,63 @@ pub fn new_full(mut config: Configuration) -> Result<TaskManager, ServiceError>
 		},
 	)?;
 
+	let (block_import, grandpa_link) = match consensus_result {
+		ConsensusResult::Aura(block_import, grandpa_link) => (block_import, grandpa_link),
+		ConsensusResult::ManualSeal(block_import) => {
+			let sealing = sealing.expect("manual seal is only set up when sealing is given; qed");
+			let proposer_factory = sc_basic_authorship::ProposerFactory::new(
+				task_manager.spawn_handle(),
+				client.clone(),
+				transaction_pool.clone(),
+				prometheus_registry.as_ref(),
+				telemetry.as_ref().map(|x| x.handle()),
+			);
+
+			// Blocks sealed outside of the RPCs are finalized right away, since there is no
+			// GRANDPA voter to do it.
+			let sealing_stream = match sealing {
+				Sealing::Manual => stream::empty().boxed(),
+				Sealing::Instant => transaction_pool.pool().validated_pool()
+					.import_notification_stream()
+					.map(move |_| EngineCommand::SealNewBlock {
+						create_empty: false,
+						finalize: true,
+						parent_hash: None,
+						sender: None,
+					})
+					.boxed(),
+				Sealing::Interval(millis) => stream::unfold((), move |_| async move {
+					futures_timer::Delay::new(Duration::from_millis(millis)).await;
+					let command = EngineCommand::SealNewBlock {
+						create_empty: true,
+						finalize: true,
+						parent_hash: None,
+						sender: None,
+					};
+					Some((command, ()))
+				}).boxed(),
+			};
+
+			let manual_seal = sc_consensus_manual_seal::run_manual_seal(ManualSealParams {
+				block_import,
+				env: proposer_factory,
+				client: client.clone(),
+				pool: transaction_pool.pool().clone(),
+				commands_stream: stream::select(commands_stream, sealing_stream),
+				select_chain,
+				consensus_data_provider: Some(Box::new(AuraConsensusDataProvider)),
+				inherent_data_providers,
+			});
+
+			// the manual-seal authoring task is considered essential, i.e. if it
+			// fails we take down the service with it.
+			task_manager.spawn_essential_handle().spawn_blocking("manual-seal", manual_seal);
+
+			network_starter.start_network();
+			return Ok(task_manager)
+		},
+	};
+
 	if role.is_authority() {
 		let proposer_factory = sc_basic_authorship::ProposerFactory::new(
 			task_manager.spawn_handle(),
@@ -233,7 +359,9 @@ pub fn new_full(mut config: Configuration) -> Result<TaskManager, ServiceError>
 				keystore: keystore_container.sync_keystore(),
 				can_author_with,
 				sync_oracle: network.clone(),
-				block_proposal_slot_portion: SlotProportion::new(2f32 / 3f32),
+				block_proposal_slot_portion: SlotProportion::new(
+					consensus_params.block_proposal_slot_portion,
+				),
 				telemetry: telemetry.as_ref().map(|x| x.handle()),
 			},
 		)?;
@@ -252,9 +380,8 @@ pub fn new_full(mut config: Configuration) -> Result<TaskManager, ServiceError>
 	};
 
 	let grandpa_config = sc_finality_grandpa::Config {
-		// FIXME #1578 make this available through chainspec
-		gossip_duration: Duration::from_millis(333),
-		justification_period: 512,
+		gossip_duration: Duration::from_millis(consensus_params.gossip_duration),
+		justification_period: consensus_params.justification_period,
 		name: Some(name),
 		observer_enabled: false,
 		keystore,
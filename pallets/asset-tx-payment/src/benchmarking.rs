@@ -0,0 +1,40 @@
+//! Benchmarks for the asset transaction payment pallet.
+
+#![cfg(feature = "runtime-benchmarks")]
+
+use super::*;
+use frame_benchmarking::{account, benchmarks, impl_benchmark_test_suite};
+use frame_support::traits::EnsureOrigin;
+use sp_runtime::traits::{StaticLookup, TrailingZeroInput};
+
+const SEED: u32 = 0;
+
+/// An asset ID decoded from zeroes, since the runtime's asset IDs cannot be constructed here.
+fn asset_id<T: Config>() -> T::AssetId {
+	T::AssetId::decode(&mut TrailingZeroInput::new(&[][..])).expect("input is infinite; qed")
+}
+
+benchmarks! {
+	set_fee_rate {
+		let asset_id = asset_id::<T>();
+		let rate = Some(FixedU128::saturating_from_integer(2));
+		let origin = T::RateOrigin::successful_origin();
+	}: _<T::Origin>(origin, asset_id, rate)
+	verify {
+		assert_eq!(FeeRates::<T>::get(asset_id), rate);
+	}
+
+	sweep_stranded {
+		let asset_id = asset_id::<T>();
+		let amount: BalanceOf<T> = 1_000u32.into();
+		T::OnChargeAssetTransaction::fund_pot(asset_id, amount);
+		Pallet::<T>::note_stranded(asset_id, amount);
+		let dest: T::AccountId = account("dest", 0, SEED);
+		let origin = T::SweepOrigin::successful_origin();
+	}: _<T::Origin>(origin, asset_id, T::Lookup::unlookup(dest))
+	verify {
+		assert_eq!(StrandedFees::<T>::get(asset_id), None);
+	}
+}
+
+impl_benchmark_test_suite!(Pallet, crate::mock::new_test_ext(), crate::mock::Test);
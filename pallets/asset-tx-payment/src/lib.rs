@@ -0,0 +1,403 @@
+//! # Asset Transaction Payment Pallet
+//!
+//! Lets signers pay transaction fees in an asset instead of the native currency.
+//!
+//! [`ChargeAssetTxPayment`] takes the place of `pallet_transaction_payment`'s
+//! `ChargeTransactionPayment` in the runtime's signed extensions. Next to the tip, it carries
+//! the asset the fee is paid in. Without one, the fee is charged by `ChargeTransactionPayment`
+//! as before. With one, the fee is computed in the native currency as usual and converted at
+//! the asset's rate in [`FeeRates`], which governance sets; assets without a rate cannot pay
+//! fees. The fee is withdrawn into a pot owned by this pallet before dispatch, and the unused
+//! part of it is refunded afterwards.
+//!
+//! Refunds and fees that the runtime cannot pay out of the pot are recorded in
+//! [`StrandedFees`], and governance moves them to an account of its choice with
+//! `sweep_stranded`.
+//!
+//! The pot holds assets that are not sufficient on their own, so it is given a permanent
+//! provider reference at genesis, or by the first runtime upgrade of a chain launched without
+//! one. The reference is never removed, so the pot is never reaped.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+mod benchmarking;
+#[cfg(test)]
+mod mock;
+#[cfg(test)]
+mod tests;
+pub mod weights;
+
+use codec::{Decode, Encode};
+use frame_support::{
+	dispatch::DispatchResult,
+	traits::Get,
+	weights::{DispatchInfo, PostDispatchInfo},
+};
+use pallet_transaction_payment::{ChargeTransactionPayment, OnChargeTransaction};
+use sp_runtime::{
+	traits::{
+		AccountIdConversion, DispatchInfoOf, Dispatchable, PostDispatchInfoOf, SaturatedConversion,
+		SignedExtension,
+	},
+	transaction_validity::{
+		InvalidTransaction, TransactionValidity, TransactionValidityError, ValidTransaction,
+	},
+	FixedPointNumber, FixedPointOperand, FixedU128,
+};
+
+pub use pallet::*;
+pub use weights::WeightInfo;
+
+pub type BalanceOf<T> =
+	<<T as pallet_transaction_payment::Config>::OnChargeTransaction as OnChargeTransaction<T>>::Balance;
+
+/// Withdraws and deposits transaction fees paid in assets.
+pub trait OnChargeAssetTransaction<AccountId, AssetId, Balance> {
+	/// Withdraw `fee` of `asset_id` from `who` into the fee pot before dispatch.
+	fn withdraw_fee(
+		who: &AccountId,
+		asset_id: AssetId,
+		fee: Balance,
+	) -> Result<(), TransactionValidityError>;
+
+	/// After dispatch, refund `who` the part of `paid` above `corrected_fee` and deposit the
+	/// corrected fee, which includes `tip`.
+	fn correct_and_deposit_fee(
+		who: &AccountId,
+		asset_id: AssetId,
+		paid: Balance,
+		corrected_fee: Balance,
+		tip: Balance,
+	);
+
+	/// Transfer `amount` of `asset_id` from the fee pot to `to`.
+	fn pay_out(asset_id: AssetId, to: &AccountId, amount: Balance) -> DispatchResult;
+
+	/// Put `amount` of `asset_id` in the fee pot, creating the asset if needed, so that
+	/// benchmarks can pay it out.
+	#[cfg(feature = "runtime-benchmarks")]
+	fn fund_pot(asset_id: AssetId, amount: Balance);
+}
+
+#[frame_support::pallet]
+pub mod pallet {
+	use frame_support::{dispatch::DispatchResultWithPostInfo, pallet_prelude::*, transactional, PalletId};
+	use frame_system::pallet_prelude::*;
+	use sp_runtime::traits::{Saturating, StaticLookup};
+	use super::*;
+
+	#[pallet::config]
+	pub trait Config: frame_system::Config + pallet_transaction_payment::Config {
+		/// Because this pallet emits events, it depends on the runtime's definition of an event.
+		type Event: From<Event<Self>> + IsType<<Self as frame_system::Config>::Event>;
+
+		/// The identifier of the assets fees may be paid in.
+		type AssetId: Member + Parameter + Copy;
+
+		/// Withdraws and deposits fees paid in assets.
+		type OnChargeAssetTransaction: OnChargeAssetTransaction<
+			Self::AccountId,
+			Self::AssetId,
+			BalanceOf<Self>,
+		>;
+
+		/// The origin allowed to set fee rates.
+		type RateOrigin: EnsureOrigin<Self::Origin>;
+
+		/// The origin allowed to move stranded fees out of the pot.
+		type SweepOrigin: EnsureOrigin<Self::Origin>;
+
+		/// The pallet's ID, used to derive the account that holds fees between withdrawal and
+		/// deposit.
+		#[pallet::constant]
+		type PalletId: Get<PalletId>;
+
+		/// Weight information for extrinsics in this pallet.
+		type WeightInfo: WeightInfo;
+	}
+
+	#[pallet::pallet]
+	#[pallet::generate_store(pub(super) trait Store)]
+	pub struct Pallet<T>(_);
+
+	/// The assets fees may be paid in, with the units of the asset charged per unit of native
+	/// fee.
+	#[pallet::storage]
+	#[pallet::getter(fn fee_rates)]
+	pub type FeeRates<T: Config> = StorageMap<_, Twox64Concat, T::AssetId, FixedU128>;
+
+	/// Refunds and fees that could not be paid out of the pot, by asset.
+	#[pallet::storage]
+	#[pallet::getter(fn stranded_fees)]
+	pub type StrandedFees<T: Config> = StorageMap<_, Twox64Concat, T::AssetId, BalanceOf<T>>;
+
+	/// Whether the pot has been given its permanent provider reference.
+	#[pallet::storage]
+	pub(super) type PotProvided<T: Config> = StorageValue<_, bool, ValueQuery>;
+
+	#[pallet::genesis_config]
+	#[derive(Default)]
+	pub struct GenesisConfig {}
+
+	#[pallet::genesis_build]
+	impl<T: Config> GenesisBuild<T> for GenesisConfig {
+		fn build(&self) {
+			Pallet::<T>::provide_pot();
+		}
+	}
+
+	#[pallet::event]
+	#[pallet::metadata(T::AccountId = "AccountId", T::AssetId = "AssetId", BalanceOf<T> = "Balance")]
+	#[pallet::generate_deposit(pub(super) fn deposit_event)]
+	pub enum Event<T: Config> {
+		/// The fee rate of an asset was set, or removed if `None`. \[asset_id, rate\]
+		FeeRateSet(T::AssetId, Option<FixedU128>),
+		/// A refund or fee could not be paid out of the pot and was recorded as stranded.
+		/// \[asset_id, amount\]
+		FeeStranded(T::AssetId, BalanceOf<T>),
+		/// The stranded fees of an asset were moved out of the pot. \[asset_id, dest, amount\]
+		StrandedFeesSwept(T::AssetId, T::AccountId, BalanceOf<T>),
+	}
+
+	#[pallet::error]
+	pub enum Error<T> {
+		/// Fees cannot be paid in an asset for free.
+		ZeroRate,
+		/// No fees of the asset are stranded in the pot.
+		NothingStranded,
+	}
+
+	#[pallet::hooks]
+	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+		fn on_runtime_upgrade() -> Weight {
+			if PotProvided::<T>::get() {
+				return T::DbWeight::get().reads(1)
+			}
+			Self::provide_pot();
+			T::DbWeight::get().reads_writes(2, 2)
+		}
+	}
+
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {
+		/// Accept fees in `asset_id` at `rate` units of the asset per unit of native fee, or
+		/// stop accepting them if `rate` is `None`.
+		///
+		/// The dispatch origin must be `RateOrigin`.
+		#[pallet::weight(T::WeightInfo::set_fee_rate())]
+		pub fn set_fee_rate(
+			origin: OriginFor<T>,
+			asset_id: T::AssetId,
+			rate: Option<FixedU128>,
+		) -> DispatchResultWithPostInfo {
+			T::RateOrigin::ensure_origin(origin)?;
+
+			match rate {
+				Some(rate) => {
+					ensure!(!rate.is_zero(), Error::<T>::ZeroRate);
+					FeeRates::<T>::insert(asset_id, rate);
+				},
+				None => FeeRates::<T>::remove(asset_id),
+			}
+
+			Self::deposit_event(Event::FeeRateSet(asset_id, rate));
+			Ok(().into())
+		}
+
+		/// Move the stranded fees of `asset_id` out of the pot to `dest`.
+		///
+		/// The dispatch origin must be `SweepOrigin`.
+		#[pallet::weight(T::WeightInfo::sweep_stranded())]
+		#[transactional]
+		pub fn sweep_stranded(
+			origin: OriginFor<T>,
+			asset_id: T::AssetId,
+			dest: <T::Lookup as StaticLookup>::Source,
+		) -> DispatchResultWithPostInfo {
+			T::SweepOrigin::ensure_origin(origin)?;
+			let dest = T::Lookup::lookup(dest)?;
+			let amount = StrandedFees::<T>::get(asset_id).ok_or(Error::<T>::NothingStranded)?;
+
+			T::OnChargeAssetTransaction::pay_out(asset_id, &dest, amount)?;
+			StrandedFees::<T>::remove(asset_id);
+
+			Self::deposit_event(Event::StrandedFeesSwept(asset_id, dest, amount));
+			Ok(().into())
+		}
+	}
+
+	impl<T: Config> Pallet<T> {
+		/// The account that holds fees paid in assets between withdrawal and deposit.
+		pub fn account_id() -> T::AccountId {
+			T::PalletId::get().into_account()
+		}
+
+		/// Record `amount` of `asset_id` that could not be paid out of the pot, so that
+		/// governance can sweep it.
+		pub fn note_stranded(asset_id: T::AssetId, amount: BalanceOf<T>) {
+			StrandedFees::<T>::mutate(asset_id, |stranded| {
+				*stranded = Some(stranded.unwrap_or_default().saturating_add(amount));
+			});
+			Self::deposit_event(Event::FeeStranded(asset_id, amount));
+		}
+
+		/// Give the pot the provider reference that keeps it alive for good.
+		fn provide_pot() {
+			frame_system::Pallet::<T>::inc_providers(&Self::account_id());
+			PotProvided::<T>::put(true);
+		}
+	}
+}
+
+/// Pays the transaction fee in the native currency, like `ChargeTransactionPayment`, or in an
+/// asset with a fee rate.
+#[derive(Encode, Decode, Clone, Eq, PartialEq)]
+pub struct ChargeAssetTxPayment<T: Config> {
+	#[codec(compact)]
+	tip: BalanceOf<T>,
+	asset_id: Option<T::AssetId>,
+}
+
+impl<T: Config> ChargeAssetTxPayment<T> {
+	/// Pay `tip` on top of the fee, both in `asset_id`, or in the native currency if `None`.
+	pub fn new(tip: BalanceOf<T>, asset_id: Option<T::AssetId>) -> Self {
+		Self { tip, asset_id }
+	}
+}
+
+impl<T: Config> ChargeAssetTxPayment<T>
+where
+	BalanceOf<T>: FixedPointOperand,
+	T::Call: Dispatchable<Info = DispatchInfo, PostInfo = PostDispatchInfo>,
+{
+	/// Withdraw the fee in `asset_id`, returning the native fee, the rate and the amount paid.
+	fn withdraw_asset_fee(
+		&self,
+		who: &T::AccountId,
+		asset_id: T::AssetId,
+		info: &DispatchInfoOf<T::Call>,
+		len: usize,
+	) -> Result<(BalanceOf<T>, FixedU128, BalanceOf<T>), TransactionValidityError> {
+		let rate = FeeRates::<T>::get(asset_id).ok_or(InvalidTransaction::Payment)?;
+		let fee = pallet_transaction_payment::Pallet::<T>::compute_fee(len as u32, info, self.tip);
+		let paid = rate.saturating_mul_int(fee);
+		T::OnChargeAssetTransaction::withdraw_fee(who, asset_id, paid)?;
+		Ok((fee, rate, paid))
+	}
+}
+
+impl<T: Config> sp_std::fmt::Debug for ChargeAssetTxPayment<T> {
+	#[cfg(feature = "std")]
+	fn fmt(&self, f: &mut sp_std::fmt::Formatter) -> sp_std::fmt::Result {
+		write!(f, "ChargeAssetTxPayment<{:?}, {:?}>", self.tip, self.asset_id)
+	}
+	#[cfg(not(feature = "std"))]
+	fn fmt(&self, _: &mut sp_std::fmt::Formatter) -> sp_std::fmt::Result {
+		Ok(())
+	}
+}
+
+/// What [`ChargeAssetTxPayment`] needs after dispatch to settle the fee.
+pub enum Pre<T: Config>
+where
+	ChargeTransactionPayment<T>: SignedExtension,
+{
+	/// The fee was paid in the native currency.
+	Native(<ChargeTransactionPayment<T> as SignedExtension>::Pre),
+	/// The fee was paid in an asset, at `rate`.
+	Asset {
+		who: T::AccountId,
+		asset_id: T::AssetId,
+		rate: FixedU128,
+		paid: BalanceOf<T>,
+		tip: BalanceOf<T>,
+	},
+}
+
+impl<T: Config> Default for Pre<T>
+where
+	ChargeTransactionPayment<T>: SignedExtension,
+{
+	fn default() -> Self {
+		Pre::Native(Default::default())
+	}
+}
+
+impl<T: Config> SignedExtension for ChargeAssetTxPayment<T>
+where
+	BalanceOf<T>: Send + Sync + From<u64> + FixedPointOperand,
+	T::Call: Dispatchable<Info = DispatchInfo, PostInfo = PostDispatchInfo>,
+{
+	const IDENTIFIER: &'static str = "ChargeAssetTxPayment";
+	type AccountId = T::AccountId;
+	type Call = T::Call;
+	type AdditionalSigned = ();
+	type Pre = Pre<T>;
+
+	fn additional_signed(&self) -> sp_std::result::Result<(), TransactionValidityError> {
+		Ok(())
+	}
+
+	fn validate(
+		&self,
+		who: &Self::AccountId,
+		call: &Self::Call,
+		info: &DispatchInfoOf<Self::Call>,
+		len: usize,
+	) -> TransactionValidity {
+		match self.asset_id {
+			None => ChargeTransactionPayment::<T>::from(self.tip).validate(who, call, info, len),
+			Some(asset_id) => {
+				let (fee, _, _) = self.withdraw_asset_fee(who, asset_id, info, len)?;
+				Ok(ValidTransaction { priority: fee.saturated_into(), ..Default::default() })
+			},
+		}
+	}
+
+	fn pre_dispatch(
+		self,
+		who: &Self::AccountId,
+		call: &Self::Call,
+		info: &DispatchInfoOf<Self::Call>,
+		len: usize,
+	) -> Result<Self::Pre, TransactionValidityError> {
+		match self.asset_id {
+			None => ChargeTransactionPayment::<T>::from(self.tip)
+				.pre_dispatch(who, call, info, len)
+				.map(Pre::Native),
+			Some(asset_id) => {
+				let (_, rate, paid) = self.withdraw_asset_fee(who, asset_id, info, len)?;
+				Ok(Pre::Asset { who: who.clone(), asset_id, rate, paid, tip: self.tip })
+			},
+		}
+	}
+
+	fn post_dispatch(
+		pre: Self::Pre,
+		info: &DispatchInfoOf<Self::Call>,
+		post_info: &PostDispatchInfoOf<Self::Call>,
+		len: usize,
+		result: &DispatchResult,
+	) -> Result<(), TransactionValidityError> {
+		match pre {
+			Pre::Native(pre) =>
+				ChargeTransactionPayment::<T>::post_dispatch(pre, info, post_info, len, result),
+			Pre::Asset { who, asset_id, rate, paid, tip } => {
+				let actual_fee = pallet_transaction_payment::Pallet::<T>::compute_actual_fee(
+					len as u32,
+					info,
+					post_info,
+					tip,
+				);
+				T::OnChargeAssetTransaction::correct_and_deposit_fee(
+					&who,
+					asset_id,
+					paid,
+					rate.saturating_mul_int(actual_fee),
+					rate.saturating_mul_int(tip),
+				);
+				Ok(())
+			},
+		}
+	}
+}
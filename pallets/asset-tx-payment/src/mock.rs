@@ -0,0 +1,186 @@
+//! A runtime with balances, assets, transaction payment and fees paid in assets for tests.
+
+use crate as pallet_asset_tx_payment;
+use frame_support::{
+	parameter_types,
+	traits::GenesisBuild,
+	weights::{DispatchClass, IdentityFee},
+	PalletId,
+};
+use frame_system::EnsureRoot;
+use pallet_transaction_payment::CurrencyAdapter;
+use sp_core::H256;
+use sp_runtime::{
+	testing::Header,
+	traits::{BlakeTwo256, IdentityLookup},
+	transaction_validity::{InvalidTransaction, TransactionValidityError},
+	DispatchResult,
+};
+
+type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Test>;
+type Block = frame_system::mocking::MockBlock<Test>;
+
+pub const ALICE: u64 = 1;
+pub const BOB: u64 = 2;
+pub const TREASURY: u64 = 99;
+pub const ASSET: u32 = 7;
+
+frame_support::construct_runtime!(
+	pub enum Test where
+		Block = Block,
+		NodeBlock = Block,
+		UncheckedExtrinsic = UncheckedExtrinsic,
+	{
+		System: frame_system::{Pallet, Call, Config, Storage, Event<T>},
+		Balances: pallet_balances::{Pallet, Call, Storage, Config<T>, Event<T>},
+		TransactionPayment: pallet_transaction_payment::{Pallet, Storage},
+		Assets: pallet_assets::{Pallet, Call, Storage, Event<T>},
+		AssetTxPayment: pallet_asset_tx_payment::{Pallet, Call, Storage, Config, Event<T>},
+	}
+);
+
+parameter_types! {
+	pub const BlockHashCount: u64 = 250;
+	/// Transactions pay for their weight and length only, without a base fee.
+	pub BlockWeights: frame_system::limits::BlockWeights =
+		frame_system::limits::BlockWeights::builder()
+			.base_block(0)
+			.for_class(DispatchClass::all(), |weights| weights.base_extrinsic = 0)
+			.for_class(DispatchClass::non_mandatory(), |weights| weights.max_total = Some(1_024))
+			.build_or_panic();
+	pub const ExistentialDeposit: u64 = 1;
+	pub const TransactionByteFee: u64 = 1;
+	pub const AssetDeposit: u64 = 1;
+	pub const ApprovalDeposit: u64 = 1;
+	pub const StringLimit: u32 = 50;
+	pub const MetadataDepositBase: u64 = 1;
+	pub const MetadataDepositPerByte: u64 = 1;
+	pub const AssetTxPaymentPalletId: PalletId = PalletId(*b"chb/atxp");
+}
+
+impl frame_system::Config for Test {
+	type BaseCallFilter = ();
+	type BlockWeights = BlockWeights;
+	type BlockLength = ();
+	type DbWeight = ();
+	type Origin = Origin;
+	type Call = Call;
+	type Index = u64;
+	type BlockNumber = u64;
+	type Hash = H256;
+	type Hashing = BlakeTwo256;
+	type AccountId = u64;
+	type Lookup = IdentityLookup<Self::AccountId>;
+	type Header = Header;
+	type Event = Event;
+	type BlockHashCount = BlockHashCount;
+	type Version = ();
+	type PalletInfo = PalletInfo;
+	type AccountData = pallet_balances::AccountData<u64>;
+	type OnNewAccount = ();
+	type OnKilledAccount = ();
+	type SystemWeightInfo = ();
+	type SS58Prefix = ();
+}
+
+impl pallet_balances::Config for Test {
+	type MaxLocks = ();
+	type Balance = u64;
+	type Event = Event;
+	type DustRemoval = ();
+	type ExistentialDeposit = ExistentialDeposit;
+	type AccountStore = System;
+	type WeightInfo = ();
+}
+
+impl pallet_transaction_payment::Config for Test {
+	type OnChargeTransaction = CurrencyAdapter<Balances, ()>;
+	type TransactionByteFee = TransactionByteFee;
+	type WeightToFee = IdentityFee<u64>;
+	type FeeMultiplierUpdate = ();
+}
+
+impl pallet_assets::Config for Test {
+	type Event = Event;
+	type Balance = u64;
+	type AssetId = u32;
+	type Currency = Balances;
+	type ForceOrigin = EnsureRoot<u64>;
+	type AssetDeposit = AssetDeposit;
+	type MetadataDepositBase = MetadataDepositBase;
+	type MetadataDepositPerByte = MetadataDepositPerByte;
+	type ApprovalDeposit = ApprovalDeposit;
+	type StringLimit = StringLimit;
+	type Freezer = ();
+	type Extra = ();
+	type WeightInfo = ();
+}
+
+/// Withdraws fees into the pot, refunds the unused part and gives the rest to `TREASURY`,
+/// recording what cannot be paid out.
+pub struct AssetFees;
+impl pallet_asset_tx_payment::OnChargeAssetTransaction<u64, u32, u64> for AssetFees {
+	fn withdraw_fee(who: &u64, asset_id: u32, fee: u64) -> Result<(), TransactionValidityError> {
+		if fee == 0 {
+			return Ok(())
+		}
+		Assets::transfer(Origin::signed(*who), asset_id, AssetTxPayment::account_id(), fee)
+			.map_err(|_| InvalidTransaction::Payment.into())
+	}
+
+	fn correct_and_deposit_fee(who: &u64, asset_id: u32, paid: u64, corrected_fee: u64, _: u64) {
+		let refund = paid.saturating_sub(corrected_fee);
+		for (to, amount) in &[(*who, refund), (TREASURY, paid - refund)] {
+			if Self::pay_out(asset_id, to, *amount).is_err() {
+				AssetTxPayment::note_stranded(asset_id, *amount);
+			}
+		}
+	}
+
+	fn pay_out(asset_id: u32, to: &u64, amount: u64) -> DispatchResult {
+		if amount == 0 {
+			return Ok(())
+		}
+		Assets::transfer(Origin::signed(AssetTxPayment::account_id()), asset_id, *to, amount)
+	}
+
+	#[cfg(feature = "runtime-benchmarks")]
+	fn fund_pot(asset_id: u32, amount: u64) {
+		let pot = AssetTxPayment::account_id();
+		let _ = Assets::force_create(Origin::root(), asset_id, pot, true, 1);
+		Assets::mint(Origin::signed(pot), asset_id, pot, amount).unwrap();
+	}
+}
+
+impl pallet_asset_tx_payment::Config for Test {
+	type Event = Event;
+	type AssetId = u32;
+	type OnChargeAssetTransaction = AssetFees;
+	type RateOrigin = EnsureRoot<u64>;
+	type SweepOrigin = EnsureRoot<u64>;
+	type PalletId = AssetTxPaymentPalletId;
+	type WeightInfo = ();
+}
+
+/// Alice and Bob start with 1,000 each, and 1,000 of `ASSET`, which is not sufficient and has a
+/// minimum balance of 10. The treasury account does not exist, so it cannot receive `ASSET`.
+pub fn new_test_ext() -> sp_io::TestExternalities {
+	let mut storage = frame_system::GenesisConfig::default().build_storage::<Test>().unwrap();
+	pallet_balances::GenesisConfig::<Test> {
+		balances: vec![(ALICE, 1_000), (BOB, 1_000)],
+	}.assimilate_storage(&mut storage).unwrap();
+	<pallet_asset_tx_payment::GenesisConfig as GenesisBuild<Test>>::assimilate_storage(
+		&Default::default(),
+		&mut storage,
+	).unwrap();
+
+	let mut ext = sp_io::TestExternalities::new(storage);
+	ext.execute_with(|| {
+		System::set_block_number(1);
+		Assets::force_create(Origin::root(), ASSET, ALICE, false, 10).unwrap();
+		for who in &[ALICE, BOB] {
+			Assets::mint(Origin::signed(ALICE), ASSET, *who, 1_000).unwrap();
+		}
+	});
+	ext
+}
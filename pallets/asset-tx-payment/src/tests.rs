@@ -0,0 +1,161 @@
+//! Tests for the asset transaction payment pallet.
+
+use super::*;
+use crate::mock::*;
+use frame_support::{assert_noop, assert_ok, traits::OnRuntimeUpgrade};
+use sp_runtime::DispatchError;
+
+const LEN: usize = 10;
+
+fn call() -> Call {
+	Call::Balances(pallet_balances::Call::transfer(BOB, 1))
+}
+
+/// A call of weight 100, which costs 110 with `LEN`.
+fn info() -> DispatchInfo {
+	DispatchInfo { weight: 100, ..Default::default() }
+}
+
+/// The call used only half of its weight, which costs 60 with `LEN`.
+fn post_info() -> PostDispatchInfo {
+	PostDispatchInfo { actual_weight: Some(50), pays_fee: Default::default() }
+}
+
+fn set_rate(rate: u128) {
+	assert_ok!(AssetTxPayment::set_fee_rate(
+		Origin::root(),
+		ASSET,
+		Some(FixedU128::saturating_from_integer(rate))
+	));
+}
+
+/// Gives the treasury a native balance, so that it can hold `ASSET`.
+fn open_treasury() {
+	assert_ok!(Balances::transfer(Origin::signed(BOB), TREASURY, 10));
+}
+
+/// Charges Alice for `call` in `asset_id`, dispatches it and settles the fee.
+fn charge(asset_id: Option<u32>) {
+	let pre = ChargeAssetTxPayment::<Test>::new(0, asset_id)
+		.pre_dispatch(&ALICE, &call(), &info(), LEN)
+		.unwrap();
+	assert_ok!(ChargeAssetTxPayment::<Test>::post_dispatch(pre, &info(), &post_info(), LEN, &Ok(())));
+}
+
+#[test]
+fn fees_without_an_asset_are_paid_natively() {
+	new_test_ext().execute_with(|| {
+		charge(None);
+		assert_eq!(Balances::free_balance(ALICE), 940);
+		assert_eq!(Assets::balance(ASSET, ALICE), 1_000);
+	});
+}
+
+#[test]
+fn assets_without_a_rate_cannot_pay_fees() {
+	new_test_ext().execute_with(|| {
+		let charge = ChargeAssetTxPayment::<Test>::new(0, Some(ASSET));
+		assert_eq!(
+			charge.validate(&ALICE, &call(), &info(), LEN),
+			Err(InvalidTransaction::Payment.into())
+		);
+		assert!(charge.pre_dispatch(&ALICE, &call(), &info(), LEN).is_err());
+		assert_eq!(Assets::balance(ASSET, ALICE), 1_000);
+	});
+}
+
+#[test]
+fn fees_are_converted_at_the_rate_and_the_unused_part_is_refunded() {
+	new_test_ext().execute_with(|| {
+		open_treasury();
+		set_rate(2);
+
+		let pre = ChargeAssetTxPayment::<Test>::new(0, Some(ASSET))
+			.pre_dispatch(&ALICE, &call(), &info(), LEN)
+			.unwrap();
+		assert_eq!(Assets::balance(ASSET, ALICE), 780);
+		assert_eq!(Assets::balance(ASSET, AssetTxPayment::account_id()), 220);
+
+		assert_ok!(ChargeAssetTxPayment::<Test>::post_dispatch(pre, &info(), &post_info(), LEN, &Ok(())));
+		assert_eq!(Assets::balance(ASSET, ALICE), 880);
+		assert_eq!(Assets::balance(ASSET, TREASURY), 120);
+		assert_eq!(Assets::balance(ASSET, AssetTxPayment::account_id()), 0);
+		assert_eq!(Balances::free_balance(ALICE), 1_000);
+	});
+}
+
+#[test]
+fn fees_above_the_balance_are_rejected() {
+	new_test_ext().execute_with(|| {
+		set_rate(10);
+		assert_eq!(
+			ChargeAssetTxPayment::<Test>::new(0, Some(ASSET)).validate(&ALICE, &call(), &info(), LEN),
+			Err(InvalidTransaction::Payment.into())
+		);
+		assert_eq!(Assets::balance(ASSET, ALICE), 1_000);
+	});
+}
+
+#[test]
+fn fee_rates_are_set_by_the_rate_origin() {
+	new_test_ext().execute_with(|| {
+		let rate = Some(FixedU128::saturating_from_integer(2));
+		assert_noop!(
+			AssetTxPayment::set_fee_rate(Origin::signed(ALICE), ASSET, rate),
+			DispatchError::BadOrigin
+		);
+		assert_noop!(
+			AssetTxPayment::set_fee_rate(Origin::root(), ASSET, Some(FixedU128::from_inner(0))),
+			Error::<Test>::ZeroRate
+		);
+
+		assert_ok!(AssetTxPayment::set_fee_rate(Origin::root(), ASSET, rate));
+		assert_eq!(AssetTxPayment::fee_rates(ASSET), rate);
+		assert_ok!(AssetTxPayment::set_fee_rate(Origin::root(), ASSET, None));
+		assert_eq!(AssetTxPayment::fee_rates(ASSET), None);
+	});
+}
+
+#[test]
+fn fees_the_treasury_cannot_receive_are_stranded_and_swept() {
+	new_test_ext().execute_with(|| {
+		set_rate(2);
+		charge(Some(ASSET));
+		assert_eq!(Assets::balance(ASSET, ALICE), 880);
+		assert_eq!(AssetTxPayment::stranded_fees(ASSET), Some(120));
+		assert_eq!(Assets::balance(ASSET, AssetTxPayment::account_id()), 120);
+
+		assert_noop!(
+			AssetTxPayment::sweep_stranded(Origin::signed(ALICE), ASSET, BOB),
+			DispatchError::BadOrigin
+		);
+		assert!(AssetTxPayment::sweep_stranded(Origin::root(), ASSET, TREASURY).is_err());
+		assert_eq!(AssetTxPayment::stranded_fees(ASSET), Some(120));
+
+		assert_ok!(AssetTxPayment::sweep_stranded(Origin::root(), ASSET, BOB));
+		assert_eq!(Assets::balance(ASSET, BOB), 1_120);
+		assert_eq!(AssetTxPayment::stranded_fees(ASSET), None);
+		assert_noop!(
+			AssetTxPayment::sweep_stranded(Origin::root(), ASSET, BOB),
+			Error::<Test>::NothingStranded
+		);
+	});
+}
+
+#[test]
+fn the_pot_is_provided_for_once() {
+	new_test_ext().execute_with(|| {
+		let pot = AssetTxPayment::account_id();
+		assert_eq!(System::providers(&pot), 1);
+		AssetTxPayment::on_runtime_upgrade();
+		assert_eq!(System::providers(&pot), 1);
+
+		// A chain launched without the pallet's genesis.
+		pallet::PotProvided::<Test>::kill();
+		assert_ok!(System::dec_providers(&pot));
+		AssetTxPayment::on_runtime_upgrade();
+		assert_eq!(System::providers(&pot), 1);
+		AssetTxPayment::on_runtime_upgrade();
+		assert_eq!(System::providers(&pot), 1);
+	});
+}
@@ -0,0 +1,122 @@
+//! Benchmarks for the auction pallet.
+
+#![cfg(feature = "runtime-benchmarks")]
+
+use super::*;
+use frame_benchmarking::{account, benchmarks, impl_benchmark_test_suite, whitelisted_caller};
+use frame_support::traits::OnInitialize;
+use frame_system::RawOrigin;
+use pallet_royalties::testing_utils::{fund, royalty_class, royalty_token, zeroed};
+use sp_runtime::traits::One;
+use sp_std::vec;
+
+const SEED: u32 = 0;
+
+fn english_auction<T>(seller: &T::AccountId, token: TokenIdOf<T>) -> Result<AuctionId, &'static str>
+where
+	T: Config + pallet_royalties::Config<Currency = <T as Config>::Currency>,
+{
+	let auction_id = NextAuctionId::<T>::get();
+	Pallet::<T>::create_english(
+		RawOrigin::Signed(seller.clone()).into(),
+		token,
+		1_000_000u32.into(),
+		1_000u32.into(),
+		T::MinDuration::get(),
+	).map_err(|_| "cannot create auction")?;
+	Ok(auction_id)
+}
+
+benchmarks! {
+	where_clause { where T: pallet_royalties::Config<Currency = <T as Config>::Currency> }
+
+	create_english {
+		let caller: T::AccountId = whitelisted_caller();
+		let token = royalty_token::<T>(&caller)?;
+		let duration = T::MinDuration::get();
+	}: _(RawOrigin::Signed(caller), token, 1_000_000u32.into(), 1_000u32.into(), duration)
+	verify {
+		assert!(Auctions::<T>::contains_key(0));
+	}
+
+	create_dutch {
+		let caller: T::AccountId = whitelisted_caller();
+		let token = royalty_token::<T>(&caller)?;
+		let duration = T::MinDuration::get();
+	}: _(RawOrigin::Signed(caller), token, 2_000_000u32.into(), 1_000_000u32.into(), duration)
+	verify {
+		assert!(Auctions::<T>::contains_key(0));
+	}
+
+	// Outbids an earlier bidder close enough to the end to extend the auction.
+	bid_english {
+		let seller: T::AccountId = account("seller", 0, SEED);
+		let previous: T::AccountId = account("bidder", 0, SEED);
+		let caller: T::AccountId = whitelisted_caller();
+		fund::<_, <T as Config>::Currency>(&previous);
+		fund::<_, <T as Config>::Currency>(&caller);
+		let token = royalty_token::<T>(&seller)?;
+		let auction_id = english_auction::<T>(&seller, token)?;
+		Pallet::<T>::bid(RawOrigin::Signed(previous).into(), auction_id, 1_000_000u32.into())
+			.map_err(|_| "cannot bid")?;
+		let end = Auctions::<T>::get(auction_id).ok_or("auction not found")?.end;
+		frame_system::Pallet::<T>::set_block_number(end - One::one());
+	}: bid(RawOrigin::Signed(caller.clone()), auction_id, 2_000_000u32.into())
+	verify {
+		let auction = Auctions::<T>::get(auction_id).ok_or("auction not found")?;
+		assert_eq!(auction.highest_bid, Some((caller, 2_000_000u32.into())));
+	}
+
+	// Buys a token outright at the starting price of a Dutch auction.
+	bid_dutch {
+		let seller: T::AccountId = account("seller", 0, SEED);
+		let caller: T::AccountId = whitelisted_caller();
+		fund::<_, <T as Config>::Currency>(&seller);
+		fund::<_, <T as Config>::Currency>(&caller);
+		let token = royalty_token::<T>(&seller)?;
+		Pallet::<T>::create_dutch(
+			RawOrigin::Signed(seller).into(),
+			token,
+			2_000_000u32.into(),
+			1_000_000u32.into(),
+			T::MinDuration::get(),
+		).map_err(|_| "cannot create auction")?;
+	}: bid(RawOrigin::Signed(caller.clone()), 0, 2_000_000u32.into())
+	verify {
+		assert!(orml_nft::Pallet::<T>::is_owner(&caller, token));
+	}
+
+	cancel {
+		let caller: T::AccountId = whitelisted_caller();
+		let token = royalty_token::<T>(&caller)?;
+		let auction_id = english_auction::<T>(&caller, token)?;
+	}: _(RawOrigin::Signed(caller.clone()), auction_id)
+	verify {
+		assert!(orml_nft::Pallet::<T>::is_owner(&caller, token));
+	}
+
+	// Settles `n` English auctions with a winning bid each, all ending in the same block.
+	on_initialize {
+		let n in 0 .. T::MaxAuctionsPerBlock::get();
+
+		let seller: T::AccountId = account("seller", 0, SEED);
+		fund::<_, <T as Config>::Currency>(&seller);
+		let class_id = royalty_class::<T>(&seller)?;
+		for i in 0 .. n {
+			let bidder: T::AccountId = account("bidder", i, SEED);
+			fund::<_, <T as Config>::Currency>(&bidder);
+			let token_id = orml_nft::Pallet::<T>::mint(&seller, class_id, vec![0; 64], zeroed())?;
+			let auction_id = english_auction::<T>(&seller, (class_id, token_id))?;
+			Pallet::<T>::bid(RawOrigin::Signed(bidder).into(), auction_id, 1_000_000u32.into())
+				.map_err(|_| "cannot bid")?;
+		}
+		let end = frame_system::Pallet::<T>::block_number() + T::MinDuration::get();
+	}: {
+		Pallet::<T>::on_initialize(end);
+	}
+	verify {
+		assert_eq!(Auctions::<T>::iter().count(), 0);
+	}
+}
+
+impl_benchmark_test_suite!(Pallet, crate::mock::new_test_ext(), crate::mock::Test);
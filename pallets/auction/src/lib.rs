@@ -0,0 +1,508 @@
+//! # Auction Pallet
+//!
+//! Timed auctions for `orml_nft` tokens. The auctioned token is held in an escrow account
+//! owned by this pallet until the auction ends.
+//!
+//! - **English** auctions accept rising bids, which are reserved from the bidder until they
+//!   are outbid. A bid placed shortly before the end extends the auction, so bidders always
+//!   get a chance to respond. The highest bidder wins when the end block is reached.
+//! - **Dutch** auctions start at a high price that falls linearly every block towards a floor,
+//!   which is offered in the last block before the end. The first bid at or above the current
+//!   price buys the token immediately.
+//!
+//! Auctions are settled in `on_initialize` of their end block. Sale proceeds pay the class
+//! royalty first and the remainder goes to the seller. At most `MaxAuctionsPerBlock` auctions
+//! end in the same block: auctions cannot be created to end in a full block, and a late bid
+//! extends its auction to the first block with room, up to `AntiSnipeDuration` blocks later.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use codec::{Decode, Encode};
+use frame_support::{
+	dispatch::DispatchResult,
+	storage::{with_transaction, TransactionOutcome},
+	traits::{Currency, ExistenceRequirement, Get, ReservableCurrency},
+	weights::Weight,
+};
+use pallet_moderation::ClassModeration;
+use pallet_royalties::RoyaltyHandler;
+use sp_runtime::{
+	traits::{AccountIdConversion, One, Saturating, SaturatedConversion, Zero},
+	DispatchError, Perbill, RuntimeDebug,
+};
+
+pub use pallet::*;
+pub use weights::WeightInfo;
+
+mod benchmarking;
+#[cfg(test)]
+mod mock;
+#[cfg(test)]
+mod tests;
+pub mod weights;
+
+pub type BalanceOf<T> =
+	<<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
+pub type TokenIdOf<T> = (<T as orml_nft::Config>::ClassId, <T as orml_nft::Config>::TokenId);
+pub type AuctionOf<T> = Auction<
+	<T as frame_system::Config>::AccountId,
+	BalanceOf<T>,
+	<T as frame_system::Config>::BlockNumber,
+	TokenIdOf<T>,
+>;
+
+/// Identifier of an auction.
+pub type AuctionId = u32;
+
+/// The pricing rules of an auction.
+#[derive(Clone, Eq, PartialEq, Encode, Decode, RuntimeDebug)]
+pub enum AuctionKind<Balance> {
+	/// Rising bids; the highest bid when the auction ends wins.
+	English {
+		/// The lowest acceptable first bid.
+		min_bid: Balance,
+		/// How much each bid must exceed the previous one by. Never zero.
+		min_increment: Balance,
+	},
+	/// Falling price; the first bid at or above the current price wins.
+	Dutch {
+		/// The price when the auction starts.
+		start_price: Balance,
+		/// The price the auction falls to in the last block before its end.
+		end_price: Balance,
+	},
+}
+
+/// A running auction.
+#[derive(Clone, Eq, PartialEq, Encode, Decode, RuntimeDebug)]
+pub struct Auction<AccountId, Balance, BlockNumber, TokenId> {
+	/// The account selling the token.
+	pub seller: AccountId,
+	/// The token being sold.
+	pub token: TokenId,
+	/// The pricing rules.
+	pub kind: AuctionKind<Balance>,
+	/// The block the auction was created in.
+	pub start: BlockNumber,
+	/// The block in which the auction is settled.
+	pub end: BlockNumber,
+	/// The best bid of an English auction so far.
+	pub highest_bid: Option<(AccountId, Balance)>,
+}
+
+#[frame_support::pallet]
+pub mod pallet {
+	use frame_support::{dispatch::DispatchResultWithPostInfo, pallet_prelude::*, transactional, PalletId};
+	use frame_system::pallet_prelude::*;
+	use super::*;
+
+	#[pallet::config]
+	pub trait Config: frame_system::Config + orml_nft::Config {
+		/// Because this pallet emits events, it depends on the runtime's definition of an event.
+		type Event: From<Event<Self>> + IsType<<Self as frame_system::Config>::Event>;
+
+		/// The currency bids are placed in.
+		type Currency: ReservableCurrency<Self::AccountId>;
+
+		/// Charges the creator royalty on each sale.
+		type Royalty: RoyaltyHandler<Self::AccountId, Self::ClassId, Self::TokenId, BalanceOf<Self>>;
+
+		/// Stops trading of classes frozen by moderators.
+		type Moderation: ClassModeration<Self::ClassId>;
+
+		/// The pallet's ID, used to derive the account that escrows auctioned tokens.
+		#[pallet::constant]
+		type PalletId: Get<PalletId>;
+
+		/// The shortest an auction may run.
+		#[pallet::constant]
+		type MinDuration: Get<Self::BlockNumber>;
+
+		/// The longest an auction may run.
+		#[pallet::constant]
+		type MaxDuration: Get<Self::BlockNumber>;
+
+		/// English auctions receiving a bid within this many blocks of their end are extended
+		/// to end this many blocks after the bid.
+		#[pallet::constant]
+		type AntiSnipeDuration: Get<Self::BlockNumber>;
+
+		/// The most auctions that may end in the same block, which bounds the work of settling
+		/// them in `on_initialize`.
+		#[pallet::constant]
+		type MaxAuctionsPerBlock: Get<u32>;
+
+		/// Weight information for extrinsics in this pallet.
+		type WeightInfo: WeightInfo;
+	}
+
+	#[pallet::pallet]
+	#[pallet::generate_store(pub(super) trait Store)]
+	pub struct Pallet<T>(_);
+
+	/// The ID the next auction will get.
+	#[pallet::storage]
+	#[pallet::getter(fn next_auction_id)]
+	pub type NextAuctionId<T> = StorageValue<_, AuctionId, ValueQuery>;
+
+	/// The running auctions.
+	#[pallet::storage]
+	#[pallet::getter(fn auctions)]
+	pub type Auctions<T: Config> = StorageMap<_, Twox64Concat, AuctionId, AuctionOf<T>>;
+
+	/// The auctions to settle in each block.
+	#[pallet::storage]
+	pub type AuctionEndAt<T: Config> = StorageDoubleMap<
+		_,
+		Twox64Concat,
+		T::BlockNumber,
+		Twox64Concat,
+		AuctionId,
+		(),
+	>;
+
+	/// The number of auctions in `AuctionEndAt` for each block.
+	#[pallet::storage]
+	pub type AuctionsEndingAt<T: Config> =
+		StorageMap<_, Twox64Concat, T::BlockNumber, u32, ValueQuery>;
+
+	#[pallet::event]
+	#[pallet::metadata(
+		T::AccountId = "AccountId",
+		T::BlockNumber = "BlockNumber",
+		T::ClassId = "ClassId",
+		T::TokenId = "TokenId",
+		BalanceOf<T> = "Balance"
+	)]
+	#[pallet::generate_deposit(pub(super) fn deposit_event)]
+	pub enum Event<T: Config> {
+		/// An auction was created. \[auction_id, seller, class_id, token_id, end\]
+		AuctionCreated(AuctionId, T::AccountId, T::ClassId, T::TokenId, T::BlockNumber),
+		/// A bid was placed on an English auction. \[auction_id, bidder, amount\]
+		BidPlaced(AuctionId, T::AccountId, BalanceOf<T>),
+		/// A late bid extended an auction. \[auction_id, end\]
+		AuctionExtended(AuctionId, T::BlockNumber),
+		/// An auction sold its token. \[auction_id, buyer, price, royalty\]
+		AuctionSettled(AuctionId, T::AccountId, BalanceOf<T>, BalanceOf<T>),
+		/// An auction ended without a sale and the token was returned. \[auction_id\]
+		AuctionExpired(AuctionId),
+		/// The winning bidder could not pay and the token was returned. \[auction_id\]
+		SettlementFailed(AuctionId),
+		/// The seller cancelled an auction. \[auction_id\]
+		AuctionCancelled(AuctionId),
+	}
+
+	#[pallet::error]
+	pub enum Error<T> {
+		/// The account does not own the token.
+		NotTokenOwner,
+		/// The token's class has been frozen by moderators.
+		ClassFrozen,
+		/// The auction does not exist.
+		AuctionNotFound,
+		/// The duration is outside the allowed range.
+		InvalidDuration,
+		/// A Dutch auction must start above its end price.
+		InvalidPriceRange,
+		/// The bid is below the minimum or the current price.
+		BidTooLow,
+		/// Sellers cannot bid on their own auctions.
+		BidderIsSeller,
+		/// Only the seller may cancel an auction.
+		NotSeller,
+		/// Auctions that received bids cannot be cancelled.
+		AuctionHasBids,
+		/// No more auction IDs are available.
+		NoAvailableAuctionId,
+		/// `MaxAuctionsPerBlock` auctions already end in the block.
+		TooManyAuctionsEnding,
+		/// English auctions must require each bid to exceed the previous one.
+		ZeroIncrement,
+	}
+
+	#[pallet::hooks]
+	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+		fn on_initialize(now: T::BlockNumber) -> Weight {
+			let mut settled: u32 = 0;
+			for (auction_id, _) in AuctionEndAt::<T>::drain_prefix(now) {
+				if let Some(auction) = Auctions::<T>::take(auction_id) {
+					Self::settle(auction_id, auction);
+					settled = settled.saturating_add(1);
+				}
+			}
+			AuctionsEndingAt::<T>::remove(now);
+			T::WeightInfo::on_initialize(settled)
+		}
+	}
+
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {
+		/// Start an English auction of `token` that runs for `duration` blocks.
+		#[pallet::weight(T::WeightInfo::create_english())]
+		#[transactional]
+		pub fn create_english(
+			origin: OriginFor<T>,
+			token: TokenIdOf<T>,
+			min_bid: BalanceOf<T>,
+			min_increment: BalanceOf<T>,
+			duration: T::BlockNumber,
+		) -> DispatchResultWithPostInfo {
+			let seller = ensure_signed(origin)?;
+			ensure!(!min_increment.is_zero(), Error::<T>::ZeroIncrement);
+			Self::create(seller, token, AuctionKind::English { min_bid, min_increment }, duration)?;
+			Ok(().into())
+		}
+
+		/// Start a Dutch auction of `token` whose price falls from `start_price` to `end_price`
+		/// over `duration` blocks. The auction is settled in its end block, so `end_price` is
+		/// offered in the block before.
+		#[pallet::weight(T::WeightInfo::create_dutch())]
+		#[transactional]
+		pub fn create_dutch(
+			origin: OriginFor<T>,
+			token: TokenIdOf<T>,
+			start_price: BalanceOf<T>,
+			end_price: BalanceOf<T>,
+			duration: T::BlockNumber,
+		) -> DispatchResultWithPostInfo {
+			let seller = ensure_signed(origin)?;
+			ensure!(start_price > end_price, Error::<T>::InvalidPriceRange);
+			Self::create(seller, token, AuctionKind::Dutch { start_price, end_price }, duration)?;
+			Ok(().into())
+		}
+
+		/// Bid `amount` on an auction.
+		///
+		/// English bids are reserved until outbid. A Dutch bid at or above the current price
+		/// buys the token immediately at the current price.
+		#[pallet::weight(
+			Pallet::<T>::bid_english_weight(T::AntiSnipeDuration::get().saturated_into())
+				.max(T::WeightInfo::bid_dutch())
+		)]
+		#[transactional]
+		pub fn bid(
+			origin: OriginFor<T>,
+			auction_id: AuctionId,
+			amount: BalanceOf<T>,
+		) -> DispatchResultWithPostInfo {
+			let bidder = ensure_signed(origin)?;
+			let mut auction = Auctions::<T>::get(auction_id).ok_or(Error::<T>::AuctionNotFound)?;
+			ensure!(auction.seller != bidder, Error::<T>::BidderIsSeller);
+			ensure!(!T::Moderation::is_frozen(&auction.token.0), Error::<T>::ClassFrozen);
+			let now = frame_system::Pallet::<T>::block_number();
+			let price = Self::current_price(&auction, now);
+			ensure!(amount >= price && !amount.is_zero(), Error::<T>::BidTooLow);
+
+			match auction.kind {
+				AuctionKind::English { .. } => {
+					if let Some((previous, reserved)) = auction.highest_bid.take() {
+						T::Currency::unreserve(&previous, reserved);
+					}
+					T::Currency::reserve(&bidder, amount)?;
+					auction.highest_bid = Some((bidder.clone(), amount));
+
+					let mut skipped = 0;
+					let mut extended_end = now.saturating_add(T::AntiSnipeDuration::get());
+					if extended_end > auction.end {
+						let latest = extended_end.saturating_add(T::AntiSnipeDuration::get());
+						while Self::is_full(extended_end) {
+							ensure!(extended_end < latest, Error::<T>::TooManyAuctionsEnding);
+							extended_end = extended_end.saturating_add(One::one());
+							skipped += 1;
+						}
+						Self::book_end(extended_end)?;
+						Self::release_end(auction.end);
+						AuctionEndAt::<T>::remove(auction.end, auction_id);
+						AuctionEndAt::<T>::insert(extended_end, auction_id, ());
+						auction.end = extended_end;
+						Self::deposit_event(Event::AuctionExtended(auction_id, extended_end));
+					}
+
+					Auctions::<T>::insert(auction_id, auction);
+					Self::deposit_event(Event::BidPlaced(auction_id, bidder, amount));
+					Ok(Some(Self::bid_english_weight(skipped)).into())
+				},
+				AuctionKind::Dutch { .. } => {
+					let royalty = Self::pay_and_release(&auction, &bidder, price)?;
+					Auctions::<T>::remove(auction_id);
+					AuctionEndAt::<T>::remove(auction.end, auction_id);
+					Self::release_end(auction.end);
+					Self::deposit_event(Event::AuctionSettled(auction_id, bidder, price, royalty));
+					Ok(Some(T::WeightInfo::bid_dutch()).into())
+				},
+			}
+		}
+
+		/// Cancel an auction that has not received any bids and return the token.
+		#[pallet::weight(T::WeightInfo::cancel())]
+		#[transactional]
+		pub fn cancel(origin: OriginFor<T>, auction_id: AuctionId) -> DispatchResultWithPostInfo {
+			let who = ensure_signed(origin)?;
+			let auction = Auctions::<T>::get(auction_id).ok_or(Error::<T>::AuctionNotFound)?;
+			ensure!(auction.seller == who, Error::<T>::NotSeller);
+			ensure!(auction.highest_bid.is_none(), Error::<T>::AuctionHasBids);
+
+			Auctions::<T>::remove(auction_id);
+			AuctionEndAt::<T>::remove(auction.end, auction_id);
+			Self::release_end(auction.end);
+			orml_nft::Pallet::<T>::transfer(&Self::account_id(), &who, auction.token)?;
+
+			Self::deposit_event(Event::AuctionCancelled(auction_id));
+			Ok(().into())
+		}
+	}
+
+	impl<T: Config> Pallet<T> {
+		/// The account that escrows auctioned tokens.
+		pub fn account_id() -> T::AccountId {
+			T::PalletId::get().into_account()
+		}
+
+		/// The price of a Dutch auction at block `now`, or the minimum next bid of an English
+		/// auction.
+		pub fn current_price(auction: &AuctionOf<T>, now: T::BlockNumber) -> BalanceOf<T> {
+			match auction.kind {
+				AuctionKind::English { min_bid, min_increment } => match &auction.highest_bid {
+					Some((_, highest)) => highest.saturating_add(min_increment),
+					None => min_bid,
+				},
+				AuctionKind::Dutch { start_price, end_price } => {
+					let elapsed = now.saturating_sub(auction.start).saturated_into::<u32>();
+					// Bids are taken until the block before the end, which offers `end_price`.
+					let steps = auction.end
+						.saturating_sub(auction.start)
+						.saturated_into::<u32>()
+						.saturating_sub(1)
+						.max(1);
+					let progress = Perbill::from_rational_approximation(elapsed.min(steps), steps);
+					start_price.saturating_sub(progress.mul_floor(start_price.saturating_sub(end_price)))
+				},
+			}
+		}
+
+		fn create(
+			seller: T::AccountId,
+			token: TokenIdOf<T>,
+			kind: AuctionKind<BalanceOf<T>>,
+			duration: T::BlockNumber,
+		) -> DispatchResult {
+			ensure!(
+				duration >= T::MinDuration::get() && duration <= T::MaxDuration::get(),
+				Error::<T>::InvalidDuration
+			);
+			ensure!(orml_nft::Pallet::<T>::is_owner(&seller, token), Error::<T>::NotTokenOwner);
+			ensure!(!T::Moderation::is_frozen(&token.0), Error::<T>::ClassFrozen);
+
+			let start = frame_system::Pallet::<T>::block_number();
+			let end = start.saturating_add(duration);
+			Self::book_end(end)?;
+
+			let auction_id = NextAuctionId::<T>::try_mutate(|id| -> Result<AuctionId, DispatchError> {
+				let current = *id;
+				*id = id.checked_add(1).ok_or(Error::<T>::NoAvailableAuctionId)?;
+				Ok(current)
+			})?;
+
+			orml_nft::Pallet::<T>::transfer(&seller, &Self::account_id(), token)?;
+
+			Auctions::<T>::insert(auction_id, Auction {
+				seller: seller.clone(),
+				token,
+				kind,
+				start,
+				end,
+				highest_bid: None,
+			});
+			AuctionEndAt::<T>::insert(end, auction_id, ());
+
+			Self::deposit_event(Event::AuctionCreated(auction_id, seller, token.0, token.1, end));
+			Ok(())
+		}
+
+		/// Whether `MaxAuctionsPerBlock` auctions already end in block `end`.
+		fn is_full(end: T::BlockNumber) -> bool {
+			AuctionsEndingAt::<T>::get(end) >= T::MaxAuctionsPerBlock::get()
+		}
+
+		/// Counts an auction towards the ones ending in block `end`.
+		fn book_end(end: T::BlockNumber) -> DispatchResult {
+			ensure!(!Self::is_full(end), Error::<T>::TooManyAuctionsEnding);
+			AuctionsEndingAt::<T>::mutate(end, |count| *count = count.saturating_add(1));
+			Ok(())
+		}
+
+		/// Stops counting an auction towards the ones ending in block `end`.
+		fn release_end(end: T::BlockNumber) {
+			AuctionsEndingAt::<T>::mutate_exists(end, |count| {
+				*count = count.map(|count| count.saturating_sub(1)).filter(|count| *count > 0);
+			});
+		}
+
+		/// The weight of an English bid that skipped `skipped` full blocks to extend its
+		/// auction.
+		fn bid_english_weight(skipped: u32) -> Weight {
+			T::WeightInfo::bid_english()
+				.saturating_add(T::DbWeight::get().reads(skipped as Weight))
+		}
+
+		/// Pays `price` from `buyer` to the seller and creator and hands over the token,
+		/// returning the royalty paid.
+		fn pay_and_release(
+			auction: &AuctionOf<T>,
+			buyer: &T::AccountId,
+			price: BalanceOf<T>,
+		) -> Result<BalanceOf<T>, DispatchError> {
+			let royalty = T::Royalty::pay_royalty(buyer, auction.token, None, price)?;
+			T::Currency::transfer(
+				buyer,
+				&auction.seller,
+				price.saturating_sub(royalty),
+				ExistenceRequirement::AllowDeath,
+			)?;
+			orml_nft::Pallet::<T>::transfer(&Self::account_id(), buyer, auction.token)?;
+			Ok(royalty)
+		}
+
+		/// Settles an auction that reached its end block.
+		fn settle(auction_id: AuctionId, auction: AuctionOf<T>) {
+			// Auctions of classes frozen while they were running end without a sale.
+			let (winner, price) = match auction.highest_bid.clone() {
+				Some(bid) if !T::Moderation::is_frozen(&auction.token.0) => bid,
+				bid => {
+					if let Some((bidder, amount)) = bid {
+						T::Currency::unreserve(&bidder, amount);
+					}
+					let _ = orml_nft::Pallet::<T>::transfer(
+						&Self::account_id(),
+						&auction.seller,
+						auction.token,
+					);
+					Self::deposit_event(Event::AuctionExpired(auction_id));
+					return
+				},
+			};
+
+			T::Currency::unreserve(&winner, price);
+			let result = with_transaction(|| {
+				match Self::pay_and_release(&auction, &winner, price) {
+					Ok(royalty) => TransactionOutcome::Commit(Ok(royalty)),
+					Err(e) => TransactionOutcome::Rollback(Err(e)),
+				}
+			});
+
+			match result {
+				Ok(royalty) => {
+					Self::deposit_event(Event::AuctionSettled(auction_id, winner, price, royalty));
+				},
+				Err(_) => {
+					let _ = orml_nft::Pallet::<T>::transfer(
+						&Self::account_id(),
+						&auction.seller,
+						auction.token,
+					);
+					Self::deposit_event(Event::SettlementFailed(auction_id));
+				},
+			}
+		}
+	}
+}
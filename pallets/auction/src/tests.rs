@@ -0,0 +1,277 @@
+//! Tests for the auction pallet.
+
+use super::*;
+use crate::mock::*;
+use frame_support::{assert_noop, assert_ok, traits::OnInitialize};
+use pallet_royalties::testing_utils::{token, token_with_royalty};
+
+/// Starts an English auction of a new token of Alice's that ends `MinDuration` blocks from now.
+fn english(min_bid: u64) -> (AuctionId, (u32, u64)) {
+	let token = token::<Test>(&ALICE).unwrap();
+	let auction_id = Auction::next_auction_id();
+	assert_ok!(Auction::create_english(Origin::signed(ALICE), token, min_bid, 10, 10));
+	(auction_id, token)
+}
+
+fn run_to(block: u64) {
+	while System::block_number() < block {
+		System::set_block_number(System::block_number() + 1);
+		Auction::on_initialize(System::block_number());
+	}
+}
+
+fn end_of(auction_id: AuctionId) -> u64 {
+	Auction::auctions(auction_id).unwrap().end
+}
+
+#[test]
+fn english_auctions_go_to_the_highest_bidder() {
+	new_test_ext().execute_with(|| {
+		let token = token_with_royalty::<Test>(&ALICE, 1_000, CHARLIE).unwrap();
+		assert_ok!(Auction::create_english(Origin::signed(ALICE), token, 100, 10, 10));
+		assert!(Nft::is_owner(&Auction::account_id(), token));
+
+		assert_noop!(Auction::bid(Origin::signed(BOB), 0, 99), Error::<Test>::BidTooLow);
+		assert_noop!(Auction::bid(Origin::signed(ALICE), 0, 100), Error::<Test>::BidderIsSeller);
+		assert_ok!(Auction::bid(Origin::signed(BOB), 0, 100));
+		assert_eq!(Balances::reserved_balance(BOB), 100);
+
+		assert_noop!(Auction::bid(Origin::signed(CHARLIE), 0, 109), Error::<Test>::BidTooLow);
+		assert_ok!(Auction::bid(Origin::signed(CHARLIE), 0, 200));
+		assert_eq!(Balances::reserved_balance(BOB), 0);
+		assert_eq!(Balances::reserved_balance(CHARLIE), 200);
+
+		run_to(11);
+		assert!(Nft::is_owner(&CHARLIE, token));
+		assert_eq!(Auction::auctions(0), None);
+		assert_eq!(AuctionsEndingAt::<Test>::get(11), 0);
+		// Charlie pays 200 and receives the 10% royalty back as the beneficiary.
+		assert_eq!(Balances::free_balance(CHARLIE), 820);
+		assert_eq!(Balances::reserved_balance(CHARLIE), 0);
+		assert_eq!(Balances::free_balance(ALICE), 1_180);
+	});
+}
+
+#[test]
+fn auctions_without_bids_return_the_token() {
+	new_test_ext().execute_with(|| {
+		let (_, token) = english(100);
+		run_to(11);
+		assert!(Nft::is_owner(&ALICE, token));
+		assert_eq!(Auction::auctions(0), None);
+	});
+}
+
+#[test]
+fn late_bids_extend_the_auction() {
+	new_test_ext().execute_with(|| {
+		let (auction_id, _) = english(100);
+		assert_eq!(end_of(auction_id), 11);
+
+		// Bids more than `AntiSnipeDuration` blocks before the end leave it unchanged.
+		run_to(5);
+		assert_ok!(Auction::bid(Origin::signed(BOB), auction_id, 100));
+		assert_eq!(end_of(auction_id), 11);
+
+		run_to(8);
+		assert_ok!(Auction::bid(Origin::signed(CHARLIE), auction_id, 200));
+		assert_eq!(end_of(auction_id), 13);
+		assert!(AuctionEndAt::<Test>::contains_key(13, auction_id));
+		assert!(!AuctionEndAt::<Test>::contains_key(11, auction_id));
+		assert_eq!(AuctionsEndingAt::<Test>::get(11), 0);
+		assert_eq!(AuctionsEndingAt::<Test>::get(13), 1);
+
+		run_to(11);
+		assert!(Auction::auctions(auction_id).is_some());
+		run_to(13);
+		assert_eq!(Auction::auctions(auction_id), None);
+		assert_eq!(Balances::free_balance(CHARLIE), 800);
+	});
+}
+
+#[test]
+fn auctions_ending_in_a_block_are_capped() {
+	new_test_ext().execute_with(|| {
+		english(100);
+		english(100);
+		let third = token::<Test>(&ALICE).unwrap();
+		assert_noop!(
+			Auction::create_english(Origin::signed(ALICE), third, 100, 10, 10),
+			Error::<Test>::TooManyAuctionsEnding,
+		);
+		assert_noop!(
+			Auction::create_dutch(Origin::signed(ALICE), third, 200, 100, 10),
+			Error::<Test>::TooManyAuctionsEnding,
+		);
+		assert_ok!(Auction::create_english(Origin::signed(ALICE), third, 100, 10, 11));
+
+		// Cancelling an auction makes room in its block.
+		assert_ok!(Auction::cancel(Origin::signed(ALICE), 0));
+		assert_ok!(Auction::create_english(Origin::signed(ALICE), token::<Test>(&ALICE).unwrap(), 100, 10, 10));
+	});
+}
+
+#[test]
+fn extensions_skip_full_blocks() {
+	new_test_ext().execute_with(|| {
+		let (auction_id, _) = english(100);
+		// Fill block 13, where a bid in block 8 would extend the auction to.
+		run_to(3);
+		english(100);
+		english(100);
+
+		run_to(8);
+		assert_ok!(Auction::bid(Origin::signed(BOB), auction_id, 100));
+		assert_eq!(end_of(auction_id), 14);
+		assert_eq!(AuctionsEndingAt::<Test>::get(13), 2);
+		assert_eq!(AuctionsEndingAt::<Test>::get(14), 1);
+	});
+}
+
+#[test]
+fn extensions_fail_when_every_block_in_reach_is_full() {
+	new_test_ext().execute_with(|| {
+		let (auction_id, _) = english(100);
+		// Fill blocks 13 to 18, every block a bid in block 8 may extend the auction to.
+		for block in 3..=8 {
+			run_to(block);
+			english(100);
+			english(100);
+		}
+
+		assert_noop!(
+			Auction::bid(Origin::signed(BOB), auction_id, 100),
+			Error::<Test>::TooManyAuctionsEnding,
+		);
+	});
+}
+
+#[test]
+fn dutch_prices_fall_linearly_to_the_end_price() {
+	new_test_ext().execute_with(|| {
+		let token = token::<Test>(&ALICE).unwrap();
+		assert_ok!(Auction::create_dutch(Origin::signed(ALICE), token, 1_000, 100, 10));
+		let auction = Auction::auctions(0).unwrap();
+
+		for (block, price) in &[(1, 1_000), (2, 900), (6, 500), (10, 100), (11, 100), (20, 100)] {
+			assert_eq!(Auction::current_price(&auction, *block), *price, "block {}", block);
+		}
+
+		run_to(6);
+		assert_noop!(Auction::bid(Origin::signed(BOB), 0, 499), Error::<Test>::BidTooLow);
+		// The buyer pays the current price, not their bid.
+		assert_ok!(Auction::bid(Origin::signed(BOB), 0, 600));
+		assert!(Nft::is_owner(&BOB, token));
+		assert_eq!(Balances::free_balance(BOB), 500);
+		assert_eq!(Balances::free_balance(ALICE), 1_500);
+		assert_eq!(Auction::auctions(0), None);
+		assert_eq!(AuctionsEndingAt::<Test>::get(11), 0);
+	});
+}
+
+#[test]
+fn the_end_price_is_offered_before_the_end() {
+	new_test_ext().execute_with(|| {
+		let token = token::<Test>(&ALICE).unwrap();
+		assert_ok!(Auction::create_dutch(Origin::signed(ALICE), token, 1_000, 100, 10));
+
+		run_to(10);
+		assert_ok!(Auction::bid(Origin::signed(BOB), 0, 100));
+		assert!(Nft::is_owner(&BOB, token));
+		assert_eq!(Balances::free_balance(ALICE), 1_100);
+	});
+}
+
+#[test]
+fn dutch_auctions_need_a_falling_price() {
+	new_test_ext().execute_with(|| {
+		let token = token::<Test>(&ALICE).unwrap();
+		assert_noop!(
+			Auction::create_dutch(Origin::signed(ALICE), token, 100, 100, 10),
+			Error::<Test>::InvalidPriceRange,
+		);
+	});
+}
+
+#[test]
+fn winners_that_cannot_pay_get_nothing() {
+	new_test_ext().execute_with(|| {
+		let (auction_id, token) = english(100);
+		assert_ok!(Auction::bid(Origin::signed(BOB), auction_id, 1_000));
+		// The winning bid is lost before the auction ends.
+		let _ = <Balances as ReservableCurrency<u64>>::slash_reserved(&BOB, 1_000);
+
+		run_to(11);
+		assert!(Nft::is_owner(&ALICE, token));
+		assert_eq!(Auction::auctions(auction_id), None);
+		assert_eq!(Balances::free_balance(ALICE), 1_000);
+	});
+}
+
+#[test]
+fn create_checks_the_token_and_duration() {
+	new_test_ext().execute_with(|| {
+		let token = token::<Test>(&ALICE).unwrap();
+		assert_noop!(
+			Auction::create_english(Origin::signed(BOB), token, 100, 10, 10),
+			Error::<Test>::NotTokenOwner,
+		);
+		assert_noop!(
+			Auction::create_english(Origin::signed(ALICE), token, 100, 0, 10),
+			Error::<Test>::ZeroIncrement,
+		);
+		assert_noop!(
+			Auction::create_english(Origin::signed(ALICE), token, 100, 10, 9),
+			Error::<Test>::InvalidDuration,
+		);
+		assert_noop!(
+			Auction::create_english(Origin::signed(ALICE), token, 100, 10, 1_001),
+			Error::<Test>::InvalidDuration,
+		);
+	});
+}
+
+#[test]
+fn only_sellers_cancel_auctions_without_bids() {
+	new_test_ext().execute_with(|| {
+		let (auction_id, token) = english(100);
+		assert_noop!(Auction::cancel(Origin::signed(BOB), auction_id), Error::<Test>::NotSeller);
+
+		assert_ok!(Auction::bid(Origin::signed(BOB), auction_id, 100));
+		assert_noop!(
+			Auction::cancel(Origin::signed(ALICE), auction_id),
+			Error::<Test>::AuctionHasBids,
+		);
+
+		let (other, other_token) = english(100);
+		assert_ok!(Auction::cancel(Origin::signed(ALICE), other));
+		assert!(Nft::is_owner(&ALICE, other_token));
+		assert!(Nft::is_owner(&Auction::account_id(), token));
+		assert_noop!(Auction::cancel(Origin::signed(ALICE), other), Error::<Test>::AuctionNotFound);
+	});
+}
+
+#[test]
+fn frozen_classes_cannot_be_auctioned() {
+	new_test_ext().execute_with(|| {
+		let (auction_id, token) = english(100);
+		assert_ok!(Auction::bid(Origin::signed(BOB), auction_id, 100));
+		assert_ok!(Moderation::freeze_class(Origin::root(), token.0));
+
+		let unlisted = (token.0, Nft::mint(&ALICE, token.0, vec![], ()).unwrap());
+		assert_noop!(
+			Auction::create_english(Origin::signed(ALICE), unlisted, 100, 10, 10),
+			Error::<Test>::ClassFrozen,
+		);
+		assert_noop!(
+			Auction::bid(Origin::signed(CHARLIE), auction_id, 200),
+			Error::<Test>::ClassFrozen,
+		);
+
+		// The running auction ends without a sale and the bid is released.
+		run_to(11);
+		assert!(Nft::is_owner(&ALICE, token));
+		assert_eq!(Balances::free_balance(BOB), 1_000);
+		assert_eq!(Balances::reserved_balance(BOB), 0);
+	});
+}
@@ -0,0 +1,73 @@
+//! Benchmarks for the fractions pallet.
+
+#![cfg(feature = "runtime-benchmarks")]
+
+use super::*;
+use frame_benchmarking::{account, benchmarks, impl_benchmark_test_suite, whitelisted_caller};
+use frame_system::RawOrigin;
+use pallet_royalties::testing_utils::{fund, token, zeroed};
+
+const SEED: u32 = 0;
+const SHARES: u32 = 1_000;
+
+fn vaulted_token<T>(curator: &T::AccountId) -> Result<TokenIdOf<T>, &'static str>
+where
+	T: Config + pallet_royalties::Config<Currency = <T as Config>::Currency>,
+{
+	fund::<_, <T as Config>::Currency>(curator);
+	let token = token::<T>(curator)?;
+	Pallet::<T>::fractionalize(
+		RawOrigin::Signed(curator.clone()).into(),
+		token,
+		zeroed(),
+		SHARES.into(),
+		1_000_000u32.into(),
+	).map_err(|_| "cannot fractionalize token")?;
+	Ok(token)
+}
+
+benchmarks! {
+	where_clause { where T: pallet_royalties::Config<Currency = <T as Config>::Currency> }
+
+	fractionalize {
+		let caller: T::AccountId = whitelisted_caller();
+		fund::<_, <T as Config>::Currency>(&caller);
+		let token = token::<T>(&caller)?;
+	}: _(RawOrigin::Signed(caller), token, zeroed(), SHARES.into(), 1_000_000u32.into())
+	verify {
+		assert!(Vaults::<T>::contains_key(token));
+	}
+
+	// Buys out a token whose shares are all held by others, leaving the proceeds to claim.
+	buyout {
+		let curator: T::AccountId = account("curator", 0, SEED);
+		let caller: T::AccountId = whitelisted_caller();
+		fund::<_, <T as Config>::Currency>(&caller);
+		let token = vaulted_token::<T>(&curator)?;
+	}: _(RawOrigin::Signed(caller.clone()), token, BalanceOf::<T>::max_value())
+	verify {
+		assert!(orml_nft::Pallet::<T>::is_owner(&caller, token));
+	}
+
+	claim {
+		let caller: T::AccountId = whitelisted_caller();
+		let buyer: T::AccountId = account("buyer", 0, SEED);
+		fund::<_, <T as Config>::Currency>(&buyer);
+		let token = vaulted_token::<T>(&caller)?;
+		Pallet::<T>::buyout(RawOrigin::Signed(buyer).into(), token, BalanceOf::<T>::max_value())
+			.map_err(|_| "cannot buy out token")?;
+	}: _(RawOrigin::Signed(caller), token)
+	verify {
+		assert!(!Vaults::<T>::contains_key(token));
+	}
+
+	redeem {
+		let caller: T::AccountId = whitelisted_caller();
+		let token = vaulted_token::<T>(&caller)?;
+	}: _(RawOrigin::Signed(caller.clone()), token)
+	verify {
+		assert!(orml_nft::Pallet::<T>::is_owner(&caller, token));
+	}
+}
+
+impl_benchmark_test_suite!(Pallet, crate::mock::new_test_ext(), crate::mock::Test);
@@ -0,0 +1,395 @@
+//! # Fractions Pallet
+//!
+//! Fractional ownership of `orml_nft` tokens through fungible shares.
+//!
+//! Fractionalizing a token locks it in a vault, an escrow account owned by this pallet, and
+//! issues a new fungible asset whose units are shares of the token. Shares can then be held and
+//! traded like any other asset. The vault ends in one of two ways:
+//!
+//! - **Buyout**: anyone may buy the token at the vault's reserve price, paying only for the
+//!   shares they do not hold. Their own shares are burned, the class royalty is paid, and the
+//!   rest of the price stays in the vault for the other shareholders, who burn their shares to
+//!   claim their part of it.
+//! - **Redemption**: an account holding every share burns them to take the token out of the
+//!   vault.
+//!
+//! Shares move freely, so whoever gathers them all takes the token without a sale. Tokens of
+//! classes with a royalty therefore cannot be fractionalized, as the royalty could not be
+//! charged.
+//!
+//! Shares are issued through the [`ShareAssets`] trait, which the runtime implements with an
+//! assets pallet. The curator pays a deposit for the share asset, which is returned as soon as
+//! the token leaves the vault, whether it is bought out or redeemed.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use codec::{Decode, Encode};
+use frame_support::{
+	dispatch::DispatchResult,
+	traits::{Currency, ExistenceRequirement, Get, ReservableCurrency},
+};
+use pallet_moderation::ClassModeration;
+use pallet_royalties::{RoyaltyClasses, RoyaltyHandler};
+use sp_runtime::{
+	helpers_128bit::multiply_by_rational,
+	traits::{AccountIdConversion, SaturatedConversion, Saturating, Zero},
+	RuntimeDebug,
+};
+
+pub use pallet::*;
+pub use weights::WeightInfo;
+
+mod benchmarking;
+#[cfg(test)]
+mod mock;
+#[cfg(test)]
+mod tests;
+pub mod weights;
+
+pub type BalanceOf<T> =
+	<<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
+pub type TokenIdOf<T> = (<T as orml_nft::Config>::ClassId, <T as orml_nft::Config>::TokenId);
+pub type VaultOf<T> = Vault<
+	<T as frame_system::Config>::AccountId,
+	<T as Config>::AssetId,
+	BalanceOf<T>,
+>;
+
+/// Issues and burns the fungible assets that represent shares of vaulted tokens.
+pub trait ShareAssets<AccountId, AssetId, Balance> {
+	/// Creates asset `id`, administered by `admin`.
+	fn create(id: AssetId, admin: &AccountId) -> DispatchResult;
+
+	/// Issues `amount` of asset `id` to `who`, on behalf of its `admin`.
+	fn mint(id: AssetId, admin: &AccountId, who: &AccountId, amount: Balance) -> DispatchResult;
+
+	/// Destroys `amount` of asset `id` held by `who`, on behalf of its `admin`.
+	fn burn(id: AssetId, admin: &AccountId, who: &AccountId, amount: Balance) -> DispatchResult;
+
+	/// Removes asset `id`, which has no supply left.
+	fn destroy(id: AssetId) -> DispatchResult;
+
+	/// The amount of asset `id` held by `who`.
+	fn balance(id: AssetId, who: &AccountId) -> Balance;
+
+	/// The amount of asset `id` in existence.
+	fn total_supply(id: AssetId) -> Balance;
+}
+
+/// What happened to a vaulted token.
+#[derive(Clone, Eq, PartialEq, Encode, Decode, RuntimeDebug)]
+pub enum VaultState<Balance> {
+	/// The token is in the vault and can be bought out or redeemed.
+	Locked,
+	/// The token was bought out. Shareholders burn their shares to claim what is left of the
+	/// proceeds.
+	BoughtOut {
+		/// The part of the buyout price not yet claimed.
+		proceeds: Balance,
+	},
+}
+
+/// A token locked in exchange for shares.
+#[derive(Clone, Eq, PartialEq, Encode, Decode, RuntimeDebug)]
+pub struct Vault<AccountId, AssetId, Balance> {
+	/// The account that fractionalized the token.
+	pub curator: AccountId,
+	/// The asset whose units are shares of the token.
+	pub asset_id: AssetId,
+	/// The price at which anyone may buy the whole token.
+	pub reserve_price: Balance,
+	/// The deposit reserved from the curator for the share asset, zero once it was returned.
+	pub deposit: Balance,
+	/// Whether the token is still in the vault.
+	pub state: VaultState<Balance>,
+}
+
+#[frame_support::pallet]
+pub mod pallet {
+	use frame_support::{dispatch::DispatchResultWithPostInfo, pallet_prelude::*, transactional, PalletId};
+	use frame_system::pallet_prelude::*;
+	use sp_runtime::traits::AtLeast32BitUnsigned;
+	use super::*;
+
+	#[pallet::config]
+	pub trait Config: frame_system::Config + orml_nft::Config {
+		/// Because this pallet emits events, it depends on the runtime's definition of an event.
+		type Event: From<Event<Self>> + IsType<<Self as frame_system::Config>::Event>;
+
+		/// The currency buyouts are paid in and share asset deposits are reserved in.
+		type Currency: ReservableCurrency<Self::AccountId>;
+
+		/// The identifier of share assets.
+		type AssetId: Member + Parameter + Default + Copy;
+
+		/// The balance of share assets.
+		type ShareBalance: Member + Parameter + AtLeast32BitUnsigned + Default + Copy;
+
+		/// Issues and burns shares.
+		type Assets: ShareAssets<Self::AccountId, Self::AssetId, Self::ShareBalance>;
+
+		/// Tells which classes charge a royalty, and charges it on buyouts.
+		type Royalty: RoyaltyHandler<Self::AccountId, Self::ClassId, Self::TokenId, BalanceOf<Self>>
+			+ RoyaltyClasses<Self::ClassId>;
+
+		/// Stops fractionalizing and buying out tokens of classes frozen by moderators.
+		type Moderation: ClassModeration<Self::ClassId>;
+
+		/// The deposit reserved from the curator for creating a share asset, returned when the
+		/// token leaves the vault.
+		#[pallet::constant]
+		type ShareDeposit: Get<BalanceOf<Self>>;
+
+		/// The pallet's ID, used to derive the account that holds vaulted tokens and buyout
+		/// proceeds.
+		#[pallet::constant]
+		type PalletId: Get<PalletId>;
+
+		/// Weight information for extrinsics in this pallet.
+		type WeightInfo: WeightInfo;
+	}
+
+	#[pallet::pallet]
+	#[pallet::generate_store(pub(super) trait Store)]
+	pub struct Pallet<T>(_);
+
+	/// The vaults of fractionalized tokens.
+	#[pallet::storage]
+	#[pallet::getter(fn vaults)]
+	pub type Vaults<T: Config> = StorageMap<_, Twox64Concat, TokenIdOf<T>, VaultOf<T>>;
+
+	#[pallet::event]
+	#[pallet::metadata(
+		T::AccountId = "AccountId",
+		T::AssetId = "AssetId",
+		T::ClassId = "ClassId",
+		T::TokenId = "TokenId",
+		T::ShareBalance = "ShareBalance",
+		BalanceOf<T> = "Balance"
+	)]
+	#[pallet::generate_deposit(pub(super) fn deposit_event)]
+	pub enum Event<T: Config> {
+		/// A token was locked in a vault and its shares issued.
+		/// \[curator, class_id, token_id, asset_id, shares, reserve_price\]
+		Fractionalized(T::AccountId, T::ClassId, T::TokenId, T::AssetId, T::ShareBalance, BalanceOf<T>),
+		/// A token was bought out of its vault. \[buyer, class_id, token_id, price, royalty\]
+		BoughtOut(T::AccountId, T::ClassId, T::TokenId, BalanceOf<T>, BalanceOf<T>),
+		/// A shareholder burned shares for part of the buyout proceeds.
+		/// \[who, class_id, token_id, shares, amount\]
+		Claimed(T::AccountId, T::ClassId, T::TokenId, T::ShareBalance, BalanceOf<T>),
+		/// A holder of every share took the token out of its vault. \[who, class_id, token_id\]
+		Redeemed(T::AccountId, T::ClassId, T::TokenId),
+	}
+
+	#[pallet::error]
+	pub enum Error<T> {
+		/// The account does not own the token.
+		NotTokenOwner,
+		/// The token's class has been frozen by moderators.
+		ClassFrozen,
+		/// The token is not in a vault.
+		VaultNotFound,
+		/// At least one share must be issued.
+		ZeroShares,
+		/// Tokens cannot be fractionalized with a free buyout.
+		ZeroReservePrice,
+		/// The token has already been bought out.
+		AlreadyBoughtOut,
+		/// The token has not been bought out.
+		NotBoughtOut,
+		/// The buyout price is above the price the buyer agreed to pay.
+		PriceTooHigh,
+		/// The account holds no shares of the token.
+		NoShares,
+		/// Only an account holding every share may redeem the token.
+		NotAllShares,
+		/// Tokens of classes with a royalty cannot be fractionalized.
+		RoyaltyClass,
+	}
+
+	#[pallet::hooks]
+	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {}
+
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {
+		/// Lock a token in a vault and issue `shares` units of the new asset `asset_id` to the
+		/// caller. Anyone may buy the token out at `reserve_price`.
+		#[pallet::weight(T::WeightInfo::fractionalize())]
+		#[transactional]
+		pub fn fractionalize(
+			origin: OriginFor<T>,
+			token: TokenIdOf<T>,
+			asset_id: T::AssetId,
+			shares: T::ShareBalance,
+			reserve_price: BalanceOf<T>,
+		) -> DispatchResultWithPostInfo {
+			let curator = ensure_signed(origin)?;
+			ensure!(!shares.is_zero(), Error::<T>::ZeroShares);
+			ensure!(!reserve_price.is_zero(), Error::<T>::ZeroReservePrice);
+			ensure!(orml_nft::Pallet::<T>::is_owner(&curator, token), Error::<T>::NotTokenOwner);
+			ensure!(!T::Moderation::is_frozen(&token.0), Error::<T>::ClassFrozen);
+			ensure!(!T::Royalty::has_royalty(&token.0), Error::<T>::RoyaltyClass);
+
+			let deposit = T::ShareDeposit::get();
+			T::Currency::reserve(&curator, deposit)?;
+			let vault = Self::account_id();
+			orml_nft::Pallet::<T>::transfer(&curator, &vault, token)?;
+			T::Assets::create(asset_id, &vault)?;
+			T::Assets::mint(asset_id, &vault, &curator, shares)?;
+			Vaults::<T>::insert(token, Vault {
+				curator: curator.clone(),
+				asset_id,
+				reserve_price,
+				deposit,
+				state: VaultState::Locked,
+			});
+
+			Self::deposit_event(Event::Fractionalized(
+				curator,
+				token.0,
+				token.1,
+				asset_id,
+				shares,
+				reserve_price,
+			));
+			Ok(().into())
+		}
+
+		/// Buy a token out of its vault.
+		///
+		/// The buyer pays the reserve price for the shares they do not hold, and their own
+		/// shares are burned. `max_price` protects the buyer from shares moving while the
+		/// transaction is pending.
+		#[pallet::weight(T::WeightInfo::buyout())]
+		#[transactional]
+		pub fn buyout(
+			origin: OriginFor<T>,
+			token: TokenIdOf<T>,
+			max_price: BalanceOf<T>,
+		) -> DispatchResultWithPostInfo {
+			let buyer = ensure_signed(origin)?;
+			let mut vault = Vaults::<T>::get(token).ok_or(Error::<T>::VaultNotFound)?;
+			ensure!(vault.state == VaultState::Locked, Error::<T>::AlreadyBoughtOut);
+			ensure!(!T::Moderation::is_frozen(&token.0), Error::<T>::ClassFrozen);
+
+			let held = T::Assets::balance(vault.asset_id, &buyer);
+			let outstanding = T::Assets::total_supply(vault.asset_id);
+			let price = Self::share_of(vault.reserve_price, outstanding.saturating_sub(held), outstanding);
+			ensure!(price <= max_price, Error::<T>::PriceTooHigh);
+
+			let royalty = T::Royalty::pay_royalty(&buyer, token, None, price)?;
+			let proceeds = price.saturating_sub(royalty);
+			T::Currency::transfer(
+				&buyer,
+				&Self::account_id(),
+				proceeds,
+				ExistenceRequirement::KeepAlive,
+			)?;
+			if !held.is_zero() {
+				T::Assets::burn(vault.asset_id, &Self::account_id(), &buyer, held)?;
+			}
+			orml_nft::Pallet::<T>::transfer(&Self::account_id(), &buyer, token)?;
+			// The curator is not kept waiting on the last shareholder to claim.
+			T::Currency::unreserve(&vault.curator, vault.deposit);
+			vault.deposit = Zero::zero();
+
+			if held == outstanding {
+				Self::close(token, &vault)?;
+			} else {
+				vault.state = VaultState::BoughtOut { proceeds };
+				Vaults::<T>::insert(token, vault);
+			}
+
+			Self::deposit_event(Event::BoughtOut(buyer, token.0, token.1, price, royalty));
+			Ok(().into())
+		}
+
+		/// Burn all of the caller's shares of a bought-out token for their part of the
+		/// proceeds.
+		#[pallet::weight(T::WeightInfo::claim())]
+		#[transactional]
+		pub fn claim(origin: OriginFor<T>, token: TokenIdOf<T>) -> DispatchResultWithPostInfo {
+			let who = ensure_signed(origin)?;
+			let mut vault = Vaults::<T>::get(token).ok_or(Error::<T>::VaultNotFound)?;
+			let proceeds = match vault.state {
+				VaultState::BoughtOut { proceeds } => proceeds,
+				VaultState::Locked => return Err(Error::<T>::NotBoughtOut.into()),
+			};
+
+			let shares = T::Assets::balance(vault.asset_id, &who);
+			ensure!(!shares.is_zero(), Error::<T>::NoShares);
+			let outstanding = T::Assets::total_supply(vault.asset_id);
+			let amount = Self::share_of(proceeds, shares, outstanding);
+
+			T::Assets::burn(vault.asset_id, &Self::account_id(), &who, shares)?;
+			T::Currency::transfer(
+				&Self::account_id(),
+				&who,
+				amount,
+				ExistenceRequirement::AllowDeath,
+			)?;
+
+			if shares == outstanding {
+				Self::close(token, &vault)?;
+			} else {
+				vault.state = VaultState::BoughtOut { proceeds: proceeds.saturating_sub(amount) };
+				Vaults::<T>::insert(token, vault);
+			}
+
+			Self::deposit_event(Event::Claimed(who, token.0, token.1, shares, amount));
+			Ok(().into())
+		}
+
+		/// Burn every share of a token to take it out of its vault.
+		#[pallet::weight(T::WeightInfo::redeem())]
+		#[transactional]
+		pub fn redeem(origin: OriginFor<T>, token: TokenIdOf<T>) -> DispatchResultWithPostInfo {
+			let who = ensure_signed(origin)?;
+			let vault = Vaults::<T>::get(token).ok_or(Error::<T>::VaultNotFound)?;
+			ensure!(vault.state == VaultState::Locked, Error::<T>::AlreadyBoughtOut);
+
+			let shares = T::Assets::balance(vault.asset_id, &who);
+			ensure!(shares == T::Assets::total_supply(vault.asset_id), Error::<T>::NotAllShares);
+
+			T::Assets::burn(vault.asset_id, &Self::account_id(), &who, shares)?;
+			Self::close(token, &vault)?;
+			orml_nft::Pallet::<T>::transfer(&Self::account_id(), &who, token)?;
+
+			Self::deposit_event(Event::Redeemed(who, token.0, token.1));
+			Ok(().into())
+		}
+	}
+
+	impl<T: Config> Pallet<T> {
+		/// The account that holds vaulted tokens and buyout proceeds.
+		pub fn account_id() -> T::AccountId {
+			T::PalletId::get().into_account()
+		}
+
+		/// End the vault of `token` once no share is left: destroy its share asset and return
+		/// the curator's deposit if it is still reserved.
+		fn close(token: TokenIdOf<T>, vault: &VaultOf<T>) -> DispatchResult {
+			Vaults::<T>::remove(token);
+			T::Assets::destroy(vault.asset_id)?;
+			T::Currency::unreserve(&vault.curator, vault.deposit);
+			Ok(())
+		}
+
+		/// The part of `amount` that `shares` out of `outstanding` are entitled to, rounded
+		/// down.
+		pub fn share_of(
+			amount: BalanceOf<T>,
+			shares: T::ShareBalance,
+			outstanding: T::ShareBalance,
+		) -> BalanceOf<T> {
+			if outstanding.is_zero() {
+				return Zero::zero()
+			}
+			multiply_by_rational(
+				amount.saturated_into(),
+				shares.saturated_into(),
+				outstanding.saturated_into(),
+			).map(|share| share.saturated_into()).unwrap_or_else(|_| Zero::zero())
+		}
+	}
+}
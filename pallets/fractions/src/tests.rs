@@ -0,0 +1,167 @@
+//! Tests for the fractions pallet.
+
+use super::*;
+use crate::mock::*;
+use frame_support::{assert_noop, assert_ok};
+use pallet_royalties::testing_utils::{token, token_with_royalty};
+
+const ASSET: u32 = 0;
+
+/// Fractionalizes a new token of Alice's into `shares` shares of `ASSET`.
+fn fractionalized(shares: u64, reserve_price: u64) -> (u32, u64) {
+	let token = token::<Test>(&ALICE).unwrap();
+	assert_ok!(Fractions::fractionalize(Origin::signed(ALICE), token, ASSET, shares, reserve_price));
+	token
+}
+
+fn give_shares(to: u64, amount: u64) {
+	assert_ok!(Assets::transfer(Origin::signed(ALICE), ASSET, to, amount));
+}
+
+fn shares(who: u64) -> u64 {
+	Assets::balance(ASSET, who)
+}
+
+#[test]
+fn fractionalizing_locks_the_token_and_issues_shares() {
+	new_test_ext().execute_with(|| {
+		let token = fractionalized(100, 300);
+		assert!(Nft::is_owner(&Fractions::account_id(), token));
+		assert_eq!(shares(ALICE), 100);
+		assert_eq!(Balances::reserved_balance(ALICE), 1);
+		assert_eq!(Fractions::vaults(token).unwrap().state, VaultState::Locked);
+	});
+}
+
+#[test]
+fn fractionalize_is_checked() {
+	new_test_ext().execute_with(|| {
+		let token = token::<Test>(&ALICE).unwrap();
+		assert_noop!(
+			Fractions::fractionalize(Origin::signed(ALICE), token, ASSET, 0, 300),
+			Error::<Test>::ZeroShares
+		);
+		assert_noop!(
+			Fractions::fractionalize(Origin::signed(ALICE), token, ASSET, 100, 0),
+			Error::<Test>::ZeroReservePrice
+		);
+		assert_noop!(
+			Fractions::fractionalize(Origin::signed(BOB), token, ASSET, 100, 300),
+			Error::<Test>::NotTokenOwner
+		);
+	});
+}
+
+#[test]
+fn buyers_pay_only_for_the_shares_they_do_not_hold() {
+	new_test_ext().execute_with(|| {
+		let token = fractionalized(100, 300);
+		give_shares(BOB, 40);
+
+		assert_noop!(Fractions::buyout(Origin::signed(BOB), token, 179), Error::<Test>::PriceTooHigh);
+		assert_ok!(Fractions::buyout(Origin::signed(BOB), token, 180));
+
+		assert!(Nft::is_owner(&BOB, token));
+		assert_eq!(Balances::free_balance(BOB), 820);
+		assert_eq!(shares(BOB), 0);
+		assert_eq!(Assets::total_supply(ASSET), 60);
+		let vault = Fractions::vaults(token).unwrap();
+		assert_eq!(vault.state, VaultState::BoughtOut { proceeds: 180 });
+		// The curator's deposit is returned without waiting for the claims.
+		assert_eq!(vault.deposit, 0);
+		assert_eq!(Balances::reserved_balance(ALICE), 0);
+
+		assert_noop!(
+			Fractions::buyout(Origin::signed(CHARLIE), token, 300),
+			Error::<Test>::AlreadyBoughtOut
+		);
+	});
+}
+
+#[test]
+fn buying_out_every_share_closes_the_vault() {
+	new_test_ext().execute_with(|| {
+		let token = fractionalized(100, 300);
+		give_shares(BOB, 100);
+
+		assert_ok!(Fractions::buyout(Origin::signed(BOB), token, 0));
+		assert!(Nft::is_owner(&BOB, token));
+		assert_eq!(Balances::free_balance(BOB), 1_000);
+		assert_eq!(Fractions::vaults(token), None);
+		assert_eq!(Assets::total_supply(ASSET), 0);
+		assert_eq!(Balances::reserved_balance(ALICE), 0);
+	});
+}
+
+#[test]
+fn claims_round_down_and_the_last_claimer_takes_the_rest() {
+	new_test_ext().execute_with(|| {
+		let token = fractionalized(3, 101);
+		give_shares(BOB, 1);
+		give_shares(CHARLIE, 1);
+		assert_noop!(Fractions::claim(Origin::signed(ALICE), token), Error::<Test>::NotBoughtOut);
+
+		// Two thirds of 101, rounded down.
+		assert_ok!(Fractions::buyout(Origin::signed(CHARLIE), token, 67));
+		assert_eq!(Balances::free_balance(CHARLIE), 933);
+		assert_noop!(Fractions::claim(Origin::signed(CHARLIE), token), Error::<Test>::NoShares);
+
+		assert_ok!(Fractions::claim(Origin::signed(ALICE), token));
+		assert_eq!(Balances::free_balance(ALICE), 1_033);
+		assert_eq!(
+			Fractions::vaults(token).unwrap().state,
+			VaultState::BoughtOut { proceeds: 34 }
+		);
+
+		assert_ok!(Fractions::claim(Origin::signed(BOB), token));
+		assert_eq!(Balances::free_balance(BOB), 1_034);
+		assert_eq!(Fractions::vaults(token), None);
+		assert_eq!(Assets::total_supply(ASSET), 0);
+	});
+}
+
+#[test]
+fn redeeming_needs_every_share() {
+	new_test_ext().execute_with(|| {
+		let token = fractionalized(100, 300);
+		give_shares(BOB, 1);
+		assert_noop!(Fractions::redeem(Origin::signed(ALICE), token), Error::<Test>::NotAllShares);
+		assert_noop!(Fractions::redeem(Origin::signed(BOB), token), Error::<Test>::NotAllShares);
+
+		assert_ok!(Assets::transfer(Origin::signed(BOB), ASSET, ALICE, 1));
+		assert_ok!(Fractions::redeem(Origin::signed(ALICE), token));
+		assert!(Nft::is_owner(&ALICE, token));
+		assert_eq!(Fractions::vaults(token), None);
+		assert_eq!(shares(ALICE), 0);
+		assert_eq!(Balances::reserved_balance(ALICE), 0);
+	});
+}
+
+#[test]
+fn royalty_classes_cannot_be_fractionalized() {
+	new_test_ext().execute_with(|| {
+		let token = token_with_royalty::<Test>(&ALICE, 1_000, CHARLIE).unwrap();
+		assert_noop!(
+			Fractions::fractionalize(Origin::signed(ALICE), token, ASSET, 100, 300),
+			Error::<Test>::RoyaltyClass
+		);
+	});
+}
+
+#[test]
+fn frozen_classes_cannot_be_fractionalized_or_bought_out() {
+	new_test_ext().execute_with(|| {
+		let vaulted = fractionalized(100, 300);
+		let unvaulted = (vaulted.0, Nft::mint(&ALICE, vaulted.0, vec![], ()).unwrap());
+		assert_ok!(Moderation::freeze_class(Origin::root(), vaulted.0));
+
+		assert_noop!(
+			Fractions::fractionalize(Origin::signed(ALICE), unvaulted, ASSET + 1, 100, 300),
+			Error::<Test>::ClassFrozen
+		);
+		assert_noop!(
+			Fractions::buyout(Origin::signed(BOB), vaulted, 300),
+			Error::<Test>::ClassFrozen
+		);
+	});
+}
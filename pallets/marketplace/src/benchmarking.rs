@@ -0,0 +1,58 @@
+//! Benchmarks for the marketplace pallet.
+
+#![cfg(feature = "runtime-benchmarks")]
+
+use super::*;
+use frame_benchmarking::{account, benchmarks, impl_benchmark_test_suite, whitelisted_caller};
+use frame_system::RawOrigin;
+use pallet_royalties::testing_utils::{fund, royalty_token};
+
+const SEED: u32 = 0;
+
+fn listed_token<T>(seller: &T::AccountId) -> Result<TokenIdOf<T>, &'static str>
+where
+	T: Config + pallet_royalties::Config<Currency = <T as Config>::Currency>,
+{
+	let token = royalty_token::<T>(seller)?;
+	Pallet::<T>::list(RawOrigin::Signed(seller.clone()).into(), token, 1_000_000u32.into(), None)
+		.map_err(|_| "cannot list token")?;
+	Ok(token)
+}
+
+benchmarks! {
+	where_clause { where T: pallet_royalties::Config<Currency = <T as Config>::Currency> }
+
+	list {
+		let caller: T::AccountId = whitelisted_caller();
+		let token = royalty_token::<T>(&caller)?;
+	}: _(RawOrigin::Signed(caller), token, 1_000_000u32.into(), None)
+	verify {
+		assert!(Listings::<T>::contains_key(token));
+	}
+
+	delist {
+		let caller: T::AccountId = whitelisted_caller();
+		let token = listed_token::<T>(&caller)?;
+	}: _(RawOrigin::Signed(caller.clone()), token)
+	verify {
+		assert!(orml_nft::Pallet::<T>::is_owner(&caller, token));
+	}
+
+	update_price {
+		let caller: T::AccountId = whitelisted_caller();
+		let token = listed_token::<T>(&caller)?;
+	}: _(RawOrigin::Signed(caller), token, 2_000_000u32.into())
+
+	buy {
+		let seller: T::AccountId = account("seller", 0, SEED);
+		let buyer: T::AccountId = whitelisted_caller();
+		fund::<_, <T as Config>::Currency>(&seller);
+		fund::<_, <T as Config>::Currency>(&buyer);
+		let token = listed_token::<T>(&seller)?;
+	}: _(RawOrigin::Signed(buyer.clone()), token, None, 1_000_000u32.into())
+	verify {
+		assert!(orml_nft::Pallet::<T>::is_owner(&buyer, token));
+	}
+}
+
+impl_benchmark_test_suite!(Pallet, crate::mock::new_test_ext(), crate::mock::Test);
@@ -0,0 +1,265 @@
+//! # Marketplace Pallet
+//!
+//! A fixed-price marketplace for `orml_nft` tokens.
+//!
+//! Listing a token moves it into an escrow account owned by this pallet, so it cannot be
+//! transferred elsewhere while it is for sale. The seller prices the token in the native
+//! currency or in an asset. A buyer pays the listing price in it: the class royalty, if any,
+//! goes to the creator and the rest to the seller, and the token is released to the buyer.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use codec::{Decode, Encode};
+use frame_support::traits::{Currency, ExistenceRequirement, Get};
+use pallet_moderation::ClassModeration;
+use pallet_royalties::{MultiAsset, RoyaltyHandler};
+use sp_runtime::{
+	traits::{AccountIdConversion, Saturating, Zero},
+	RuntimeDebug,
+};
+
+pub use pallet::*;
+pub use weights::WeightInfo;
+
+mod benchmarking;
+#[cfg(test)]
+mod mock;
+#[cfg(test)]
+mod tests;
+pub mod weights;
+
+pub type BalanceOf<T> =
+	<<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
+pub type TokenIdOf<T> = (<T as orml_nft::Config>::ClassId, <T as orml_nft::Config>::TokenId);
+
+/// A token offered for sale.
+#[derive(Clone, Eq, PartialEq, Encode, Decode, RuntimeDebug)]
+pub struct Listing<AccountId, Balance, AssetId> {
+	/// The account that listed the token and receives the proceeds.
+	pub seller: AccountId,
+	/// The price asked for the token.
+	pub price: Balance,
+	/// The asset the price is in, or `None` for the native currency.
+	pub asset: Option<AssetId>,
+}
+
+#[frame_support::pallet]
+pub mod pallet {
+	use frame_support::{dispatch::DispatchResultWithPostInfo, pallet_prelude::*, transactional, PalletId};
+	use frame_system::pallet_prelude::*;
+	use super::*;
+
+	#[pallet::config]
+	pub trait Config: frame_system::Config + orml_nft::Config {
+		/// Because this pallet emits events, it depends on the runtime's definition of an event.
+		type Event: From<Event<Self>> + IsType<<Self as frame_system::Config>::Event>;
+
+		/// The native currency, which also sets the balance type of asset prices.
+		type Currency: Currency<Self::AccountId>;
+
+		/// The identifier of the assets tokens may be priced in.
+		type AssetId: Member + Parameter + Copy;
+
+		/// Pays for tokens in the native currency or in assets.
+		type Assets: MultiAsset<Self::AccountId, Self::AssetId, BalanceOf<Self>>;
+
+		/// Charges the creator royalty on each sale.
+		type Royalty: RoyaltyHandler<
+			Self::AccountId,
+			Self::ClassId,
+			Self::TokenId,
+			BalanceOf<Self>,
+			AssetId = Self::AssetId,
+		>;
+
+		/// Stops trading of classes frozen by moderators.
+		type Moderation: ClassModeration<Self::ClassId>;
+
+		/// The pallet's ID, used to derive the account that escrows listed tokens.
+		#[pallet::constant]
+		type PalletId: Get<PalletId>;
+
+		/// Weight information for extrinsics in this pallet.
+		type WeightInfo: WeightInfo;
+	}
+
+	#[pallet::pallet]
+	#[pallet::generate_store(pub(super) trait Store)]
+	pub struct Pallet<T>(_);
+
+	/// The tokens currently for sale.
+	#[pallet::storage]
+	#[pallet::getter(fn listings)]
+	pub type Listings<T: Config> = StorageMap<
+		_,
+		Twox64Concat,
+		TokenIdOf<T>,
+		Listing<T::AccountId, BalanceOf<T>, T::AssetId>,
+	>;
+
+	#[pallet::event]
+	#[pallet::metadata(
+		T::AccountId = "AccountId",
+		T::AssetId = "AssetId",
+		T::ClassId = "ClassId",
+		T::TokenId = "TokenId",
+		BalanceOf<T> = "Balance"
+	)]
+	#[pallet::generate_deposit(pub(super) fn deposit_event)]
+	pub enum Event<T: Config> {
+		/// A token was listed for sale. \[seller, class_id, token_id, asset, price\]
+		Listed(T::AccountId, T::ClassId, T::TokenId, Option<T::AssetId>, BalanceOf<T>),
+		/// A listing was withdrawn. \[seller, class_id, token_id\]
+		Delisted(T::AccountId, T::ClassId, T::TokenId),
+		/// The price of a listing changed. \[seller, class_id, token_id, price\]
+		PriceUpdated(T::AccountId, T::ClassId, T::TokenId, BalanceOf<T>),
+		/// A listed token was bought.
+		/// \[seller, buyer, class_id, token_id, asset, price, royalty\]
+		Sold(
+			T::AccountId,
+			T::AccountId,
+			T::ClassId,
+			T::TokenId,
+			Option<T::AssetId>,
+			BalanceOf<T>,
+			BalanceOf<T>,
+		),
+	}
+
+	#[pallet::error]
+	pub enum Error<T> {
+		/// The account does not own the token.
+		NotTokenOwner,
+		/// The token's class has been frozen by moderators.
+		ClassFrozen,
+		/// The token is already listed.
+		AlreadyListed,
+		/// The token is not listed.
+		NotListed,
+		/// Only the seller may change a listing.
+		NotSeller,
+		/// Tokens cannot be listed for free.
+		ZeroPrice,
+		/// Sellers cannot buy their own listings.
+		BuyerIsSeller,
+		/// The listing price is above the price the buyer agreed to pay.
+		PriceTooHigh,
+		/// The listing is priced in another asset than the buyer agreed to pay in.
+		WrongAsset,
+	}
+
+	#[pallet::hooks]
+	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {}
+
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {
+		/// List a token for sale at `price` of `asset`, or of the native currency if `asset` is
+		/// `None`, moving it into escrow.
+		#[pallet::weight(T::WeightInfo::list())]
+		#[transactional]
+		pub fn list(
+			origin: OriginFor<T>,
+			token: TokenIdOf<T>,
+			price: BalanceOf<T>,
+			asset: Option<T::AssetId>,
+		) -> DispatchResultWithPostInfo {
+			let seller = ensure_signed(origin)?;
+			ensure!(!price.is_zero(), Error::<T>::ZeroPrice);
+			ensure!(!Listings::<T>::contains_key(token), Error::<T>::AlreadyListed);
+			ensure!(orml_nft::Pallet::<T>::is_owner(&seller, token), Error::<T>::NotTokenOwner);
+			ensure!(!T::Moderation::is_frozen(&token.0), Error::<T>::ClassFrozen);
+
+			orml_nft::Pallet::<T>::transfer(&seller, &Self::account_id(), token)?;
+			Listings::<T>::insert(token, Listing { seller: seller.clone(), price, asset });
+
+			Self::deposit_event(Event::Listed(seller, token.0, token.1, asset, price));
+			Ok(().into())
+		}
+
+		/// Withdraw a listing and return the token to the seller.
+		#[pallet::weight(T::WeightInfo::delist())]
+		#[transactional]
+		pub fn delist(origin: OriginFor<T>, token: TokenIdOf<T>) -> DispatchResultWithPostInfo {
+			let who = ensure_signed(origin)?;
+			let listing = Listings::<T>::get(token).ok_or(Error::<T>::NotListed)?;
+			ensure!(listing.seller == who, Error::<T>::NotSeller);
+
+			Listings::<T>::remove(token);
+			orml_nft::Pallet::<T>::transfer(&Self::account_id(), &who, token)?;
+
+			Self::deposit_event(Event::Delisted(who, token.0, token.1));
+			Ok(().into())
+		}
+
+		/// Change the price of a listing.
+		#[pallet::weight(T::WeightInfo::update_price())]
+		pub fn update_price(
+			origin: OriginFor<T>,
+			token: TokenIdOf<T>,
+			price: BalanceOf<T>,
+		) -> DispatchResultWithPostInfo {
+			let who = ensure_signed(origin)?;
+			ensure!(!price.is_zero(), Error::<T>::ZeroPrice);
+
+			Listings::<T>::try_mutate(token, |listing| -> DispatchResult {
+				let listing = listing.as_mut().ok_or(Error::<T>::NotListed)?;
+				ensure!(listing.seller == who, Error::<T>::NotSeller);
+				listing.price = price;
+				Ok(())
+			})?;
+
+			Self::deposit_event(Event::PriceUpdated(who, token.0, token.1, price));
+			Ok(().into())
+		}
+
+		/// Buy a listed token, paying in `asset`.
+		///
+		/// `asset` and `max_price` protect the buyer from the seller relisting the token or
+		/// raising the price while the transaction is pending.
+		#[pallet::weight(T::WeightInfo::buy())]
+		#[transactional]
+		pub fn buy(
+			origin: OriginFor<T>,
+			token: TokenIdOf<T>,
+			asset: Option<T::AssetId>,
+			max_price: BalanceOf<T>,
+		) -> DispatchResultWithPostInfo {
+			let buyer = ensure_signed(origin)?;
+			let listing = Listings::<T>::get(token).ok_or(Error::<T>::NotListed)?;
+			ensure!(listing.seller != buyer, Error::<T>::BuyerIsSeller);
+			ensure!(listing.asset == asset, Error::<T>::WrongAsset);
+			ensure!(listing.price <= max_price, Error::<T>::PriceTooHigh);
+			ensure!(!T::Moderation::is_frozen(&token.0), Error::<T>::ClassFrozen);
+
+			let royalty = T::Royalty::pay_royalty(&buyer, token, asset, listing.price)?;
+			T::Assets::transfer(
+				asset,
+				&buyer,
+				&listing.seller,
+				listing.price.saturating_sub(royalty),
+				ExistenceRequirement::KeepAlive,
+			)?;
+
+			Listings::<T>::remove(token);
+			orml_nft::Pallet::<T>::transfer(&Self::account_id(), &buyer, token)?;
+
+			Self::deposit_event(Event::Sold(
+				listing.seller,
+				buyer,
+				token.0,
+				token.1,
+				asset,
+				listing.price,
+				royalty,
+			));
+			Ok(().into())
+		}
+	}
+
+	impl<T: Config> Pallet<T> {
+		/// The account that escrows listed tokens.
+		pub fn account_id() -> T::AccountId {
+			T::PalletId::get().into_account()
+		}
+	}
+}
@@ -0,0 +1,163 @@
+//! A runtime with balances, assets, NFTs, royalties, moderation and the marketplace for tests.
+
+use crate as pallet_marketplace;
+use frame_support::traits::Currency;
+use frame_system::EnsureRoot;
+use sp_runtime::DispatchResult;
+use frame_support::{parameter_types, PalletId};
+use sp_core::H256;
+use sp_runtime::{
+	testing::Header,
+	traits::{BlakeTwo256, IdentityLookup},
+};
+
+type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Test>;
+type Block = frame_system::mocking::MockBlock<Test>;
+
+pub const ALICE: u64 = 1;
+pub const BOB: u64 = 2;
+pub const CHARLIE: u64 = 3;
+
+frame_support::construct_runtime!(
+	pub enum Test where
+		Block = Block,
+		NodeBlock = Block,
+		UncheckedExtrinsic = UncheckedExtrinsic,
+	{
+		System: frame_system::{Pallet, Call, Config, Storage, Event<T>},
+		Balances: pallet_balances::{Pallet, Call, Storage, Config<T>, Event<T>},
+		Assets: pallet_assets::{Pallet, Call, Storage, Event<T>},
+		Nft: orml_nft::{Pallet, Call, Storage, Config<T>},
+		Royalties: pallet_royalties::{Pallet, Call, Storage, Event<T>},
+		Moderation: pallet_moderation::{Pallet, Call, Storage, Event<T>},
+		Marketplace: pallet_marketplace::{Pallet, Call, Storage, Event<T>},
+	}
+);
+
+parameter_types! {
+	pub const BlockHashCount: u64 = 250;
+	pub const ExistentialDeposit: u64 = 1;
+	pub const AssetDeposit: u64 = 1;
+	pub const ApprovalDeposit: u64 = 1;
+	pub const StringLimit: u32 = 50;
+	pub const MetadataDepositBase: u64 = 1;
+	pub const MetadataDepositPerByte: u64 = 1;
+	pub const RoyaltiesPalletId: PalletId = PalletId(*b"chb/roya");
+	pub const MaxRoyaltyBasisPoints: u16 = 5_000;
+	pub const MarketplacePalletId: PalletId = PalletId(*b"chb/mrkt");
+}
+
+impl frame_system::Config for Test {
+	type BaseCallFilter = ();
+	type BlockWeights = ();
+	type BlockLength = ();
+	type DbWeight = ();
+	type Origin = Origin;
+	type Call = Call;
+	type Index = u64;
+	type BlockNumber = u64;
+	type Hash = H256;
+	type Hashing = BlakeTwo256;
+	type AccountId = u64;
+	type Lookup = IdentityLookup<Self::AccountId>;
+	type Header = Header;
+	type Event = Event;
+	type BlockHashCount = BlockHashCount;
+	type Version = ();
+	type PalletInfo = PalletInfo;
+	type AccountData = pallet_balances::AccountData<u64>;
+	type OnNewAccount = ();
+	type OnKilledAccount = ();
+	type SystemWeightInfo = ();
+	type SS58Prefix = ();
+}
+
+impl pallet_balances::Config for Test {
+	type MaxLocks = ();
+	type Balance = u64;
+	type Event = Event;
+	type DustRemoval = ();
+	type ExistentialDeposit = ExistentialDeposit;
+	type AccountStore = System;
+	type WeightInfo = ();
+}
+
+impl orml_nft::Config for Test {
+	type ClassId = u32;
+	type TokenId = u64;
+	type ClassData = ();
+	type TokenData = ();
+}
+
+impl pallet_assets::Config for Test {
+	type Event = Event;
+	type Balance = u64;
+	type AssetId = u32;
+	type Currency = Balances;
+	type ForceOrigin = EnsureRoot<u64>;
+	type AssetDeposit = AssetDeposit;
+	type MetadataDepositBase = MetadataDepositBase;
+	type MetadataDepositPerByte = MetadataDepositPerByte;
+	type ApprovalDeposit = ApprovalDeposit;
+	type StringLimit = StringLimit;
+	type Freezer = ();
+	type Extra = ();
+	type WeightInfo = ();
+}
+
+/// Pays in balances, or in assets of `pallet_assets`.
+pub struct MultiAssets;
+impl pallet_royalties::MultiAsset<u64, u32, u64> for MultiAssets {
+	fn transfer(
+		asset: Option<u32>,
+		from: &u64,
+		to: &u64,
+		amount: u64,
+		existence: frame_support::traits::ExistenceRequirement,
+	) -> DispatchResult {
+		match asset {
+			None => <Balances as Currency<u64>>::transfer(from, to, amount, existence),
+			Some(id) => Assets::transfer(Origin::signed(*from), id, *to, amount),
+		}
+	}
+}
+
+impl pallet_royalties::Config for Test {
+	type Event = Event;
+	type Currency = Balances;
+	type AssetId = u32;
+	type Assets = MultiAssets;
+	type Moderation = Moderation;
+	type PalletId = RoyaltiesPalletId;
+	type MaxRoyaltyBasisPoints = MaxRoyaltyBasisPoints;
+	type WeightInfo = ();
+}
+
+impl pallet_moderation::Config for Test {
+	type Event = Event;
+	type ModeratorOrigin = EnsureRoot<u64>;
+	type WeightInfo = ();
+}
+
+impl pallet_marketplace::Config for Test {
+	type Event = Event;
+	type Currency = Balances;
+	type AssetId = u32;
+	type Assets = MultiAssets;
+	type Royalty = Royalties;
+	type Moderation = Moderation;
+	type PalletId = MarketplacePalletId;
+	type WeightInfo = ();
+}
+
+/// Alice, Bob and Charlie start with 1,000 each.
+pub fn new_test_ext() -> sp_io::TestExternalities {
+	let mut storage = frame_system::GenesisConfig::default().build_storage::<Test>().unwrap();
+	pallet_balances::GenesisConfig::<Test> {
+		balances: vec![(ALICE, 1_000), (BOB, 1_000), (CHARLIE, 1_000)],
+	}.assimilate_storage(&mut storage).unwrap();
+
+	let mut ext = sp_io::TestExternalities::new(storage);
+	ext.execute_with(|| System::set_block_number(1));
+	ext
+}
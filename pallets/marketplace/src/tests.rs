@@ -0,0 +1,152 @@
+//! Tests for the marketplace pallet.
+
+use super::*;
+use crate::mock::*;
+use frame_support::{assert_noop, assert_ok};
+use pallet_royalties::testing_utils::{token, token_with_royalty};
+
+const ASSET: u32 = 0;
+
+/// Creates an asset and gives Bob 1,000 of it.
+fn asset() {
+	assert_ok!(Assets::force_create(Origin::root(), ASSET, ALICE, true, 1));
+	assert_ok!(Assets::mint(Origin::signed(ALICE), ASSET, BOB, 1_000));
+}
+
+#[test]
+fn listing_escrows_the_token() {
+	new_test_ext().execute_with(|| {
+		let token = token::<Test>(&ALICE).unwrap();
+		assert_ok!(Marketplace::list(Origin::signed(ALICE), token, 100, None));
+
+		assert!(Nft::is_owner(&Marketplace::account_id(), token));
+		assert_eq!(
+			Marketplace::listings(token),
+			Some(Listing { seller: ALICE, price: 100, asset: None }),
+		);
+	});
+}
+
+#[test]
+fn list_checks_the_token_and_price() {
+	new_test_ext().execute_with(|| {
+		let token = token::<Test>(&ALICE).unwrap();
+		assert_noop!(
+			Marketplace::list(Origin::signed(ALICE), token, 0, None),
+			Error::<Test>::ZeroPrice,
+		);
+		assert_noop!(
+			Marketplace::list(Origin::signed(BOB), token, 100, None),
+			Error::<Test>::NotTokenOwner,
+		);
+
+		assert_ok!(Marketplace::list(Origin::signed(ALICE), token, 100, None));
+		assert_noop!(
+			Marketplace::list(Origin::signed(ALICE), token, 100, None),
+			Error::<Test>::AlreadyListed,
+		);
+	});
+}
+
+#[test]
+fn buying_pays_the_seller_and_the_royalty() {
+	new_test_ext().execute_with(|| {
+		let token = token_with_royalty::<Test>(&ALICE, 1_000, CHARLIE).unwrap();
+		assert_ok!(Marketplace::list(Origin::signed(ALICE), token, 100, None));
+		assert_ok!(Marketplace::buy(Origin::signed(BOB), token, None, 100));
+
+		assert!(Nft::is_owner(&BOB, token));
+		assert_eq!(Marketplace::listings(token), None);
+		assert_eq!(Balances::free_balance(ALICE), 1_090);
+		assert_eq!(Balances::free_balance(BOB), 900);
+		assert_eq!(Balances::free_balance(CHARLIE), 1_010);
+	});
+}
+
+#[test]
+fn buying_in_an_asset_pays_in_the_asset() {
+	new_test_ext().execute_with(|| {
+		asset();
+		let token = token_with_royalty::<Test>(&ALICE, 1_000, CHARLIE).unwrap();
+		assert_ok!(Marketplace::list(Origin::signed(ALICE), token, 100, Some(ASSET)));
+		assert_ok!(Marketplace::buy(Origin::signed(BOB), token, Some(ASSET), 100));
+
+		assert!(Nft::is_owner(&BOB, token));
+		assert_eq!(Assets::balance(ASSET, ALICE), 90);
+		assert_eq!(Assets::balance(ASSET, BOB), 900);
+		assert_eq!(Assets::balance(ASSET, CHARLIE), 10);
+		assert_eq!(Balances::free_balance(BOB), 1_000);
+	});
+}
+
+#[test]
+fn buyers_pay_only_the_asset_and_price_they_agreed_to() {
+	new_test_ext().execute_with(|| {
+		asset();
+		let token = token::<Test>(&ALICE).unwrap();
+		assert_ok!(Marketplace::list(Origin::signed(ALICE), token, 100, None));
+
+		assert_noop!(
+			Marketplace::buy(Origin::signed(BOB), token, Some(ASSET), 100),
+			Error::<Test>::WrongAsset,
+		);
+		assert_ok!(Marketplace::update_price(Origin::signed(ALICE), token, 150));
+		assert_noop!(
+			Marketplace::buy(Origin::signed(BOB), token, None, 100),
+			Error::<Test>::PriceTooHigh,
+		);
+		assert_noop!(
+			Marketplace::buy(Origin::signed(ALICE), token, None, 150),
+			Error::<Test>::BuyerIsSeller,
+		);
+
+		assert_ok!(Marketplace::buy(Origin::signed(BOB), token, None, 200));
+		assert_eq!(Balances::free_balance(BOB), 850);
+	});
+}
+
+#[test]
+fn only_the_seller_changes_a_listing() {
+	new_test_ext().execute_with(|| {
+		let token = token::<Test>(&ALICE).unwrap();
+		assert_noop!(Marketplace::delist(Origin::signed(ALICE), token), Error::<Test>::NotListed);
+		assert_ok!(Marketplace::list(Origin::signed(ALICE), token, 100, None));
+
+		assert_noop!(Marketplace::delist(Origin::signed(BOB), token), Error::<Test>::NotSeller);
+		assert_noop!(
+			Marketplace::update_price(Origin::signed(BOB), token, 1),
+			Error::<Test>::NotSeller,
+		);
+		assert_noop!(
+			Marketplace::update_price(Origin::signed(ALICE), token, 0),
+			Error::<Test>::ZeroPrice,
+		);
+
+		assert_ok!(Marketplace::delist(Origin::signed(ALICE), token));
+		assert!(Nft::is_owner(&ALICE, token));
+		assert_eq!(Marketplace::listings(token), None);
+	});
+}
+
+#[test]
+fn frozen_classes_cannot_be_listed_or_bought() {
+	new_test_ext().execute_with(|| {
+		let listed = token::<Test>(&ALICE).unwrap();
+		let unlisted = (listed.0, Nft::mint(&ALICE, listed.0, vec![], ()).unwrap());
+		assert_ok!(Marketplace::list(Origin::signed(ALICE), listed, 100, None));
+		assert_ok!(Moderation::freeze_class(Origin::root(), listed.0));
+
+		assert_noop!(
+			Marketplace::list(Origin::signed(ALICE), unlisted, 100, None),
+			Error::<Test>::ClassFrozen,
+		);
+		assert_noop!(
+			Marketplace::buy(Origin::signed(BOB), listed, None, 100),
+			Error::<Test>::ClassFrozen,
+		);
+
+		// Sellers can still take their tokens back.
+		assert_ok!(Marketplace::delist(Origin::signed(ALICE), listed));
+		assert!(Nft::is_owner(&ALICE, listed));
+	});
+}
@@ -0,0 +1,34 @@
+//! Benchmarks for the media check pallet.
+
+#![cfg(feature = "runtime-benchmarks")]
+
+use super::*;
+use frame_benchmarking::{benchmarks, impl_benchmark_test_suite, whitelisted_caller};
+use frame_system::RawOrigin;
+use sp_runtime::traits::TrailingZeroInput;
+use sp_std::vec;
+
+/// A value of a type the benchmarks cannot construct, such as keys and signatures, decoded
+/// from zeroes. Signatures are only checked in `validate_unsigned`.
+fn zeroed<D: Decode>() -> D {
+	D::decode(&mut TrailingZeroInput::new(&[][..])).expect("input is infinite; qed")
+}
+
+benchmarks! {
+	report_media {
+		let owner: T::AccountId = whitelisted_caller();
+		let class_id = orml_nft::Pallet::<T>::create_class(&owner, vec![], zeroed())?;
+		let token_id = orml_nft::Pallet::<T>::mint(&owner, class_id, vec![], zeroed())?;
+		let report = MediaReport {
+			token: (class_id, token_id),
+			status: MediaStatus::Tampered,
+			checked_at: frame_system::Pallet::<T>::block_number(),
+			public: zeroed(),
+		};
+	}: _(RawOrigin::None, report, zeroed())
+	verify {
+		assert!(Flags::<T>::contains_key(class_id, token_id));
+	}
+}
+
+impl_benchmark_test_suite!(Pallet, crate::mock::new_test_ext(), crate::mock::Test);
@@ -0,0 +1,438 @@
+//! # Media Check Pallet
+//!
+//! An off-chain worker that checks the media of NFTs is still available and unchanged.
+//!
+//! Every `CheckInterval` blocks, validators walk through up to `MaxChecksPerRun` tokens whose
+//! metadata follows the Chiba Studio metadata standard, and fetch the IPFS block named by the
+//! media CID from an HTTP gateway. A block the gateway does not have is `Missing`; a block
+//! whose hash does not match the CID is `Tampered`. Changes of a token's status are reported
+//! on-chain with unsigned transactions, so clients can warn about broken media. Flags of burned
+//! tokens are cleared the same way.
+//!
+//! Reports carry a payload signed with a [`KEY_TYPE`] key, which is only accepted if its account
+//! is one of the `Validators`. Validators add their account's sr25519 key to the keystore
+//! under that key type.
+//!
+//! The gateway is read from the node's persistent off-chain storage under [`GATEWAY_KEY`],
+//! which `chiba-node --media-gateway` sets, and defaults to [`DEFAULT_GATEWAY`]. The worker
+//! requests `<gateway><cid>?format=raw` and hashes the response, so only the root block of
+//! chunked media is verified. Gateway errors and timeouts are inconclusive and report nothing.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use codec::{Decode, Encode};
+use frame_system::offchain::{
+	AppCrypto, SendTransactionTypes, SendUnsignedTransaction, SignedPayload, Signer, SigningTypes,
+};
+use sp_core::crypto::KeyTypeId;
+use sp_runtime::{
+	offchain::{http, storage::StorageValueRef, Duration, StorageKind},
+	traits::{IdentifyAccount, One, SaturatedConversion, Saturating, Zero},
+	RuntimeDebug,
+};
+use sp_std::prelude::*;
+
+pub use pallet::*;
+pub use weights::WeightInfo;
+
+mod benchmarking;
+#[cfg(test)]
+mod mock;
+#[cfg(test)]
+mod tests;
+pub mod weights;
+
+pub type TokenIdOf<T> = (<T as orml_nft::Config>::ClassId, <T as orml_nft::Config>::TokenId);
+
+/// The key type of the keys that sign reports.
+pub const KEY_TYPE: KeyTypeId = KeyTypeId(*b"chmc");
+
+/// The sr25519 keys that sign reports.
+pub mod crypto {
+	use super::KEY_TYPE;
+	use sp_runtime::{
+		app_crypto::{app_crypto, sr25519},
+		MultiSignature, MultiSigner,
+	};
+
+	app_crypto!(sr25519, KEY_TYPE);
+
+	/// Signs reports for runtimes whose accounts are `MultiSigner`s.
+	pub struct MediaCheckAuthId;
+	impl frame_system::offchain::AppCrypto<MultiSigner, MultiSignature> for MediaCheckAuthId {
+		type RuntimeAppPublic = Public;
+		type GenericSignature = sp_core::sr25519::Signature;
+		type GenericPublic = sp_core::sr25519::Public;
+	}
+}
+
+/// The off-chain storage key of the gateway URL, stored as raw UTF-8.
+pub const GATEWAY_KEY: &[u8] = b"chiba::media-gateway";
+
+/// The gateway used when none is configured: a local IPFS daemon.
+pub const DEFAULT_GATEWAY: &[u8] = b"http://127.0.0.1:8080/ipfs/";
+
+/// The off-chain storage key of the next token to check.
+const CURSOR_KEY: &[u8] = b"chiba::media-check::cursor";
+
+/// How long to wait for the gateway, in milliseconds.
+const HTTP_TIMEOUT_MS: u64 = 5_000;
+
+/// The largest response read. IPFS blocks are at most 2 MiB, so anything longer cannot match.
+const MAX_BLOCK_SIZE: usize = 2 * 1024 * 1024;
+
+/// What a check found out about the media of a token.
+#[derive(Clone, Copy, Eq, PartialEq, Encode, Decode, RuntimeDebug)]
+pub enum MediaStatus {
+	/// The gateway served content matching the CID.
+	Available,
+	/// The gateway does not have the content.
+	Missing,
+	/// The gateway served content that does not match the CID.
+	Tampered,
+}
+
+/// The result of a check, signed by the validator that made it.
+#[derive(Clone, Eq, PartialEq, Encode, Decode, RuntimeDebug)]
+pub struct MediaReport<Public, ClassId, TokenId, BlockNumber> {
+	/// The token checked.
+	pub token: (ClassId, TokenId),
+	/// What the check found. Reports of burned tokens clear their flag whatever their status.
+	pub status: MediaStatus,
+	/// The block whose off-chain worker made the check.
+	pub checked_at: BlockNumber,
+	/// The key that signed the report.
+	pub public: Public,
+}
+
+pub type MediaReportOf<T> = MediaReport<
+	<T as SigningTypes>::Public,
+	<T as orml_nft::Config>::ClassId,
+	<T as orml_nft::Config>::TokenId,
+	<T as frame_system::Config>::BlockNumber,
+>;
+
+impl<T: Config> SignedPayload<T> for MediaReportOf<T> {
+	fn public(&self) -> T::Public {
+		self.public.clone()
+	}
+}
+
+/// A problem with the media of a token, as last reported.
+#[derive(Clone, Eq, PartialEq, Encode, Decode, RuntimeDebug)]
+pub struct MediaFlag<BlockNumber> {
+	/// Whether the media is `Missing` or `Tampered`.
+	pub status: MediaStatus,
+	/// The block in which the problem was found.
+	pub checked_at: BlockNumber,
+}
+
+#[frame_support::pallet]
+pub mod pallet {
+	use frame_support::{dispatch::DispatchResultWithPostInfo, pallet_prelude::*};
+	use frame_system::pallet_prelude::*;
+	use super::*;
+
+	#[pallet::config]
+	pub trait Config:
+		frame_system::Config + orml_nft::Config + SendTransactionTypes<Call<Self>> + SigningTypes
+	{
+		/// Because this pallet emits events, it depends on the runtime's definition of an event.
+		type Event: From<Event<Self>> + IsType<<Self as frame_system::Config>::Event>;
+
+		/// The keys that sign reports.
+		type AuthorityId: AppCrypto<Self::Public, Self::Signature>;
+
+		/// The accounts whose keys may sign reports.
+		type Validators: Get<Vec<Self::AccountId>>;
+
+		/// The number of blocks between runs of the off-chain worker. Reports older than this
+		/// are rejected.
+		#[pallet::constant]
+		type CheckInterval: Get<Self::BlockNumber>;
+
+		/// The most tokens checked in one run.
+		#[pallet::constant]
+		type MaxChecksPerRun: Get<u32>;
+
+		/// The priority of the unsigned transactions reporting checks.
+		#[pallet::constant]
+		type UnsignedPriority: Get<TransactionPriority>;
+
+		/// Weight information for extrinsics in this pallet.
+		type WeightInfo: WeightInfo;
+	}
+
+	#[pallet::pallet]
+	#[pallet::generate_store(pub(super) trait Store)]
+	pub struct Pallet<T>(_);
+
+	/// The tokens whose media was last found missing or tampered with.
+	#[pallet::storage]
+	#[pallet::getter(fn flags)]
+	pub type Flags<T: Config> = StorageDoubleMap<
+		_,
+		Twox64Concat,
+		T::ClassId,
+		Twox64Concat,
+		T::TokenId,
+		MediaFlag<T::BlockNumber>,
+	>;
+
+	#[pallet::event]
+	#[pallet::metadata(T::ClassId = "ClassId", T::TokenId = "TokenId")]
+	#[pallet::generate_deposit(pub(super) fn deposit_event)]
+	pub enum Event<T: Config> {
+		/// The media of a token is missing or was tampered with. \[class_id, token_id, status\]
+		MediaFlagged(T::ClassId, T::TokenId, MediaStatus),
+		/// The media of a flagged token is available again. \[class_id, token_id\]
+		MediaRestored(T::ClassId, T::TokenId),
+		/// The flag of a burned token was removed. \[class_id, token_id\]
+		FlagCleared(T::ClassId, T::TokenId),
+	}
+
+	#[pallet::error]
+	pub enum Error<T> {
+		/// The token does not exist and is not flagged.
+		TokenNotFound,
+		/// The report does not change the status of the token.
+		StatusUnchanged,
+	}
+
+	#[pallet::hooks]
+	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+		fn offchain_worker(now: T::BlockNumber) {
+			let interval = T::CheckInterval::get();
+			if interval.is_zero() || !(now % interval).is_zero() {
+				return
+			}
+			let signer = Signer::<T, T::AuthorityId>::any_account();
+			if !signer.can_sign() {
+				log::debug!(target: "runtime::media-check", "No key to sign reports with");
+				return
+			}
+			Self::check_media(&signer, now);
+		}
+	}
+
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {
+		/// Record the result of a media check made by the off-chain worker of a validator.
+		///
+		/// The dispatch origin must be none: this is only submitted as an unsigned transaction,
+		/// whose `signature` of `report` is checked in `validate_unsigned`.
+		#[pallet::weight(T::WeightInfo::report_media())]
+		pub fn report_media(
+			origin: OriginFor<T>,
+			report: MediaReportOf<T>,
+			_signature: T::Signature,
+		) -> DispatchResultWithPostInfo {
+			ensure_none(origin)?;
+			let (class_id, token_id) = report.token;
+			let flagged = Flags::<T>::get(class_id, token_id).map(|flag| flag.status);
+
+			if !orml_nft::Tokens::<T>::contains_key(class_id, token_id) {
+				ensure!(flagged.is_some(), Error::<T>::TokenNotFound);
+				Flags::<T>::remove(class_id, token_id);
+				Self::deposit_event(Event::FlagCleared(class_id, token_id));
+				return Ok(().into())
+			}
+
+			let checked_at = report.checked_at;
+			match report.status {
+				MediaStatus::Available => {
+					ensure!(flagged.is_some(), Error::<T>::StatusUnchanged);
+					Flags::<T>::remove(class_id, token_id);
+					Self::deposit_event(Event::MediaRestored(class_id, token_id));
+				},
+				problem => {
+					ensure!(flagged != Some(problem), Error::<T>::StatusUnchanged);
+					Flags::<T>::insert(class_id, token_id, MediaFlag { status: problem, checked_at });
+					Self::deposit_event(Event::MediaFlagged(class_id, token_id, problem));
+				},
+			}
+			Ok(().into())
+		}
+	}
+
+	#[pallet::validate_unsigned]
+	impl<T: Config> ValidateUnsigned for Pallet<T> {
+		type Call = Call<T>;
+
+		fn validate_unsigned(_source: TransactionSource, call: &Self::Call) -> TransactionValidity {
+			let (report, signature) = match call {
+				Call::report_media(report, signature) => (report, signature),
+				_ => return InvalidTransaction::Call.into(),
+			};
+
+			if !SignedPayload::<T>::verify::<T::AuthorityId>(report, signature.clone()) {
+				return InvalidTransaction::BadProof.into()
+			}
+			if !T::Validators::get().contains(&report.public.clone().into_account()) {
+				return InvalidTransaction::BadSigner.into()
+			}
+
+			let checked_at = report.checked_at;
+			let now = frame_system::Pallet::<T>::block_number();
+			if checked_at > now {
+				return InvalidTransaction::Future.into()
+			}
+			if now.saturating_sub(checked_at) > T::CheckInterval::get() {
+				return InvalidTransaction::Stale.into()
+			}
+
+			ValidTransaction::with_tag_prefix("ChibaMediaCheck")
+				.priority(T::UnsignedPriority::get())
+				.and_provides((report.token, checked_at))
+				.longevity(T::CheckInterval::get().saturated_into::<u64>())
+				.propagate(true)
+				.build()
+		}
+	}
+}
+
+impl<T: Config> Pallet<T> {
+	/// Checks the next batch of tokens and reports those whose status changed, as well as flagged
+	/// tokens that were burned.
+	fn check_media(signer: &Signer<T, T::AuthorityId>, now: T::BlockNumber) {
+		let gateway = Self::gateway();
+		let cursor = StorageValueRef::persistent(CURSOR_KEY);
+		let (mut class_id, mut token_id) = cursor.get::<TokenIdOf<T>>()
+			.flatten()
+			.unwrap_or_default();
+
+		let next_class_id = orml_nft::Pallet::<T>::next_class_id();
+		let max_checks = T::MaxChecksPerRun::get();
+		let mut checks = 0;
+		// Burned tokens and classes leave gaps, so the walk is bounded as well as the checks.
+		let mut steps = 0;
+		while checks < max_checks && steps < max_checks.saturating_mul(10) {
+			steps += 1;
+			if class_id >= next_class_id {
+				class_id = Zero::zero();
+				token_id = Zero::zero();
+				break
+			}
+			if token_id >= orml_nft::Pallet::<T>::next_token_id(class_id) {
+				class_id += One::one();
+				token_id = Zero::zero();
+				continue
+			}
+
+			let token = (class_id, token_id);
+			token_id += One::one();
+			let flagged = Flags::<T>::get(token.0, token.1).map(|flag| flag.status);
+			let metadata = match orml_nft::Pallet::<T>::tokens(token.0, token.1) {
+				Some(info) => info.metadata,
+				None => {
+					if flagged.is_some() {
+						Self::report(signer, token, MediaStatus::Missing, now);
+					}
+					continue
+				},
+			};
+			let media = match chiba_nft_metadata::parse(&metadata) {
+				Ok(metadata) => metadata.media,
+				Err(_) => continue,
+			};
+
+			checks += 1;
+			let status = match Self::fetch_and_verify(&gateway, &media) {
+				Ok(status) => status,
+				Err(e) => {
+					log::debug!(target: "runtime::media-check", "Inconclusive check of {:?}: {}", token, e);
+					continue
+				},
+			};
+
+			let changed = match status {
+				MediaStatus::Available => flagged.is_some(),
+				problem => flagged != Some(problem),
+			};
+			if changed {
+				Self::report(signer, token, status, now);
+			}
+		}
+
+		cursor.set(&(class_id, token_id));
+	}
+
+	/// Submits a report signed with the first of this node's keys.
+	fn report(
+		signer: &Signer<T, T::AuthorityId>,
+		token: TokenIdOf<T>,
+		status: MediaStatus,
+		checked_at: T::BlockNumber,
+	) {
+		let result = signer.send_unsigned_transaction(
+			|account| MediaReport { token, status, checked_at, public: account.public.clone() },
+			|report, signature| Call::report_media(report, signature),
+		);
+		if !matches!(result, Some((_, Ok(())))) {
+			log::warn!(target: "runtime::media-check", "Failed to report media of {:?}", token);
+		}
+	}
+
+	/// The configured gateway URL, ending with a slash.
+	fn gateway() -> Vec<u8> {
+		let mut gateway = sp_io::offchain::local_storage_get(StorageKind::PERSISTENT, GATEWAY_KEY)
+			.filter(|gateway| !gateway.is_empty())
+			.unwrap_or_else(|| DEFAULT_GATEWAY.to_vec());
+		if !gateway.ends_with(b"/") {
+			gateway.push(b'/');
+		}
+		gateway
+	}
+
+	/// Fetches the block named by `cid` and compares its hash with the CID.
+	fn fetch_and_verify(gateway: &[u8], cid: &[u8]) -> Result<MediaStatus, &'static str> {
+		let multihash = chiba_nft_metadata::cid::multihash(cid).ok_or("invalid CID")?;
+		let hash: fn(&[u8]) -> [u8; 32] = match multihash.code {
+			chiba_nft_metadata::cid::SHA2_256 => sp_io::hashing::sha2_256,
+			chiba_nft_metadata::cid::BLAKE2B_256 => sp_io::hashing::blake2_256,
+			_ => return Err("unsupported hash function"),
+		};
+		if multihash.digest.len() != 32 {
+			return Err("unsupported digest length")
+		}
+
+		let mut url = gateway.to_vec();
+		url.extend_from_slice(cid);
+		url.extend_from_slice(b"?format=raw");
+		let url = sp_std::str::from_utf8(&url).map_err(|_| "gateway URL is not UTF-8")?;
+
+		let deadline = sp_io::offchain::timestamp().add(Duration::from_millis(HTTP_TIMEOUT_MS));
+		let pending = http::Request::get(url)
+			.add_header("Accept", "application/vnd.ipld.raw")
+			.deadline(deadline)
+			.send()
+			.map_err(|_| "cannot send request")?;
+		let response = pending.try_wait(deadline)
+			.map_err(|_| "gateway timed out")?
+			.map_err(|_| "request failed")?;
+
+		match response.code {
+			200 => (),
+			404 | 410 => return Ok(MediaStatus::Missing),
+			_ => return Err("unexpected gateway response"),
+		}
+
+		let mut body = Vec::new();
+		let mut chunks = response.body();
+		for byte in chunks.by_ref() {
+			if body.len() == MAX_BLOCK_SIZE {
+				return Ok(MediaStatus::Tampered)
+			}
+			body.push(byte);
+		}
+		if chunks.error().is_some() {
+			return Err("cannot read response")
+		}
+
+		if hash(&body)[..] == multihash.digest[..] {
+			Ok(MediaStatus::Available)
+		} else {
+			Ok(MediaStatus::Tampered)
+		}
+	}
+}
@@ -0,0 +1,110 @@
+//! A runtime with NFTs and media checks for tests, whose accounts are sr25519 keys so that
+//! reports can be signed.
+
+use crate as pallet_media_check;
+use frame_support::parameter_types;
+use sp_core::{sr25519, Pair, H256};
+use sp_runtime::{
+	testing::{Header, TestXt},
+	traits::{BlakeTwo256, IdentifyAccount, IdentityLookup, Verify},
+	MultiSignature, MultiSigner,
+};
+
+type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Test>;
+type Block = frame_system::mocking::MockBlock<Test>;
+pub type AccountId = <<MultiSignature as Verify>::Signer as IdentifyAccount>::AccountId;
+
+frame_support::construct_runtime!(
+	pub enum Test where
+		Block = Block,
+		NodeBlock = Block,
+		UncheckedExtrinsic = UncheckedExtrinsic,
+	{
+		System: frame_system::{Pallet, Call, Config, Storage, Event<T>},
+		Nft: orml_nft::{Pallet, Call, Storage, Config<T>},
+		MediaCheck: pallet_media_check::{Pallet, Call, Storage, Event<T>, ValidateUnsigned},
+	}
+);
+
+/// The key of Alice, the only validator.
+pub fn alice() -> sr25519::Pair {
+	sr25519::Pair::from_seed(&[1; 32])
+}
+
+/// The key of Bob, who is not a validator.
+pub fn bob() -> sr25519::Pair {
+	sr25519::Pair::from_seed(&[2; 32])
+}
+
+pub fn account(pair: &sr25519::Pair) -> AccountId {
+	MultiSigner::from(pair.public()).into_account()
+}
+
+parameter_types! {
+	pub const BlockHashCount: u64 = 250;
+	pub const CheckInterval: u64 = 10;
+	pub const MaxChecksPerRun: u32 = 5;
+	pub const UnsignedPriority: u64 = 1 << 20;
+	pub Validators: Vec<AccountId> = vec![account(&alice())];
+}
+
+impl frame_system::Config for Test {
+	type BaseCallFilter = ();
+	type BlockWeights = ();
+	type BlockLength = ();
+	type DbWeight = ();
+	type Origin = Origin;
+	type Call = Call;
+	type Index = u64;
+	type BlockNumber = u64;
+	type Hash = H256;
+	type Hashing = BlakeTwo256;
+	type AccountId = AccountId;
+	type Lookup = IdentityLookup<Self::AccountId>;
+	type Header = Header;
+	type Event = Event;
+	type BlockHashCount = BlockHashCount;
+	type Version = ();
+	type PalletInfo = PalletInfo;
+	type AccountData = ();
+	type OnNewAccount = ();
+	type OnKilledAccount = ();
+	type SystemWeightInfo = ();
+	type SS58Prefix = ();
+}
+
+impl orml_nft::Config for Test {
+	type ClassId = u32;
+	type TokenId = u64;
+	type ClassData = ();
+	type TokenData = ();
+}
+
+impl frame_system::offchain::SigningTypes for Test {
+	type Public = MultiSigner;
+	type Signature = MultiSignature;
+}
+
+impl<C> frame_system::offchain::SendTransactionTypes<C> for Test where
+	Call: From<C>,
+{
+	type OverarchingCall = Call;
+	type Extrinsic = TestXt<Call, ()>;
+}
+
+impl pallet_media_check::Config for Test {
+	type Event = Event;
+	type AuthorityId = pallet_media_check::crypto::MediaCheckAuthId;
+	type Validators = Validators;
+	type CheckInterval = CheckInterval;
+	type MaxChecksPerRun = MaxChecksPerRun;
+	type UnsignedPriority = UnsignedPriority;
+	type WeightInfo = ();
+}
+
+pub fn new_test_ext() -> sp_io::TestExternalities {
+	let storage = frame_system::GenesisConfig::default().build_storage::<Test>().unwrap();
+	let mut ext = sp_io::TestExternalities::new(storage);
+	ext.execute_with(|| System::set_block_number(1));
+	ext
+}
@@ -0,0 +1,146 @@
+//! Tests for the media check pallet.
+
+use super::*;
+use crate::mock::*;
+// The pallet's calls, rather than the mock runtime's.
+use super::Call;
+use frame_support::{
+	assert_noop, assert_ok, dispatch::DispatchResultWithPostInfo, unsigned::ValidateUnsigned,
+};
+use sp_core::{sr25519, Pair};
+use sp_runtime::{
+	transaction_validity::{InvalidTransaction, TransactionSource},
+	DispatchError, MultiSignature, MultiSigner,
+};
+
+fn report(
+	pair: &sr25519::Pair,
+	token: (u32, u64),
+	status: MediaStatus,
+	checked_at: u64,
+) -> MediaReportOf<Test> {
+	MediaReport { token, status, checked_at, public: MultiSigner::from(pair.public()) }
+}
+
+fn sign(pair: &sr25519::Pair, report: &MediaReportOf<Test>) -> MultiSignature {
+	MultiSignature::from(pair.sign(&report.encode()))
+}
+
+/// Validates and dispatches a report of Alice's, as a block would.
+fn submit(token: (u32, u64), status: MediaStatus) -> DispatchResultWithPostInfo {
+	let report = report(&alice(), token, status, System::block_number());
+	let signature = sign(&alice(), &report);
+	let call = Call::report_media(report.clone(), signature.clone());
+	assert!(MediaCheck::validate_unsigned(TransactionSource::External, &call).is_ok());
+	MediaCheck::report_media(Origin::none(), report, signature)
+}
+
+fn token() -> (u32, u64) {
+	let owner = account(&bob());
+	let class_id = Nft::create_class(&owner, vec![], ()).unwrap();
+	(class_id, Nft::mint(&owner, class_id, vec![], ()).unwrap())
+}
+
+#[test]
+fn only_reports_signed_by_validators_are_valid() {
+	new_test_ext().execute_with(|| {
+		let validate = |report: MediaReportOf<Test>, signature| {
+			MediaCheck::validate_unsigned(
+				TransactionSource::External,
+				&Call::report_media(report, signature),
+			)
+		};
+		let token = token();
+
+		let by_alice = report(&alice(), token, MediaStatus::Tampered, 1);
+		assert!(validate(by_alice.clone(), sign(&alice(), &by_alice)).is_ok());
+
+		let by_bob = report(&bob(), token, MediaStatus::Tampered, 1);
+		assert_eq!(
+			validate(by_bob.clone(), sign(&bob(), &by_bob)),
+			InvalidTransaction::BadSigner.into()
+		);
+		assert_eq!(
+			validate(by_alice.clone(), sign(&bob(), &by_alice)),
+			InvalidTransaction::BadProof.into()
+		);
+
+		let altered = MediaReport { status: MediaStatus::Missing, ..by_alice.clone() };
+		assert_eq!(
+			validate(altered, sign(&alice(), &by_alice)),
+			InvalidTransaction::BadProof.into()
+		);
+	});
+}
+
+#[test]
+fn reports_must_be_recent() {
+	new_test_ext().execute_with(|| {
+		let token = token();
+		System::set_block_number(20);
+		let validate = |checked_at| {
+			let report = report(&alice(), token, MediaStatus::Missing, checked_at);
+			let signature = sign(&alice(), &report);
+			MediaCheck::validate_unsigned(
+				TransactionSource::External,
+				&Call::report_media(report, signature),
+			)
+		};
+
+		assert!(validate(20).is_ok());
+		assert!(validate(10).is_ok());
+		assert_eq!(validate(9), InvalidTransaction::Stale.into());
+		assert_eq!(validate(21), InvalidTransaction::Future.into());
+	});
+}
+
+#[test]
+fn reports_flag_and_restore_tokens() {
+	new_test_ext().execute_with(|| {
+		let token = token();
+		assert_noop!(submit(token, MediaStatus::Available), Error::<Test>::StatusUnchanged);
+
+		assert_ok!(submit(token, MediaStatus::Missing));
+		assert_eq!(
+			MediaCheck::flags(token.0, token.1),
+			Some(MediaFlag { status: MediaStatus::Missing, checked_at: 1 })
+		);
+		assert_noop!(submit(token, MediaStatus::Missing), Error::<Test>::StatusUnchanged);
+
+		System::set_block_number(2);
+		assert_ok!(submit(token, MediaStatus::Tampered));
+		assert_eq!(
+			MediaCheck::flags(token.0, token.1),
+			Some(MediaFlag { status: MediaStatus::Tampered, checked_at: 2 })
+		);
+
+		assert_ok!(submit(token, MediaStatus::Available));
+		assert_eq!(MediaCheck::flags(token.0, token.1), None);
+	});
+}
+
+#[test]
+fn flags_of_burned_tokens_are_cleared() {
+	new_test_ext().execute_with(|| {
+		let token = token();
+		assert_ok!(submit(token, MediaStatus::Tampered));
+
+		assert_ok!(Nft::burn(&account(&bob()), token));
+		assert_ok!(submit(token, MediaStatus::Missing));
+		assert_eq!(MediaCheck::flags(token.0, token.1), None);
+
+		assert_noop!(submit(token, MediaStatus::Missing), Error::<Test>::TokenNotFound);
+	});
+}
+
+#[test]
+fn reports_must_be_unsigned_transactions() {
+	new_test_ext().execute_with(|| {
+		let report = report(&alice(), token(), MediaStatus::Missing, 1);
+		let signature = sign(&alice(), &report);
+		assert_noop!(
+			MediaCheck::report_media(Origin::signed(account(&alice())), report, signature),
+			DispatchError::BadOrigin
+		);
+	});
+}
@@ -0,0 +1,42 @@
+//! Benchmarks for the moderation pallet.
+
+#![cfg(feature = "runtime-benchmarks")]
+
+use super::*;
+use codec::Decode;
+use frame_benchmarking::{account, benchmarks, impl_benchmark_test_suite};
+use frame_support::traits::EnsureOrigin;
+use sp_runtime::traits::TrailingZeroInput;
+use sp_std::vec;
+
+const SEED: u32 = 0;
+
+/// Creates a class with data decoded from zeroes, since the runtime's class data cannot be
+/// constructed here.
+fn class<T: Config>() -> Result<T::ClassId, &'static str> {
+	let owner: T::AccountId = account("owner", 0, SEED);
+	let data = T::ClassData::decode(&mut TrailingZeroInput::new(&[][..]))
+		.expect("input is infinite; qed");
+	Ok(orml_nft::Pallet::<T>::create_class(&owner, vec![], data)?)
+}
+
+benchmarks! {
+	freeze_class {
+		let class_id = class::<T>()?;
+		let origin = T::ModeratorOrigin::successful_origin();
+	}: _<T::Origin>(origin, class_id)
+	verify {
+		assert!(Pallet::<T>::is_frozen(&class_id));
+	}
+
+	thaw_class {
+		let class_id = class::<T>()?;
+		let origin = T::ModeratorOrigin::successful_origin();
+		Pallet::<T>::freeze_class(origin.clone(), class_id).map_err(|_| "cannot freeze class")?;
+	}: _<T::Origin>(origin, class_id)
+	verify {
+		assert!(!Pallet::<T>::is_frozen(&class_id));
+	}
+}
+
+impl_benchmark_test_suite!(Pallet, crate::mock::new_test_ext(), crate::mock::Test);
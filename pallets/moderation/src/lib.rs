@@ -0,0 +1,116 @@
+//! # Moderation Pallet
+//!
+//! Lets governance freeze NFT classes that break the platform's content rules. Tokens of a
+//! frozen class keep their owners, but cannot be listed, auctioned or bought through the
+//! Chiba Studio trading pallets until the class is thawed.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+pub use pallet::*;
+pub use weights::WeightInfo;
+
+mod benchmarking;
+#[cfg(test)]
+mod mock;
+#[cfg(test)]
+mod tests;
+pub mod weights;
+
+/// Tells trading pallets whether a class has been frozen by moderators.
+pub trait ClassModeration<ClassId> {
+	/// Whether tokens of `class_id` may not be traded.
+	fn is_frozen(class_id: &ClassId) -> bool;
+}
+
+impl<ClassId> ClassModeration<ClassId> for () {
+	fn is_frozen(_: &ClassId) -> bool {
+		false
+	}
+}
+
+#[frame_support::pallet]
+pub mod pallet {
+	use frame_support::{dispatch::DispatchResultWithPostInfo, pallet_prelude::*};
+	use frame_system::pallet_prelude::*;
+	use super::*;
+
+	#[pallet::config]
+	pub trait Config: frame_system::Config + orml_nft::Config {
+		/// Because this pallet emits events, it depends on the runtime's definition of an event.
+		type Event: From<Event<Self>> + IsType<<Self as frame_system::Config>::Event>;
+
+		/// The origin allowed to freeze and thaw classes.
+		type ModeratorOrigin: EnsureOrigin<Self::Origin>;
+
+		/// Weight information for extrinsics in this pallet.
+		type WeightInfo: WeightInfo;
+	}
+
+	#[pallet::pallet]
+	#[pallet::generate_store(pub(super) trait Store)]
+	pub struct Pallet<T>(_);
+
+	/// The classes that are currently frozen.
+	#[pallet::storage]
+	#[pallet::getter(fn frozen_classes)]
+	pub type FrozenClasses<T: Config> = StorageMap<_, Twox64Concat, T::ClassId, ()>;
+
+	#[pallet::event]
+	#[pallet::metadata(T::ClassId = "ClassId")]
+	#[pallet::generate_deposit(pub(super) fn deposit_event)]
+	pub enum Event<T: Config> {
+		/// A class was frozen. \[class_id\]
+		ClassFrozen(T::ClassId),
+		/// A class was thawed. \[class_id\]
+		ClassThawed(T::ClassId),
+	}
+
+	#[pallet::error]
+	pub enum Error<T> {
+		/// The class does not exist.
+		ClassNotFound,
+		/// The class is already frozen.
+		AlreadyFrozen,
+		/// The class is not frozen.
+		NotFrozen,
+	}
+
+	#[pallet::hooks]
+	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {}
+
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {
+		/// Stop all trading of tokens of `class_id`.
+		///
+		/// The dispatch origin must be `ModeratorOrigin`.
+		#[pallet::weight(T::WeightInfo::freeze_class())]
+		pub fn freeze_class(origin: OriginFor<T>, class_id: T::ClassId) -> DispatchResultWithPostInfo {
+			T::ModeratorOrigin::ensure_origin(origin)?;
+			ensure!(orml_nft::Classes::<T>::contains_key(class_id), Error::<T>::ClassNotFound);
+			ensure!(!FrozenClasses::<T>::contains_key(class_id), Error::<T>::AlreadyFrozen);
+
+			FrozenClasses::<T>::insert(class_id, ());
+			Self::deposit_event(Event::ClassFrozen(class_id));
+			Ok(().into())
+		}
+
+		/// Allow trading of tokens of `class_id` again.
+		///
+		/// The dispatch origin must be `ModeratorOrigin`.
+		#[pallet::weight(T::WeightInfo::thaw_class())]
+		pub fn thaw_class(origin: OriginFor<T>, class_id: T::ClassId) -> DispatchResultWithPostInfo {
+			T::ModeratorOrigin::ensure_origin(origin)?;
+			ensure!(FrozenClasses::<T>::contains_key(class_id), Error::<T>::NotFrozen);
+
+			FrozenClasses::<T>::remove(class_id);
+			Self::deposit_event(Event::ClassThawed(class_id));
+			Ok(().into())
+		}
+	}
+}
+
+impl<T: Config> ClassModeration<T::ClassId> for Pallet<T> {
+	fn is_frozen(class_id: &T::ClassId) -> bool {
+		FrozenClasses::<T>::contains_key(class_id)
+	}
+}
@@ -0,0 +1,58 @@
+//! Tests for the moderation pallet.
+
+use super::*;
+use crate::mock::*;
+use frame_support::{assert_noop, assert_ok};
+use sp_runtime::DispatchError;
+
+fn class() -> u32 {
+	Nft::create_class(&ALICE, vec![], ()).unwrap()
+}
+
+#[test]
+fn classes_can_be_frozen_and_thawed() {
+	new_test_ext().execute_with(|| {
+		let class_id = class();
+		assert!(!Moderation::is_frozen(&class_id));
+
+		assert_ok!(Moderation::freeze_class(Origin::root(), class_id));
+		assert!(Moderation::is_frozen(&class_id));
+		assert_eq!(Moderation::frozen_classes(class_id), Some(()));
+
+		assert_ok!(Moderation::thaw_class(Origin::root(), class_id));
+		assert!(!Moderation::is_frozen(&class_id));
+	});
+}
+
+#[test]
+fn only_moderators_can_freeze_and_thaw() {
+	new_test_ext().execute_with(|| {
+		let class_id = class();
+		assert_noop!(
+			Moderation::freeze_class(Origin::signed(ALICE), class_id),
+			DispatchError::BadOrigin
+		);
+
+		assert_ok!(Moderation::freeze_class(Origin::root(), class_id));
+		assert_noop!(
+			Moderation::thaw_class(Origin::signed(ALICE), class_id),
+			DispatchError::BadOrigin
+		);
+	});
+}
+
+#[test]
+fn freezing_and_thawing_are_checked() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(Moderation::freeze_class(Origin::root(), 0), Error::<Test>::ClassNotFound);
+
+		let class_id = class();
+		assert_noop!(Moderation::thaw_class(Origin::root(), class_id), Error::<Test>::NotFrozen);
+
+		assert_ok!(Moderation::freeze_class(Origin::root(), class_id));
+		assert_noop!(
+			Moderation::freeze_class(Origin::root(), class_id),
+			Error::<Test>::AlreadyFrozen
+		);
+	});
+}
@@ -0,0 +1,66 @@
+//! Benchmarks for the NFT batch pallet.
+
+#![cfg(feature = "runtime-benchmarks")]
+
+use super::*;
+use frame_benchmarking::{account, benchmarks, impl_benchmark_test_suite, whitelisted_caller};
+use frame_support::traits::Get;
+use frame_system::RawOrigin;
+use pallet_royalties::testing_utils::{largest_metadata, zeroed};
+use sp_runtime::traits::StaticLookup;
+use sp_std::{vec, vec::Vec};
+
+const SEED: u32 = 0;
+
+/// Mints `n` tokens of a new class to `owner`.
+fn tokens<T: Config>(owner: &T::AccountId, n: u32) -> Result<Vec<TokenIdOf<T>>, &'static str> {
+	let class_id = orml_nft::Pallet::<T>::create_class(owner, vec![0; 64], zeroed())?;
+	(0 .. n)
+		.map(|_| {
+			orml_nft::Pallet::<T>::mint(owner, class_id, vec![0; 64], zeroed())
+				.map(|token_id| (class_id, token_id))
+				.map_err(|_| "cannot mint token")
+		})
+		.collect()
+}
+
+benchmarks! {
+	batch_mint {
+		let n in 1 .. T::MaxBatchSize::get();
+
+		let caller: T::AccountId = whitelisted_caller();
+		let class_id = orml_nft::Pallet::<T>::create_class(&caller, vec![0; 64], zeroed())?;
+		let batch: Vec<_> = (0 .. n).map(|_| (largest_metadata(), zeroed())).collect();
+		let owner = T::Lookup::unlookup(caller.clone());
+	}: _(RawOrigin::Signed(caller), class_id, owner, batch)
+	verify {
+		assert_eq!(orml_nft::Pallet::<T>::classes(class_id).unwrap().total_issuance, n.into());
+	}
+
+	batch_transfer {
+		let n in 1 .. T::MaxBatchSize::get();
+
+		let caller: T::AccountId = whitelisted_caller();
+		let tokens = tokens::<T>(&caller, n)?;
+		let batch: Vec<_> = tokens
+			.iter()
+			.enumerate()
+			.map(|(i, token)| (T::Lookup::unlookup(account("recipient", i as u32, SEED)), *token))
+			.collect();
+	}: _(RawOrigin::Signed(caller.clone()), batch)
+	verify {
+		assert!(tokens.iter().all(|token| !orml_nft::Pallet::<T>::is_owner(&caller, *token)));
+	}
+
+	batch_burn {
+		let n in 1 .. T::MaxBatchSize::get();
+
+		let caller: T::AccountId = whitelisted_caller();
+		let tokens = tokens::<T>(&caller, n)?;
+	}: _(RawOrigin::Signed(caller), tokens.clone())
+	verify {
+		assert!(tokens.iter().all(|token| orml_nft::Pallet::<T>::tokens(token.0, token.1).is_none()));
+	}
+}
+
+impl_benchmark_test_suite!(Pallet, crate::mock::new_test_ext(), crate::mock::Test);
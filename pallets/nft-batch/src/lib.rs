@@ -0,0 +1,182 @@
+//! # NFT Batch Pallet
+//!
+//! Mints, transfers and burns many `orml_nft` tokens in one extrinsic, so that creators can
+//! drop a whole collection in a few blocks and inventory can be moved in bulk.
+//!
+//! Each call handles up to `MaxBatchSize` items and is charged per item. A batch is atomic: if
+//! any item fails, none of them take effect. Minted tokens get consecutive IDs, and their
+//! metadata must follow the Chiba Studio metadata standard of `chiba_nft_metadata`. Tokens of
+//! classes with a royalty cannot be transferred here, since the royalty is only charged on
+//! sales.
+//!
+//! Generic batching of other calls is left to `pallet_utility`.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use pallet_moderation::ClassModeration;
+use pallet_royalties::RoyaltyClasses;
+use sp_std::vec::Vec;
+
+pub use pallet::*;
+pub use weights::WeightInfo;
+
+mod benchmarking;
+#[cfg(test)]
+mod mock;
+#[cfg(test)]
+mod tests;
+pub mod weights;
+
+pub type TokenIdOf<T> = (<T as orml_nft::Config>::ClassId, <T as orml_nft::Config>::TokenId);
+
+#[frame_support::pallet]
+pub mod pallet {
+	use frame_support::{dispatch::DispatchResultWithPostInfo, pallet_prelude::*, transactional};
+	use frame_system::pallet_prelude::*;
+	use sp_runtime::traits::StaticLookup;
+	use super::*;
+
+	#[pallet::config]
+	pub trait Config: frame_system::Config + orml_nft::Config {
+		/// Because this pallet emits events, it depends on the runtime's definition of an event.
+		type Event: From<Event<Self>> + IsType<<Self as frame_system::Config>::Event>;
+
+		/// Stops minting into classes frozen by moderators.
+		type Moderation: ClassModeration<Self::ClassId>;
+
+		/// Stops plain transfers of tokens that owe a royalty when they change hands.
+		type Royalties: RoyaltyClasses<Self::ClassId>;
+
+		/// The most items a single batch may contain.
+		#[pallet::constant]
+		type MaxBatchSize: Get<u32>;
+
+		/// Weight information for extrinsics in this pallet.
+		type WeightInfo: WeightInfo;
+	}
+
+	#[pallet::pallet]
+	#[pallet::generate_store(pub(super) trait Store)]
+	pub struct Pallet<T>(_);
+
+	#[pallet::event]
+	#[pallet::metadata(
+		T::AccountId = "AccountId",
+		T::ClassId = "ClassId",
+		T::TokenId = "TokenId"
+	)]
+	#[pallet::generate_deposit(pub(super) fn deposit_event)]
+	pub enum Event<T: Config> {
+		/// Tokens were minted with consecutive IDs. \[class_id, owner, first_token_id, count\]
+		BatchMinted(T::ClassId, T::AccountId, T::TokenId, u32),
+		/// Tokens were transferred. \[from, count\]
+		BatchTransferred(T::AccountId, u32),
+		/// Tokens were burned. \[owner, count\]
+		BatchBurned(T::AccountId, u32),
+	}
+
+	#[pallet::error]
+	pub enum Error<T> {
+		/// The batch contains more than `MaxBatchSize` items.
+		TooManyItems,
+		/// The class does not exist.
+		ClassNotFound,
+		/// Only the class owner may mint tokens.
+		NoPermission,
+		/// The class has been frozen by moderators.
+		ClassFrozen,
+		/// Token metadata does not follow the metadata standard.
+		InvalidMetadata,
+		/// Tokens of classes with a royalty can only change hands through a sale.
+		RoyaltyClass,
+	}
+
+	#[pallet::hooks]
+	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {}
+
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {
+		/// Mint a token of `class_id` to `owner` for each pair of metadata and data in `tokens`.
+		///
+		/// The dispatch origin must be the class owner. Each metadata must be an encoded
+		/// `chiba_nft_metadata::Metadata`.
+		#[pallet::weight(T::WeightInfo::batch_mint(tokens.len() as u32))]
+		#[transactional]
+		pub fn batch_mint(
+			origin: OriginFor<T>,
+			class_id: T::ClassId,
+			owner: <T::Lookup as StaticLookup>::Source,
+			tokens: Vec<(Vec<u8>, T::TokenData)>,
+		) -> DispatchResultWithPostInfo {
+			let who = ensure_signed(origin)?;
+			let owner = T::Lookup::lookup(owner)?;
+			let count = Self::ensure_batch_size(tokens.len())?;
+
+			let class = orml_nft::Pallet::<T>::classes(class_id).ok_or(Error::<T>::ClassNotFound)?;
+			ensure!(class.owner == who, Error::<T>::NoPermission);
+			ensure!(!T::Moderation::is_frozen(&class_id), Error::<T>::ClassFrozen);
+
+			let first_token_id = orml_nft::Pallet::<T>::next_token_id(class_id);
+			for (metadata, data) in tokens {
+				chiba_nft_metadata::parse(&metadata).map_err(|_| Error::<T>::InvalidMetadata)?;
+				orml_nft::Pallet::<T>::mint(&owner, class_id, metadata, data)?;
+			}
+
+			Self::deposit_event(Event::BatchMinted(class_id, owner, first_token_id, count));
+			Ok(().into())
+		}
+
+		/// Transfer each token in `transfers` to its paired account.
+		///
+		/// The dispatch origin must own every token, and no token may belong to a class with a
+		/// royalty.
+		#[pallet::weight(T::WeightInfo::batch_transfer(transfers.len() as u32))]
+		#[transactional]
+		pub fn batch_transfer(
+			origin: OriginFor<T>,
+			transfers: Vec<(<T::Lookup as StaticLookup>::Source, TokenIdOf<T>)>,
+		) -> DispatchResultWithPostInfo {
+			let who = ensure_signed(origin)?;
+			let count = Self::ensure_batch_size(transfers.len())?;
+
+			for (to, token) in transfers {
+				ensure!(!T::Royalties::has_royalty(&token.0), Error::<T>::RoyaltyClass);
+				let to = T::Lookup::lookup(to)?;
+				orml_nft::Pallet::<T>::transfer(&who, &to, token)?;
+			}
+
+			Self::deposit_event(Event::BatchTransferred(who, count));
+			Ok(().into())
+		}
+
+		/// Burn every token in `tokens`.
+		///
+		/// The dispatch origin must own every token.
+		#[pallet::weight(T::WeightInfo::batch_burn(tokens.len() as u32))]
+		#[transactional]
+		pub fn batch_burn(
+			origin: OriginFor<T>,
+			tokens: Vec<TokenIdOf<T>>,
+		) -> DispatchResultWithPostInfo {
+			let who = ensure_signed(origin)?;
+			let count = Self::ensure_batch_size(tokens.len())?;
+
+			for token in tokens {
+				orml_nft::Pallet::<T>::burn(&who, token)?;
+			}
+
+			Self::deposit_event(Event::BatchBurned(who, count));
+			Ok(().into())
+		}
+	}
+
+	impl<T: Config> Pallet<T> {
+		fn ensure_batch_size(len: usize) -> Result<u32, Error<T>> {
+			let max = T::MaxBatchSize::get();
+			if len > max as usize {
+				return Err(Error::<T>::TooManyItems)
+			}
+			Ok(len as u32)
+		}
+	}
+}
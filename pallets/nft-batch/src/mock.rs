@@ -0,0 +1,119 @@
+//! A runtime with balances, NFTs, royalties, moderation and NFT batches for tests.
+
+use crate as pallet_nft_batch;
+use frame_support::{parameter_types, PalletId};
+use frame_system::EnsureRoot;
+use sp_core::H256;
+use sp_runtime::{
+	testing::Header,
+	traits::{BlakeTwo256, IdentityLookup},
+};
+
+type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Test>;
+type Block = frame_system::mocking::MockBlock<Test>;
+
+pub const ALICE: u64 = 1;
+pub const BOB: u64 = 2;
+pub const CHARLIE: u64 = 3;
+
+frame_support::construct_runtime!(
+	pub enum Test where
+		Block = Block,
+		NodeBlock = Block,
+		UncheckedExtrinsic = UncheckedExtrinsic,
+	{
+		System: frame_system::{Pallet, Call, Config, Storage, Event<T>},
+		Balances: pallet_balances::{Pallet, Call, Storage, Config<T>, Event<T>},
+		Nft: orml_nft::{Pallet, Call, Storage, Config<T>},
+		Royalties: pallet_royalties::{Pallet, Call, Storage, Event<T>},
+		Moderation: pallet_moderation::{Pallet, Call, Storage, Event<T>},
+		NftBatch: pallet_nft_batch::{Pallet, Call, Event<T>},
+	}
+);
+
+parameter_types! {
+	pub const BlockHashCount: u64 = 250;
+	pub const ExistentialDeposit: u64 = 1;
+	pub const RoyaltiesPalletId: PalletId = PalletId(*b"chb/roya");
+	pub const MaxRoyaltyBasisPoints: u16 = 5_000;
+	pub const MaxBatchSize: u32 = 50;
+}
+
+impl frame_system::Config for Test {
+	type BaseCallFilter = ();
+	type BlockWeights = ();
+	type BlockLength = ();
+	type DbWeight = ();
+	type Origin = Origin;
+	type Call = Call;
+	type Index = u64;
+	type BlockNumber = u64;
+	type Hash = H256;
+	type Hashing = BlakeTwo256;
+	type AccountId = u64;
+	type Lookup = IdentityLookup<Self::AccountId>;
+	type Header = Header;
+	type Event = Event;
+	type BlockHashCount = BlockHashCount;
+	type Version = ();
+	type PalletInfo = PalletInfo;
+	type AccountData = pallet_balances::AccountData<u64>;
+	type OnNewAccount = ();
+	type OnKilledAccount = ();
+	type SystemWeightInfo = ();
+	type SS58Prefix = ();
+}
+
+impl pallet_balances::Config for Test {
+	type MaxLocks = ();
+	type Balance = u64;
+	type Event = Event;
+	type DustRemoval = ();
+	type ExistentialDeposit = ExistentialDeposit;
+	type AccountStore = System;
+	type WeightInfo = ();
+}
+
+impl orml_nft::Config for Test {
+	type ClassId = u32;
+	type TokenId = u64;
+	type ClassData = ();
+	type TokenData = ();
+}
+
+impl pallet_royalties::Config for Test {
+	type Event = Event;
+	type Currency = Balances;
+	type AssetId = u32;
+	type Assets = pallet_royalties::NativeOnly<Balances>;
+	type Moderation = Moderation;
+	type PalletId = RoyaltiesPalletId;
+	type MaxRoyaltyBasisPoints = MaxRoyaltyBasisPoints;
+	type WeightInfo = ();
+}
+
+impl pallet_moderation::Config for Test {
+	type Event = Event;
+	type ModeratorOrigin = EnsureRoot<u64>;
+	type WeightInfo = ();
+}
+
+impl pallet_nft_batch::Config for Test {
+	type Event = Event;
+	type Moderation = Moderation;
+	type Royalties = Royalties;
+	type MaxBatchSize = MaxBatchSize;
+	type WeightInfo = ();
+}
+
+/// Alice, Bob and Charlie start with 1,000 each.
+pub fn new_test_ext() -> sp_io::TestExternalities {
+	let mut storage = frame_system::GenesisConfig::default().build_storage::<Test>().unwrap();
+	pallet_balances::GenesisConfig::<Test> {
+		balances: vec![(ALICE, 1_000), (BOB, 1_000), (CHARLIE, 1_000)],
+	}.assimilate_storage(&mut storage).unwrap();
+
+	let mut ext = sp_io::TestExternalities::new(storage);
+	ext.execute_with(|| System::set_block_number(1));
+	ext
+}
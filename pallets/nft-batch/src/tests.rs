@@ -0,0 +1,163 @@
+//! Tests for the NFT batch pallet.
+
+use super::*;
+use crate::mock::*;
+use chiba_nft_metadata::Metadata;
+use codec::Encode;
+use frame_support::{assert_noop, assert_ok};
+
+fn metadata(name: &str) -> Vec<u8> {
+	Metadata {
+		name: name.as_bytes().to_vec(),
+		description: vec![],
+		media: b"QmYwAPJzv5CZsnA625s3Xf2nemtYgPpHdWEz79ojWnPbdG".to_vec(),
+		mime_type: b"image/png".to_vec(),
+		attributes: vec![],
+	}.encode()
+}
+
+fn batch(n: usize) -> Vec<(Vec<u8>, ())> {
+	(0 .. n).map(|_| (metadata("Sakura"), ())).collect()
+}
+
+/// Mints `n` tokens of a new class to `owner`.
+fn tokens(owner: u64, n: u64) -> Vec<(u32, u64)> {
+	let class_id = Nft::create_class(&owner, vec![], ()).unwrap();
+	(0 .. n).map(|_| (class_id, Nft::mint(&owner, class_id, vec![], ()).unwrap())).collect()
+}
+
+fn owner_of(token: (u32, u64)) -> Option<u64> {
+	Nft::tokens(token.0, token.1).map(|token| token.owner)
+}
+
+#[test]
+fn batch_mint_mints_consecutive_tokens() {
+	new_test_ext().execute_with(|| {
+		let class_id = Nft::create_class(&ALICE, vec![], ()).unwrap();
+		Nft::mint(&ALICE, class_id, vec![], ()).unwrap();
+
+		assert_ok!(NftBatch::batch_mint(Origin::signed(ALICE), class_id, BOB, batch(3)));
+		assert_eq!(Nft::classes(class_id).unwrap().total_issuance, 4);
+		for token_id in 1 ..= 3 {
+			let token = Nft::tokens(class_id, token_id).unwrap();
+			assert_eq!(token.owner, BOB);
+			assert_eq!(token.metadata, metadata("Sakura"));
+		}
+	});
+}
+
+#[test]
+fn batch_mint_is_checked() {
+	new_test_ext().execute_with(|| {
+		let class_id = Nft::create_class(&ALICE, vec![], ()).unwrap();
+		assert_noop!(
+			NftBatch::batch_mint(Origin::signed(ALICE), class_id + 1, ALICE, batch(1)),
+			Error::<Test>::ClassNotFound
+		);
+		assert_noop!(
+			NftBatch::batch_mint(Origin::signed(BOB), class_id, BOB, batch(1)),
+			Error::<Test>::NoPermission
+		);
+		assert_noop!(
+			NftBatch::batch_mint(Origin::signed(ALICE), class_id, ALICE, vec![(vec![], ())]),
+			Error::<Test>::InvalidMetadata
+		);
+
+		assert_ok!(Moderation::freeze_class(Origin::root(), class_id));
+		assert_noop!(
+			NftBatch::batch_mint(Origin::signed(ALICE), class_id, ALICE, batch(1)),
+			Error::<Test>::ClassFrozen
+		);
+	});
+}
+
+#[test]
+fn batches_are_capped() {
+	new_test_ext().execute_with(|| {
+		let class_id = Nft::create_class(&ALICE, vec![], ()).unwrap();
+		assert_ok!(NftBatch::batch_mint(Origin::signed(ALICE), class_id, ALICE, batch(50)));
+		assert_noop!(
+			NftBatch::batch_mint(Origin::signed(ALICE), class_id, ALICE, batch(51)),
+			Error::<Test>::TooManyItems
+		);
+
+		let owned = tokens(ALICE, 51);
+		let transfers: Vec<_> = owned.iter().map(|token| (BOB, *token)).collect();
+		assert_noop!(
+			NftBatch::batch_transfer(Origin::signed(ALICE), transfers.clone()),
+			Error::<Test>::TooManyItems
+		);
+		assert_ok!(NftBatch::batch_transfer(Origin::signed(ALICE), transfers[.. 50].to_vec()));
+
+		assert_noop!(
+			NftBatch::batch_burn(Origin::signed(BOB), owned.clone()),
+			Error::<Test>::TooManyItems
+		);
+		assert_ok!(NftBatch::batch_burn(Origin::signed(BOB), owned[.. 50].to_vec()));
+	});
+}
+
+#[test]
+fn failed_batches_change_nothing() {
+	new_test_ext().execute_with(|| {
+		let class_id = Nft::create_class(&ALICE, vec![], ()).unwrap();
+		let mut mints = batch(2);
+		mints.push((vec![], ()));
+		assert_noop!(
+			NftBatch::batch_mint(Origin::signed(ALICE), class_id, ALICE, mints),
+			Error::<Test>::InvalidMetadata
+		);
+
+		let alices = tokens(ALICE, 2);
+		let bobs = tokens(BOB, 1);
+		assert_noop!(
+			NftBatch::batch_transfer(
+				Origin::signed(ALICE),
+				vec![(CHARLIE, alices[0]), (CHARLIE, alices[1]), (CHARLIE, bobs[0])],
+			),
+			orml_nft::Error::<Test>::NoPermission
+		);
+		assert_noop!(
+			NftBatch::batch_burn(Origin::signed(ALICE), vec![alices[0], alices[1], bobs[0]]),
+			orml_nft::Error::<Test>::NoPermission
+		);
+	});
+}
+
+#[test]
+fn batch_transfer_moves_every_token() {
+	new_test_ext().execute_with(|| {
+		let owned = tokens(ALICE, 2);
+		assert_ok!(NftBatch::batch_transfer(
+			Origin::signed(ALICE),
+			vec![(BOB, owned[0]), (CHARLIE, owned[1])],
+		));
+		assert_eq!(owner_of(owned[0]), Some(BOB));
+		assert_eq!(owner_of(owned[1]), Some(CHARLIE));
+	});
+}
+
+#[test]
+fn royalty_classes_cannot_be_batch_transferred() {
+	new_test_ext().execute_with(|| {
+		let class_id = Nft::create_class(&ALICE, vec![], ()).unwrap();
+		assert_ok!(Royalties::set_royalty(Origin::signed(ALICE), class_id, 1_000, CHARLIE));
+		let token = (class_id, Nft::mint(&ALICE, class_id, vec![], ()).unwrap());
+		assert_noop!(
+			NftBatch::batch_transfer(Origin::signed(ALICE), vec![(BOB, token)]),
+			Error::<Test>::RoyaltyClass
+		);
+	});
+}
+
+#[test]
+fn batch_burn_burns_every_token() {
+	new_test_ext().execute_with(|| {
+		let owned = tokens(ALICE, 3);
+		assert_ok!(NftBatch::batch_burn(Origin::signed(ALICE), owned[.. 2].to_vec()));
+		assert_eq!(owner_of(owned[0]), None);
+		assert_eq!(owner_of(owned[1]), None);
+		assert_eq!(owner_of(owned[2]), Some(ALICE));
+		assert_eq!(Nft::classes(owned[0].0).unwrap().total_issuance, 1);
+	});
+}
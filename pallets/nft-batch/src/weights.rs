@@ -0,0 +1,61 @@
+//! Weights for pallet_nft_batch
+
+#![allow(unused_parens)]
+#![allow(unused_imports)]
+
+use frame_support::{traits::Get, weights::{Weight, constants::RocksDbWeight}};
+use sp_std::marker::PhantomData;
+
+/// Weight functions needed for pallet_nft_batch.
+pub trait WeightInfo {
+	fn batch_mint(n: u32, ) -> Weight;
+	fn batch_transfer(n: u32, ) -> Weight;
+	fn batch_burn(n: u32, ) -> Weight;
+}
+
+/// Weights for pallet_nft_batch.
+pub struct SubstrateWeight<T>(PhantomData<T>);
+impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
+	fn batch_mint(n: u32, ) -> Weight {
+		(10_000_000 as Weight)
+			.saturating_add((45_000_000 as Weight).saturating_mul(n as Weight))
+			.saturating_add(T::DbWeight::get().reads(1 as Weight))
+			.saturating_add(T::DbWeight::get().reads((2 as Weight).saturating_mul(n as Weight)))
+			.saturating_add(T::DbWeight::get().writes((4 as Weight).saturating_mul(n as Weight)))
+	}
+	fn batch_transfer(n: u32, ) -> Weight {
+		(5_000_000 as Weight)
+			.saturating_add((35_000_000 as Weight).saturating_mul(n as Weight))
+			.saturating_add(T::DbWeight::get().reads((2 as Weight).saturating_mul(n as Weight)))
+			.saturating_add(T::DbWeight::get().writes((3 as Weight).saturating_mul(n as Weight)))
+	}
+	fn batch_burn(n: u32, ) -> Weight {
+		(5_000_000 as Weight)
+			.saturating_add((35_000_000 as Weight).saturating_mul(n as Weight))
+			.saturating_add(T::DbWeight::get().reads((2 as Weight).saturating_mul(n as Weight)))
+			.saturating_add(T::DbWeight::get().writes((3 as Weight).saturating_mul(n as Weight)))
+	}
+}
+
+// For backwards compatibility and tests
+impl WeightInfo for () {
+	fn batch_mint(n: u32, ) -> Weight {
+		(10_000_000 as Weight)
+			.saturating_add((45_000_000 as Weight).saturating_mul(n as Weight))
+			.saturating_add(RocksDbWeight::get().reads(1 as Weight))
+			.saturating_add(RocksDbWeight::get().reads((2 as Weight).saturating_mul(n as Weight)))
+			.saturating_add(RocksDbWeight::get().writes((4 as Weight).saturating_mul(n as Weight)))
+	}
+	fn batch_transfer(n: u32, ) -> Weight {
+		(5_000_000 as Weight)
+			.saturating_add((35_000_000 as Weight).saturating_mul(n as Weight))
+			.saturating_add(RocksDbWeight::get().reads((2 as Weight).saturating_mul(n as Weight)))
+			.saturating_add(RocksDbWeight::get().writes((3 as Weight).saturating_mul(n as Weight)))
+	}
+	fn batch_burn(n: u32, ) -> Weight {
+		(5_000_000 as Weight)
+			.saturating_add((35_000_000 as Weight).saturating_mul(n as Weight))
+			.saturating_add(RocksDbWeight::get().reads((2 as Weight).saturating_mul(n as Weight)))
+			.saturating_add(RocksDbWeight::get().writes((3 as Weight).saturating_mul(n as Weight)))
+	}
+}
@@ -0,0 +1,86 @@
+//! Benchmarks for the calls of `pallet_atomic_swap` with a balance swap, the cheapest action.
+//!
+//! `create_swap` and `cancel_swap` charge a fixed weight whatever the action. These benchmarks
+//! show what that weight has to cover before `RoyaltySwapAction` adds the cost of escrowing a
+//! token or an asset; `pallet_royalties` benchmarks the claims of each action.
+
+use frame_benchmarking::{account, benchmarks, impl_benchmark_test_suite, whitelisted_caller};
+use frame_support::traits::Currency;
+use frame_system::RawOrigin;
+use pallet_royalties::{testing_utils::fund, RoyaltySwapAction};
+use sp_std::vec;
+
+const SEED: u32 = 0;
+
+/// The benchmarked pallet, a wrapper so that it can be registered under `pallet_atomic_swap`.
+pub struct Pallet<T: Config>(pallet_atomic_swap::Pallet<T>);
+
+pub trait Config:
+	pallet_royalties::Config + pallet_atomic_swap::Config<SwapAction = RoyaltySwapAction<Self>>
+{
+}
+
+fn balance_swap<T: Config>() -> RoyaltySwapAction<T> {
+	RoyaltySwapAction::Balance(1_000_000u32.into())
+}
+
+benchmarks! {
+	create_swap {
+		let caller: T::AccountId = whitelisted_caller();
+		fund::<_, <T as pallet_royalties::Config>::Currency>(&caller);
+		let target: T::AccountId = account("target", 0, SEED);
+		let hashed_proof = sp_io::hashing::blake2_256(&[1; 32]);
+	}: {
+		pallet_atomic_swap::Pallet::<T>::create_swap(
+			RawOrigin::Signed(caller).into(),
+			target,
+			hashed_proof,
+			balance_swap::<T>(),
+			1_000u32.into(),
+		)?;
+	}
+
+	claim_swap {
+		let source: T::AccountId = account("source", 0, SEED);
+		fund::<_, <T as pallet_royalties::Config>::Currency>(&source);
+		let caller: T::AccountId = whitelisted_caller();
+		let proof = vec![1; 32];
+		pallet_atomic_swap::Pallet::<T>::create_swap(
+			RawOrigin::Signed(source).into(),
+			caller.clone(),
+			sp_io::hashing::blake2_256(&proof),
+			balance_swap::<T>(),
+			1_000u32.into(),
+		)?;
+	}: {
+		pallet_atomic_swap::Pallet::<T>::claim_swap(
+			RawOrigin::Signed(caller.clone()).into(),
+			proof,
+			balance_swap::<T>(),
+		)?;
+	}
+	verify {
+		let balance = <T as pallet_royalties::Config>::Currency::free_balance(&caller);
+		assert_eq!(balance, 1_000_000u32.into());
+	}
+
+	cancel_swap {
+		let caller: T::AccountId = whitelisted_caller();
+		fund::<_, <T as pallet_royalties::Config>::Currency>(&caller);
+		let target: T::AccountId = account("target", 0, SEED);
+		let hashed_proof = sp_io::hashing::blake2_256(&[1; 32]);
+		pallet_atomic_swap::Pallet::<T>::create_swap(
+			RawOrigin::Signed(caller.clone()).into(),
+			target.clone(),
+			hashed_proof,
+			balance_swap::<T>(),
+			1_000u32.into(),
+		)?;
+		let end = frame_system::Pallet::<T>::block_number() + 1_000u32.into();
+		frame_system::Pallet::<T>::set_block_number(end);
+	}: {
+		pallet_atomic_swap::Pallet::<T>::cancel_swap(RawOrigin::Signed(caller).into(), target, hashed_proof)?;
+	}
+}
+
+impl_benchmark_test_suite!(Pallet, crate::mock::new_test_ext(), crate::mock::Test);
@@ -0,0 +1,17 @@
+//! # NFT Benchmarking
+//!
+//! Benchmarks for the pallets the runtime takes from outside this repository: the NFT storage
+//! of `orml_nft`, and `pallet_atomic_swap`. Neither crate ships benchmarks, and neither takes a
+//! `WeightInfo`, so the results are used to check the weights that cover them elsewhere: the
+//! NFT operations performed by the Chiba Studio pallets, and the fixed weights of
+//! `create_swap` and `cancel_swap` that `pallet_royalties::RoyaltySwapAction` tops up.
+//!
+//! Each suite is a separate benchmarking pallet, registered under the name of the pallet it
+//! measures.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+pub mod atomic_swap;
+#[cfg(test)]
+mod mock;
+pub mod nft;
@@ -0,0 +1,51 @@
+//! Benchmarks for the NFT operations of `orml_nft`, which has no calls of its own but does the
+//! storage work behind every class creation, mint, transfer and burn.
+
+use frame_benchmarking::{account, benchmarks, impl_benchmark_test_suite, whitelisted_caller};
+use pallet_royalties::testing_utils::{token, zeroed};
+use sp_std::vec;
+
+const SEED: u32 = 0;
+
+/// The benchmarked pallet, a wrapper so that it can be registered under `orml_nft`.
+pub struct Pallet<T: Config>(orml_nft::Pallet<T>);
+
+pub trait Config: orml_nft::Config {}
+
+benchmarks! {
+	create_class {
+		let caller: T::AccountId = whitelisted_caller();
+	}: {
+		orml_nft::Pallet::<T>::create_class(&caller, vec![0; 64], zeroed())?;
+	}
+
+	mint {
+		let caller: T::AccountId = whitelisted_caller();
+		let class_id = orml_nft::Pallet::<T>::create_class(&caller, vec![0; 64], zeroed())?;
+	}: {
+		orml_nft::Pallet::<T>::mint(&caller, class_id, vec![0; 64], zeroed())?;
+	}
+
+	transfer {
+		let caller: T::AccountId = whitelisted_caller();
+		let recipient: T::AccountId = account("recipient", 0, SEED);
+		let token = token::<T>(&caller)?;
+	}: {
+		orml_nft::Pallet::<T>::transfer(&caller, &recipient, token)?;
+	}
+	verify {
+		assert!(orml_nft::Pallet::<T>::is_owner(&recipient, token));
+	}
+
+	burn {
+		let caller: T::AccountId = whitelisted_caller();
+		let token = token::<T>(&caller)?;
+	}: {
+		orml_nft::Pallet::<T>::burn(&caller, token)?;
+	}
+	verify {
+		assert!(orml_nft::Pallet::<T>::tokens(token.0, token.1).is_none());
+	}
+}
+
+impl_benchmark_test_suite!(Pallet, crate::mock::new_test_ext(), crate::mock::Test);
@@ -0,0 +1,90 @@
+//! Benchmarks for the rentals pallet.
+
+#![cfg(feature = "runtime-benchmarks")]
+
+use super::*;
+use frame_benchmarking::{account, benchmarks, impl_benchmark_test_suite, whitelisted_caller};
+use frame_support::traits::OnInitialize;
+use frame_system::RawOrigin;
+use pallet_royalties::testing_utils::{fund, token, zeroed};
+use sp_runtime::traits::Bounded;
+use sp_std::vec;
+
+const SEED: u32 = 0;
+
+fn offered_token<T: Config>(owner: &T::AccountId) -> Result<TokenIdOf<T>, &'static str> {
+	let token = token::<T>(owner)?;
+	Pallet::<T>::offer(
+		RawOrigin::Signed(owner.clone()).into(),
+		token,
+		1_000u32.into(),
+		T::MaxDuration::get(),
+	).map_err(|_| "cannot offer token")?;
+	Ok(token)
+}
+
+benchmarks! {
+	offer {
+		let caller: T::AccountId = whitelisted_caller();
+		let token = token::<T>(&caller)?;
+		let max_duration = T::MaxDuration::get();
+	}: _(RawOrigin::Signed(caller), token, 1_000u32.into(), max_duration)
+	verify {
+		assert!(Offers::<T>::contains_key(token));
+	}
+
+	withdraw {
+		let caller: T::AccountId = whitelisted_caller();
+		let token = offered_token::<T>(&caller)?;
+	}: _(RawOrigin::Signed(caller.clone()), token)
+	verify {
+		assert!(orml_nft::Pallet::<T>::is_owner(&caller, token));
+	}
+
+	rent {
+		let owner: T::AccountId = account("owner", 0, SEED);
+		let caller: T::AccountId = whitelisted_caller();
+		fund::<_, T::Currency>(&owner);
+		fund::<_, T::Currency>(&caller);
+		let token = offered_token::<T>(&owner)?;
+		let duration = T::MaxDuration::get();
+	}: _(RawOrigin::Signed(caller.clone()), token, duration, BalanceOf::<T>::max_value())
+	verify {
+		assert_eq!(Pallet::<T>::user_of(token), Some(caller));
+	}
+
+	// Expires `n` rentals, all ending in the same block.
+	on_initialize {
+		let n in 0 .. T::MaxRentalsPerBlock::get();
+
+		let owner: T::AccountId = account("owner", 0, SEED);
+		fund::<_, T::Currency>(&owner);
+		let class_id = orml_nft::Pallet::<T>::create_class(&owner, vec![0; 64], zeroed())?;
+		let duration = T::MaxDuration::get();
+		for i in 0 .. n {
+			let renter: T::AccountId = account("renter", i, SEED);
+			fund::<_, T::Currency>(&renter);
+			let token_id = orml_nft::Pallet::<T>::mint(&owner, class_id, vec![0; 64], zeroed())?;
+			Pallet::<T>::offer(
+				RawOrigin::Signed(owner.clone()).into(),
+				(class_id, token_id),
+				1_000u32.into(),
+				duration,
+			).map_err(|_| "cannot offer token")?;
+			Pallet::<T>::rent(
+				RawOrigin::Signed(renter).into(),
+				(class_id, token_id),
+				duration,
+				BalanceOf::<T>::max_value(),
+			).map_err(|_| "cannot rent token")?;
+		}
+		let end = frame_system::Pallet::<T>::block_number() + duration;
+	}: {
+		Pallet::<T>::on_initialize(end);
+	}
+	verify {
+		assert_eq!(Rentals::<T>::iter().count(), 0);
+	}
+}
+
+impl_benchmark_test_suite!(Pallet, crate::mock::new_test_ext(), crate::mock::Test);
@@ -0,0 +1,316 @@
+//! # Rentals Pallet
+//!
+//! Time-limited usage rights for `orml_nft` tokens.
+//!
+//! An owner offers a token for rent at a price per block, which moves it into an escrow account
+//! owned by this pallet, so it cannot be transferred while it is offered or rented. Anyone else
+//! can rent the token for up to the offer's maximum duration by paying the rental fee to the
+//! owner in the native currency, and becomes the token's **user** until the rental expires.
+//!
+//! Rentals expire in `on_initialize` of their end block. The token then stays on offer and can
+//! be rented again, until the owner withdraws the offer and takes the token back. The owner
+//! remains recorded on the offer throughout. At most `MaxRentalsPerBlock` rentals expire in the
+//! same block; a rental that would expire in a full block is refused.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use codec::{Decode, Encode};
+use frame_support::{
+	dispatch::DispatchResult,
+	traits::{Currency, ExistenceRequirement, Get},
+	weights::Weight,
+};
+use pallet_moderation::ClassModeration;
+use sp_runtime::{
+	traits::{AccountIdConversion, SaturatedConversion, Saturating, Zero},
+	RuntimeDebug,
+};
+
+pub use pallet::*;
+pub use weights::WeightInfo;
+
+mod benchmarking;
+#[cfg(test)]
+mod mock;
+#[cfg(test)]
+mod tests;
+pub mod weights;
+
+pub type BalanceOf<T> =
+	<<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
+pub type TokenIdOf<T> = (<T as orml_nft::Config>::ClassId, <T as orml_nft::Config>::TokenId);
+
+/// A token offered for rent.
+#[derive(Clone, Eq, PartialEq, Encode, Decode, RuntimeDebug)]
+pub struct RentalOffer<AccountId, Balance, BlockNumber> {
+	/// The account that owns the token and receives the rental fees.
+	pub owner: AccountId,
+	/// The fee charged for each block of a rental.
+	pub price_per_block: Balance,
+	/// The longest rental accepted, in blocks.
+	pub max_duration: BlockNumber,
+}
+
+/// The current user of a rented token.
+#[derive(Clone, Eq, PartialEq, Encode, Decode, RuntimeDebug)]
+pub struct Rental<AccountId, BlockNumber> {
+	/// The account that rented the token.
+	pub user: AccountId,
+	/// The block in which the rental expires.
+	pub expires: BlockNumber,
+}
+
+#[frame_support::pallet]
+pub mod pallet {
+	use frame_support::{dispatch::DispatchResultWithPostInfo, pallet_prelude::*, transactional, PalletId};
+	use frame_system::pallet_prelude::*;
+	use super::*;
+
+	#[pallet::config]
+	pub trait Config: frame_system::Config + orml_nft::Config {
+		/// Because this pallet emits events, it depends on the runtime's definition of an event.
+		type Event: From<Event<Self>> + IsType<<Self as frame_system::Config>::Event>;
+
+		/// The currency rental fees are paid in.
+		type Currency: Currency<Self::AccountId>;
+
+		/// Stops renting of classes frozen by moderators.
+		type Moderation: ClassModeration<Self::ClassId>;
+
+		/// The pallet's ID, used to derive the account that escrows offered tokens.
+		#[pallet::constant]
+		type PalletId: Get<PalletId>;
+
+		/// The longest rental an offer may allow, in blocks.
+		#[pallet::constant]
+		type MaxDuration: Get<Self::BlockNumber>;
+
+		/// The most rentals that may expire in the same block, which bounds the work of
+		/// expiring them in `on_initialize`.
+		#[pallet::constant]
+		type MaxRentalsPerBlock: Get<u32>;
+
+		/// Weight information for extrinsics in this pallet.
+		type WeightInfo: WeightInfo;
+	}
+
+	#[pallet::pallet]
+	#[pallet::generate_store(pub(super) trait Store)]
+	pub struct Pallet<T>(_);
+
+	/// The tokens currently offered for rent.
+	#[pallet::storage]
+	#[pallet::getter(fn offers)]
+	pub type Offers<T: Config> = StorageMap<
+		_,
+		Twox64Concat,
+		TokenIdOf<T>,
+		RentalOffer<T::AccountId, BalanceOf<T>, T::BlockNumber>,
+	>;
+
+	/// The tokens currently rented, with their users.
+	#[pallet::storage]
+	#[pallet::getter(fn rentals)]
+	pub type Rentals<T: Config> = StorageMap<
+		_,
+		Twox64Concat,
+		TokenIdOf<T>,
+		Rental<T::AccountId, T::BlockNumber>,
+	>;
+
+	/// The rentals to expire in each block.
+	#[pallet::storage]
+	pub type RentalEndAt<T: Config> = StorageDoubleMap<
+		_,
+		Twox64Concat,
+		T::BlockNumber,
+		Twox64Concat,
+		TokenIdOf<T>,
+		(),
+	>;
+
+	/// The number of rentals in `RentalEndAt` for each block.
+	#[pallet::storage]
+	pub type RentalsEndingAt<T: Config> =
+		StorageMap<_, Twox64Concat, T::BlockNumber, u32, ValueQuery>;
+
+	#[pallet::event]
+	#[pallet::metadata(
+		T::AccountId = "AccountId",
+		T::BlockNumber = "BlockNumber",
+		T::ClassId = "ClassId",
+		T::TokenId = "TokenId",
+		BalanceOf<T> = "Balance"
+	)]
+	#[pallet::generate_deposit(pub(super) fn deposit_event)]
+	pub enum Event<T: Config> {
+		/// A token was offered for rent.
+		/// \[owner, class_id, token_id, price_per_block, max_duration\]
+		Offered(T::AccountId, T::ClassId, T::TokenId, BalanceOf<T>, T::BlockNumber),
+		/// An offer was withdrawn and the token returned. \[owner, class_id, token_id\]
+		OfferWithdrawn(T::AccountId, T::ClassId, T::TokenId),
+		/// A token was rented. \[user, class_id, token_id, expires, fee\]
+		Rented(T::AccountId, T::ClassId, T::TokenId, T::BlockNumber, BalanceOf<T>),
+		/// A rental expired. \[user, class_id, token_id\]
+		RentalExpired(T::AccountId, T::ClassId, T::TokenId),
+	}
+
+	#[pallet::error]
+	pub enum Error<T> {
+		/// The account does not own the token.
+		NotTokenOwner,
+		/// The token's class has been frozen by moderators.
+		ClassFrozen,
+		/// The token is already offered for rent.
+		AlreadyOffered,
+		/// The token is not offered for rent.
+		NotOffered,
+		/// Only the owner may withdraw an offer.
+		NotOwner,
+		/// Tokens cannot be offered for free.
+		ZeroPrice,
+		/// The duration is zero or longer than allowed.
+		InvalidDuration,
+		/// The token is rented.
+		AlreadyRented,
+		/// Owners cannot rent their own tokens.
+		RenterIsOwner,
+		/// The rental fee is above the fee the renter agreed to pay.
+		FeeTooHigh,
+		/// `MaxRentalsPerBlock` rentals already expire in the block.
+		TooManyRentalsEnding,
+	}
+
+	#[pallet::hooks]
+	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+		fn on_initialize(now: T::BlockNumber) -> Weight {
+			let mut expired: u32 = 0;
+			for (token, _) in RentalEndAt::<T>::drain_prefix(now) {
+				if let Some(rental) = Rentals::<T>::take(token) {
+					Self::deposit_event(Event::RentalExpired(rental.user, token.0, token.1));
+					expired = expired.saturating_add(1);
+				}
+			}
+			RentalsEndingAt::<T>::remove(now);
+			T::WeightInfo::on_initialize(expired)
+		}
+	}
+
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {
+		/// Offer a token for rent at `price_per_block` for rentals of up to `max_duration`
+		/// blocks, moving it into escrow.
+		#[pallet::weight(T::WeightInfo::offer())]
+		#[transactional]
+		pub fn offer(
+			origin: OriginFor<T>,
+			token: TokenIdOf<T>,
+			price_per_block: BalanceOf<T>,
+			max_duration: T::BlockNumber,
+		) -> DispatchResultWithPostInfo {
+			let owner = ensure_signed(origin)?;
+			ensure!(!price_per_block.is_zero(), Error::<T>::ZeroPrice);
+			ensure!(
+				!max_duration.is_zero() && max_duration <= T::MaxDuration::get(),
+				Error::<T>::InvalidDuration
+			);
+			ensure!(!Offers::<T>::contains_key(token), Error::<T>::AlreadyOffered);
+			ensure!(orml_nft::Pallet::<T>::is_owner(&owner, token), Error::<T>::NotTokenOwner);
+			ensure!(!T::Moderation::is_frozen(&token.0), Error::<T>::ClassFrozen);
+
+			orml_nft::Pallet::<T>::transfer(&owner, &Self::account_id(), token)?;
+			Offers::<T>::insert(token, RentalOffer {
+				owner: owner.clone(),
+				price_per_block,
+				max_duration,
+			});
+
+			Self::deposit_event(Event::Offered(owner, token.0, token.1, price_per_block, max_duration));
+			Ok(().into())
+		}
+
+		/// Withdraw an offer and return the token to its owner. Rented tokens cannot be
+		/// withdrawn until the rental expires.
+		#[pallet::weight(T::WeightInfo::withdraw())]
+		#[transactional]
+		pub fn withdraw(origin: OriginFor<T>, token: TokenIdOf<T>) -> DispatchResultWithPostInfo {
+			let who = ensure_signed(origin)?;
+			let offer = Offers::<T>::get(token).ok_or(Error::<T>::NotOffered)?;
+			ensure!(offer.owner == who, Error::<T>::NotOwner);
+			ensure!(!Rentals::<T>::contains_key(token), Error::<T>::AlreadyRented);
+
+			Offers::<T>::remove(token);
+			orml_nft::Pallet::<T>::transfer(&Self::account_id(), &who, token)?;
+
+			Self::deposit_event(Event::OfferWithdrawn(who, token.0, token.1));
+			Ok(().into())
+		}
+
+		/// Rent an offered token for `duration` blocks, becoming its user.
+		///
+		/// `max_fee` protects the renter from the offer changing while the transaction is
+		/// pending.
+		#[pallet::weight(T::WeightInfo::rent())]
+		#[transactional]
+		pub fn rent(
+			origin: OriginFor<T>,
+			token: TokenIdOf<T>,
+			duration: T::BlockNumber,
+			max_fee: BalanceOf<T>,
+		) -> DispatchResultWithPostInfo {
+			let user = ensure_signed(origin)?;
+			let offer = Offers::<T>::get(token).ok_or(Error::<T>::NotOffered)?;
+			ensure!(offer.owner != user, Error::<T>::RenterIsOwner);
+			ensure!(!Rentals::<T>::contains_key(token), Error::<T>::AlreadyRented);
+			ensure!(
+				!duration.is_zero() && duration <= offer.max_duration,
+				Error::<T>::InvalidDuration
+			);
+			ensure!(!T::Moderation::is_frozen(&token.0), Error::<T>::ClassFrozen);
+
+			let fee = Self::rental_fee(&offer, duration);
+			ensure!(fee <= max_fee, Error::<T>::FeeTooHigh);
+
+			let expires = frame_system::Pallet::<T>::block_number().saturating_add(duration);
+			RentalsEndingAt::<T>::try_mutate(expires, |count| -> DispatchResult {
+				ensure!(*count < T::MaxRentalsPerBlock::get(), Error::<T>::TooManyRentalsEnding);
+				*count = count.saturating_add(1);
+				Ok(())
+			})?;
+			T::Currency::transfer(&user, &offer.owner, fee, ExistenceRequirement::KeepAlive)?;
+
+			Rentals::<T>::insert(token, Rental { user: user.clone(), expires });
+			RentalEndAt::<T>::insert(expires, token, ());
+
+			Self::deposit_event(Event::Rented(user, token.0, token.1, expires, fee));
+			Ok(().into())
+		}
+	}
+
+	impl<T: Config> Pallet<T> {
+		/// The account that escrows offered tokens.
+		pub fn account_id() -> T::AccountId {
+			T::PalletId::get().into_account()
+		}
+
+		/// The fee for renting a token under `offer` for `duration` blocks.
+		pub fn rental_fee(
+			offer: &RentalOffer<T::AccountId, BalanceOf<T>, T::BlockNumber>,
+			duration: T::BlockNumber,
+		) -> BalanceOf<T> {
+			offer.price_per_block.saturating_mul(duration.saturated_into::<u32>().into())
+		}
+
+		/// The account currently allowed to use a token: its renter while it is rented, and
+		/// otherwise its owner.
+		pub fn user_of(token: TokenIdOf<T>) -> Option<T::AccountId> {
+			if let Some(rental) = Rentals::<T>::get(token) {
+				return Some(rental.user)
+			}
+			if let Some(offer) = Offers::<T>::get(token) {
+				return Some(offer.owner)
+			}
+			orml_nft::Pallet::<T>::tokens(token.0, token.1).map(|token| token.owner)
+		}
+	}
+}
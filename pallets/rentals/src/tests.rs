@@ -0,0 +1,196 @@
+//! Tests for the rentals pallet.
+
+use super::{Error, Rental, RentalOffer, RentalsEndingAt};
+use crate::mock::*;
+use frame_support::{assert_noop, assert_ok, traits::OnInitialize};
+use pallet_royalties::testing_utils::token;
+
+/// Offers a new token of Alice's at 10 per block for up to 20 blocks.
+fn offered() -> (u32, u64) {
+	let token = token::<Test>(&ALICE).unwrap();
+	assert_ok!(Rentals::offer(Origin::signed(ALICE), token, 10, 20));
+	token
+}
+
+fn run_to(block: u64) {
+	while System::block_number() < block {
+		System::set_block_number(System::block_number() + 1);
+		Rentals::on_initialize(System::block_number());
+	}
+}
+
+fn owner_of(token: (u32, u64)) -> u64 {
+	Nft::tokens(token.0, token.1).unwrap().owner
+}
+
+#[test]
+fn offers_escrow_the_token() {
+	new_test_ext().execute_with(|| {
+		let token = offered();
+		assert_eq!(owner_of(token), Rentals::account_id());
+		assert_eq!(
+			Rentals::offers(token),
+			Some(RentalOffer { owner: ALICE, price_per_block: 10, max_duration: 20 })
+		);
+		assert_eq!(Rentals::user_of(token), Some(ALICE));
+	});
+}
+
+#[test]
+fn offers_are_checked() {
+	new_test_ext().execute_with(|| {
+		let token = token::<Test>(&ALICE).unwrap();
+		assert_noop!(Rentals::offer(Origin::signed(ALICE), token, 0, 20), Error::<Test>::ZeroPrice);
+		assert_noop!(
+			Rentals::offer(Origin::signed(ALICE), token, 10, 0),
+			Error::<Test>::InvalidDuration
+		);
+		assert_noop!(
+			Rentals::offer(Origin::signed(ALICE), token, 10, 101),
+			Error::<Test>::InvalidDuration
+		);
+		assert_noop!(
+			Rentals::offer(Origin::signed(BOB), token, 10, 20),
+			Error::<Test>::NotTokenOwner
+		);
+
+		assert_ok!(Rentals::offer(Origin::signed(ALICE), token, 10, 20));
+		assert_noop!(
+			Rentals::offer(Origin::signed(ALICE), token, 10, 20),
+			Error::<Test>::AlreadyOffered
+		);
+	});
+}
+
+#[test]
+fn renting_pays_the_owner() {
+	new_test_ext().execute_with(|| {
+		let token = offered();
+		assert_ok!(Rentals::rent(Origin::signed(BOB), token, 5, 50));
+
+		assert_eq!(Balances::free_balance(ALICE), 1_050);
+		assert_eq!(Balances::free_balance(BOB), 950);
+		assert_eq!(Rentals::rentals(token), Some(Rental { user: BOB, expires: 6 }));
+		assert_eq!(RentalsEndingAt::<Test>::get(6), 1);
+		assert_eq!(Rentals::user_of(token), Some(BOB));
+		assert_eq!(owner_of(token), Rentals::account_id());
+	});
+}
+
+#[test]
+fn rentals_are_checked() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			Rentals::rent(Origin::signed(BOB), (0, 0), 5, 50),
+			Error::<Test>::NotOffered
+		);
+
+		let token = offered();
+		assert_noop!(
+			Rentals::rent(Origin::signed(ALICE), token, 5, 50),
+			Error::<Test>::RenterIsOwner
+		);
+		assert_noop!(
+			Rentals::rent(Origin::signed(BOB), token, 0, 50),
+			Error::<Test>::InvalidDuration
+		);
+		assert_noop!(
+			Rentals::rent(Origin::signed(BOB), token, 21, 1_000),
+			Error::<Test>::InvalidDuration
+		);
+		assert_noop!(
+			Rentals::rent(Origin::signed(BOB), token, 5, 49),
+			Error::<Test>::FeeTooHigh
+		);
+
+		assert_ok!(Rentals::rent(Origin::signed(BOB), token, 5, 50));
+		assert_noop!(
+			Rentals::rent(Origin::signed(CHARLIE), token, 5, 50),
+			Error::<Test>::AlreadyRented
+		);
+	});
+}
+
+#[test]
+fn rentals_expire_and_the_token_stays_offered() {
+	new_test_ext().execute_with(|| {
+		let token = offered();
+		assert_ok!(Rentals::rent(Origin::signed(BOB), token, 5, 50));
+
+		run_to(5);
+		assert_eq!(Rentals::user_of(token), Some(BOB));
+
+		run_to(6);
+		assert_eq!(Rentals::rentals(token), None);
+		assert_eq!(RentalsEndingAt::<Test>::get(6), 0);
+		assert_eq!(Rentals::user_of(token), Some(ALICE));
+		assert!(Rentals::offers(token).is_some());
+
+		assert_ok!(Rentals::rent(Origin::signed(CHARLIE), token, 5, 50));
+		assert_eq!(Rentals::user_of(token), Some(CHARLIE));
+	});
+}
+
+#[test]
+fn rented_tokens_cannot_be_withdrawn() {
+	new_test_ext().execute_with(|| {
+		let token = offered();
+		assert_ok!(Rentals::rent(Origin::signed(BOB), token, 5, 50));
+		assert_noop!(Rentals::withdraw(Origin::signed(ALICE), token), Error::<Test>::AlreadyRented);
+
+		run_to(6);
+		assert_ok!(Rentals::withdraw(Origin::signed(ALICE), token));
+		assert_eq!(owner_of(token), ALICE);
+		assert_eq!(Rentals::offers(token), None);
+		assert_eq!(Rentals::user_of(token), Some(ALICE));
+	});
+}
+
+#[test]
+fn only_the_owner_can_withdraw() {
+	new_test_ext().execute_with(|| {
+		let token = offered();
+		assert_noop!(Rentals::withdraw(Origin::signed(BOB), token), Error::<Test>::NotOwner);
+		assert_ok!(Rentals::withdraw(Origin::signed(ALICE), token));
+		assert_noop!(Rentals::withdraw(Origin::signed(ALICE), token), Error::<Test>::NotOffered);
+	});
+}
+
+#[test]
+fn rentals_ending_in_a_block_are_capped() {
+	new_test_ext().execute_with(|| {
+		let (first, second, third) = (offered(), offered(), offered());
+		assert_ok!(Rentals::rent(Origin::signed(BOB), first, 5, 50));
+		assert_ok!(Rentals::rent(Origin::signed(BOB), second, 5, 50));
+		assert_noop!(
+			Rentals::rent(Origin::signed(BOB), third, 5, 50),
+			Error::<Test>::TooManyRentalsEnding
+		);
+		assert_ok!(Rentals::rent(Origin::signed(BOB), third, 6, 60));
+
+		run_to(6);
+		assert_eq!(Rentals::rentals(first), None);
+		assert_eq!(Rentals::rentals(second), None);
+		assert!(Rentals::rentals(third).is_some());
+	});
+}
+
+#[test]
+fn frozen_classes_cannot_be_offered_or_rented() {
+	new_test_ext().execute_with(|| {
+		let token = offered();
+		let unoffered = self::token::<Test>(&ALICE).unwrap();
+		assert_ok!(Moderation::freeze_class(Origin::root(), token.0));
+		assert_ok!(Moderation::freeze_class(Origin::root(), unoffered.0));
+
+		assert_noop!(
+			Rentals::offer(Origin::signed(ALICE), unoffered, 10, 20),
+			Error::<Test>::ClassFrozen
+		);
+		assert_noop!(
+			Rentals::rent(Origin::signed(BOB), token, 5, 50),
+			Error::<Test>::ClassFrozen
+		);
+		assert_ok!(Rentals::withdraw(Origin::signed(ALICE), token));
+	});
+}
@@ -0,0 +1,136 @@
+//! Benchmarks for the royalties pallet and for claiming atomic swaps of [`RoyaltySwapAction`].
+
+#![cfg(feature = "runtime-benchmarks")]
+
+use super::*;
+use crate::testing_utils::{fund, largest_metadata, royalty_token, zeroed};
+use frame_benchmarking::{account, benchmarks, impl_benchmark_test_suite, whitelisted_caller};
+use frame_system::RawOrigin;
+use sp_runtime::traits::StaticLookup;
+use sp_std::{vec, vec::Vec};
+
+const SEED: u32 = 0;
+
+/// Offers `action` from `source` to `target`, returning the proof that claims it.
+fn create_swap<T>(
+	source: T::AccountId,
+	target: T::AccountId,
+	action: RoyaltySwapAction<T>,
+) -> Result<Vec<u8>, &'static str>
+where
+	T: Config + pallet_atomic_swap::Config<SwapAction = RoyaltySwapAction<T>>,
+{
+	let proof = vec![1; 32];
+	pallet_atomic_swap::Pallet::<T>::create_swap(
+		RawOrigin::Signed(source).into(),
+		target,
+		sp_io::hashing::blake2_256(&proof),
+		action,
+		1_000u32.into(),
+	).map_err(|_| "cannot create swap")?;
+	Ok(proof)
+}
+
+benchmarks! {
+	where_clause { where T: pallet_atomic_swap::Config<SwapAction = RoyaltySwapAction<T>> }
+
+	create_class {
+		let caller: T::AccountId = whitelisted_caller();
+		let beneficiary = T::Lookup::unlookup(account("beneficiary", 0, SEED));
+		let basis_points = T::MaxRoyaltyBasisPoints::get();
+	}: _(RawOrigin::Signed(caller), largest_metadata(), zeroed(), basis_points, beneficiary)
+
+	set_royalty {
+		let caller: T::AccountId = whitelisted_caller();
+		let class_id = orml_nft::Pallet::<T>::create_class(&caller, vec![0; 64], zeroed())?;
+		let beneficiary = T::Lookup::unlookup(account("beneficiary", 0, SEED));
+		let basis_points = T::MaxRoyaltyBasisPoints::get();
+	}: _(RawOrigin::Signed(caller), class_id, basis_points, beneficiary)
+	verify {
+		assert!(Royalties::<T>::contains_key(class_id));
+	}
+
+	claim_balance_swap {
+		let buyer: T::AccountId = account("buyer", 0, SEED);
+		fund::<_, T::Currency>(&buyer);
+		let seller: T::AccountId = whitelisted_caller();
+		let action = RoyaltySwapAction::Balance(1_000_000u32.into());
+		let proof = create_swap::<T>(buyer, seller.clone(), action.clone())?;
+	}: {
+		pallet_atomic_swap::Pallet::<T>::claim_swap(RawOrigin::Signed(seller).into(), proof, action)?;
+	}
+
+	claim_token_swap {
+		let seller: T::AccountId = account("seller", 0, SEED);
+		fund::<_, T::Currency>(&seller);
+		let buyer: T::AccountId = whitelisted_caller();
+		fund::<_, T::Currency>(&buyer);
+		let token = royalty_token::<T>(&seller)?;
+		let action = RoyaltySwapAction::Token { token, price: 1_000_000u32.into() };
+		let proof = create_swap::<T>(seller, buyer.clone(), action.clone())?;
+	}: {
+		pallet_atomic_swap::Pallet::<T>::claim_swap(RawOrigin::Signed(buyer.clone()).into(), proof, action)?;
+	}
+	verify {
+		assert!(orml_nft::Pallet::<T>::is_owner(&buyer, token));
+	}
+
+	recover_token {
+		let caller: T::AccountId = whitelisted_caller();
+		let token = royalty_token::<T>(&caller)?;
+		orml_nft::Pallet::<T>::transfer(&caller, &Pallet::<T>::account_id(), token)?;
+		StrandedTokens::<T>::insert(token, caller.clone());
+	}: _(RawOrigin::Signed(caller.clone()), token)
+	verify {
+		assert!(orml_nft::Pallet::<T>::is_owner(&caller, token));
+	}
+
+	recover_asset {
+		let caller: T::AccountId = whitelisted_caller();
+		fund::<_, T::Currency>(&Pallet::<T>::account_id());
+		StrandedAssets::<T>::insert(&caller, None::<T::AssetId>, BalanceOf::<T>::from(1_000_000u32));
+	}: _(RawOrigin::Signed(caller.clone()), None)
+	verify {
+		assert!(StrandedAssets::<T>::get(&caller, None::<T::AssetId>).is_zero());
+	}
+
+	create_token_swap {
+		let caller: T::AccountId = whitelisted_caller();
+		let buyer: T::AccountId = account("buyer", 0, SEED);
+		let token = royalty_token::<T>(&caller)?;
+		let action = RoyaltySwapAction::Token { token, price: 1_000_000u32.into() };
+		let hashed_proof = sp_io::hashing::blake2_256(&[1; 32]);
+	}: {
+		pallet_atomic_swap::Pallet::<T>::create_swap(
+			RawOrigin::Signed(caller).into(),
+			buyer,
+			hashed_proof,
+			action,
+			1_000u32.into(),
+		)?;
+	}
+	verify {
+		assert!(orml_nft::Pallet::<T>::is_owner(&Pallet::<T>::account_id(), token));
+	}
+
+	cancel_token_swap {
+		let caller: T::AccountId = whitelisted_caller();
+		let buyer: T::AccountId = account("buyer", 0, SEED);
+		let token = royalty_token::<T>(&caller)?;
+		let action = RoyaltySwapAction::Token { token, price: 1_000_000u32.into() };
+		let proof = create_swap::<T>(caller.clone(), buyer.clone(), action)?;
+		let end = frame_system::Pallet::<T>::block_number() + 1_000u32.into();
+		frame_system::Pallet::<T>::set_block_number(end);
+	}: {
+		pallet_atomic_swap::Pallet::<T>::cancel_swap(
+			RawOrigin::Signed(caller.clone()).into(),
+			buyer,
+			sp_io::hashing::blake2_256(&proof),
+		)?;
+	}
+	verify {
+		assert!(orml_nft::Pallet::<T>::is_owner(&caller, token));
+	}
+}
+
+impl_benchmark_test_suite!(Pallet, crate::mock::new_test_ext(), crate::mock::Test);
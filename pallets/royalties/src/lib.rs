@@ -0,0 +1,622 @@
+//! # Royalties Pallet
+//!
+//! Lets creators attach a royalty to an `orml_nft` class. The royalty is a share of the sale
+//! price, expressed in basis points, that is paid to a beneficiary every time a token of the
+//! class changes hands through a priced transfer.
+//!
+//! Classes created through this pallet must carry metadata that follows the Chiba Studio
+//! metadata standard of `chiba_nft_metadata`.
+//!
+//! A royalty can be set when a class is created through this pallet, or by the class owner
+//! on an existing class that has not minted any tokens yet. Once set it cannot be changed,
+//! so buyers of existing tokens know what they will owe on resale.
+//!
+//! Other pallets that sell tokens charge royalties through the [`RoyaltyHandler`] trait. For
+//! `pallet_atomic_swap`, [`RoyaltySwapAction`] escrows the token and has the buyer pay its
+//! price, royalty included, when the swap is claimed. Tokens of classes with a royalty can
+//! only change hands through such sales; [`RoyaltyClasses`] tells other pallets which classes
+//! those are. Tokens of classes frozen by moderators cannot be offered or sold through swaps.
+//!
+//! Tokens and assets that cannot be returned from escrow when a swap fails or is cancelled are
+//! recorded, and their owner can retry with `recover_token` or `recover_asset`.
+//!
+//! Prices may be in the native currency or in an asset. Payments in either go through the
+//! [`MultiAsset`] trait, where an asset ID of `None` stands for the native currency.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use codec::{Decode, Encode};
+use frame_support::{
+	dispatch::DispatchResult,
+	traits::{BalanceStatus, Currency, ExistenceRequirement, Get, ReservableCurrency},
+	transactional,
+	weights::{DispatchClass, Weight},
+};
+use pallet_moderation::ClassModeration;
+use sp_runtime::{
+	traits::{AccountIdConversion, Zero},
+	DispatchError, Permill, RuntimeDebug,
+};
+
+pub use pallet::*;
+pub use weights::WeightInfo;
+
+mod benchmarking;
+#[cfg(test)]
+mod mock;
+#[cfg(test)]
+mod tests;
+#[cfg(any(feature = "runtime-benchmarks", feature = "std"))]
+pub mod testing_utils;
+pub mod weights;
+
+pub type BalanceOf<T> =
+	<<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
+pub type TokenIdOf<T> = (<T as orml_nft::Config>::ClassId, <T as orml_nft::Config>::TokenId);
+
+/// The number of basis points that make up the whole sale price.
+pub const MAX_BASIS_POINTS: u16 = 10_000;
+
+/// The royalty attached to an NFT class.
+#[derive(Clone, Eq, PartialEq, Encode, Decode, RuntimeDebug)]
+pub struct RoyaltyInfo<AccountId> {
+	/// The account that receives royalty payments.
+	pub beneficiary: AccountId,
+	/// The share of the sale price paid as royalty, in basis points.
+	pub basis_points: u16,
+}
+
+impl<AccountId> RoyaltyInfo<AccountId> {
+	/// The royalty owed on a sale for `price`, rounded down.
+	pub fn amount<Balance: sp_runtime::traits::AtLeast32BitUnsigned + Copy>(
+		&self,
+		price: Balance,
+	) -> Balance {
+		Permill::from_parts(u32::from(self.basis_points) * 100).mul_floor(price)
+	}
+}
+
+/// Moves balances of the native currency and of assets.
+pub trait MultiAsset<AccountId, AssetId, Balance> {
+	/// Transfers `amount` of `asset` from `from` to `to`. An `asset` of `None` is the native
+	/// currency, for which `existence` applies as for `Currency::transfer`.
+	fn transfer(
+		asset: Option<AssetId>,
+		from: &AccountId,
+		to: &AccountId,
+		amount: Balance,
+		existence: ExistenceRequirement,
+	) -> DispatchResult;
+}
+
+/// Moves balances of the native currency `C` only, for runtimes without assets. Transfers of
+/// assets fail.
+pub struct NativeOnly<C>(sp_std::marker::PhantomData<C>);
+
+impl<AccountId, AssetId, C: Currency<AccountId>> MultiAsset<AccountId, AssetId, C::Balance>
+	for NativeOnly<C>
+{
+	fn transfer(
+		asset: Option<AssetId>,
+		from: &AccountId,
+		to: &AccountId,
+		amount: C::Balance,
+		existence: ExistenceRequirement,
+	) -> DispatchResult {
+		match asset {
+			None => C::transfer(from, to, amount, existence),
+			Some(_) => Err(DispatchError::Other("assets are not supported")),
+		}
+	}
+}
+
+/// Tells other pallets which classes charge a royalty.
+pub trait RoyaltyClasses<ClassId> {
+	/// Whether tokens of `class_id` owe a royalty whenever they change hands.
+	fn has_royalty(class_id: &ClassId) -> bool;
+}
+
+impl<ClassId> RoyaltyClasses<ClassId> for () {
+	fn has_royalty(_: &ClassId) -> bool {
+		false
+	}
+}
+
+/// Charges royalties on behalf of pallets that sell NFTs.
+pub trait RoyaltyHandler<AccountId, ClassId, TokenId, Balance> {
+	/// The identifier of the assets prices may be paid in.
+	type AssetId;
+
+	/// The royalty owed on a sale of a token of `class_id` for `price`.
+	fn royalty(class_id: ClassId, price: Balance) -> Balance;
+
+	/// Pays the royalty owed on a sale of `token` for `price` of `asset` (`None` for the
+	/// native currency) from `payer` to the class beneficiary, returning the amount paid.
+	///
+	/// The royalty comes out of the price: callers pay the seller `price` minus the returned
+	/// amount.
+	fn pay_royalty(
+		payer: &AccountId,
+		token: (ClassId, TokenId),
+		asset: Option<Self::AssetId>,
+		price: Balance,
+	) -> Result<Balance, DispatchError>;
+}
+
+impl<AccountId, ClassId, TokenId, Balance: Zero> RoyaltyHandler<AccountId, ClassId, TokenId, Balance>
+	for ()
+{
+	type AssetId = ();
+
+	fn royalty(_: ClassId, _: Balance) -> Balance {
+		Zero::zero()
+	}
+
+	fn pay_royalty(
+		_: &AccountId,
+		_: (ClassId, TokenId),
+		_: Option<()>,
+		_: Balance,
+	) -> Result<Balance, DispatchError> {
+		Ok(Zero::zero())
+	}
+}
+
+#[frame_support::pallet]
+pub mod pallet {
+	use frame_support::{dispatch::DispatchResultWithPostInfo, pallet_prelude::*, PalletId};
+	use frame_system::pallet_prelude::*;
+	use sp_runtime::traits::StaticLookup;
+	use sp_std::vec::Vec;
+	use super::*;
+
+	#[pallet::config]
+	pub trait Config: frame_system::Config + orml_nft::Config {
+		/// Because this pallet emits events, it depends on the runtime's definition of an event.
+		type Event: From<Event<Self>> + IsType<<Self as frame_system::Config>::Event>;
+
+		/// The native currency, in which swapped balances are reserved.
+		type Currency: ReservableCurrency<Self::AccountId>;
+
+		/// The identifier of the assets prices may be paid in.
+		type AssetId: Member + Parameter + Copy;
+
+		/// Pays royalties in the native currency or in assets.
+		type Assets: MultiAsset<Self::AccountId, Self::AssetId, BalanceOf<Self>>;
+
+		/// Stops swapping tokens of classes frozen by moderators.
+		type Moderation: ClassModeration<Self::ClassId>;
+
+		/// The pallet's ID, used to derive the account that escrows swapped tokens.
+		#[pallet::constant]
+		type PalletId: Get<PalletId>;
+
+		/// The highest royalty a class may charge, in basis points.
+		#[pallet::constant]
+		type MaxRoyaltyBasisPoints: Get<u16>;
+
+		/// Weight information for extrinsics in this pallet.
+		type WeightInfo: WeightInfo;
+	}
+
+	#[pallet::pallet]
+	#[pallet::generate_store(pub(super) trait Store)]
+	pub struct Pallet<T>(_);
+
+	/// The royalty of each NFT class that has one.
+	#[pallet::storage]
+	#[pallet::getter(fn royalties)]
+	pub type Royalties<T: Config> =
+		StorageMap<_, Twox64Concat, T::ClassId, RoyaltyInfo<T::AccountId>>;
+
+	/// Tokens that could not be returned from escrow, with the account they belong to.
+	#[pallet::storage]
+	#[pallet::getter(fn stranded_tokens)]
+	pub type StrandedTokens<T: Config> = StorageMap<_, Twox64Concat, TokenIdOf<T>, T::AccountId>;
+
+	/// Balances that could not be returned from escrow, by owner and asset (`None` for the
+	/// native currency).
+	#[pallet::storage]
+	#[pallet::getter(fn stranded_assets)]
+	pub type StrandedAssets<T: Config> = StorageDoubleMap<
+		_,
+		Twox64Concat,
+		T::AccountId,
+		Twox64Concat,
+		Option<T::AssetId>,
+		BalanceOf<T>,
+		ValueQuery,
+	>;
+
+	#[pallet::event]
+	#[pallet::metadata(
+		T::AccountId = "AccountId",
+		T::AssetId = "AssetId",
+		T::ClassId = "ClassId",
+		T::TokenId = "TokenId",
+		BalanceOf<T> = "Balance"
+	)]
+	#[pallet::generate_deposit(pub(super) fn deposit_event)]
+	pub enum Event<T: Config> {
+		/// A class was created with a royalty. \[class_id, owner\]
+		ClassCreated(T::ClassId, T::AccountId),
+		/// A royalty was attached to a class. \[class_id, beneficiary, basis_points\]
+		RoyaltySet(T::ClassId, T::AccountId, u16),
+		/// A royalty was paid on the sale of a token.
+		/// \[class_id, token_id, payer, beneficiary, asset, amount\]
+		RoyaltyPaid(T::ClassId, T::TokenId, T::AccountId, T::AccountId, Option<T::AssetId>, BalanceOf<T>),
+		/// A token could not be returned from escrow and can be recovered by its owner.
+		/// \[class_id, token_id, owner\]
+		TokenStranded(T::ClassId, T::TokenId, T::AccountId),
+		/// A stranded token was returned to its owner. \[class_id, token_id, owner\]
+		TokenRecovered(T::ClassId, T::TokenId, T::AccountId),
+		/// A balance could not be returned from escrow and can be recovered by its owner.
+		/// \[owner, asset, amount\]
+		AssetStranded(T::AccountId, Option<T::AssetId>, BalanceOf<T>),
+		/// A stranded balance was returned to its owner. \[owner, asset, amount\]
+		AssetRecovered(T::AccountId, Option<T::AssetId>, BalanceOf<T>),
+	}
+
+	#[pallet::error]
+	pub enum Error<T> {
+		/// The class does not exist.
+		ClassNotFound,
+		/// Only the class owner may set its royalty.
+		NoPermission,
+		/// The royalty exceeds `MaxRoyaltyBasisPoints`.
+		RoyaltyTooHigh,
+		/// The class already has a royalty.
+		RoyaltyAlreadySet,
+		/// A royalty can only be set before any token of the class is minted.
+		ClassNotEmpty,
+		/// The account does not own the token.
+		NotTokenOwner,
+		/// The class metadata does not follow the metadata standard.
+		InvalidMetadata,
+		/// Nothing is stranded in escrow for the account.
+		NothingStranded,
+		/// The token's class has been frozen by moderators.
+		ClassFrozen,
+	}
+
+	#[pallet::hooks]
+	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {}
+
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {
+		/// Create an NFT class that pays `basis_points` of every sale to `beneficiary`.
+		///
+		/// `metadata` must be an encoded `chiba_nft_metadata::Metadata`.
+		#[pallet::weight(T::WeightInfo::create_class())]
+		pub fn create_class(
+			origin: OriginFor<T>,
+			metadata: Vec<u8>,
+			data: T::ClassData,
+			basis_points: u16,
+			beneficiary: <T::Lookup as StaticLookup>::Source,
+		) -> DispatchResultWithPostInfo {
+			let who = ensure_signed(origin)?;
+			let beneficiary = T::Lookup::lookup(beneficiary)?;
+			ensure!(basis_points <= T::MaxRoyaltyBasisPoints::get(), Error::<T>::RoyaltyTooHigh);
+			chiba_nft_metadata::parse(&metadata).map_err(|_| Error::<T>::InvalidMetadata)?;
+
+			let class_id = orml_nft::Pallet::<T>::create_class(&who, metadata, data)?;
+			Self::insert_royalty(class_id, beneficiary, basis_points);
+
+			Self::deposit_event(Event::ClassCreated(class_id, who));
+			Ok(().into())
+		}
+
+		/// Attach a royalty to a class that has no royalty and no tokens yet.
+		///
+		/// The dispatch origin must be the class owner.
+		#[pallet::weight(T::WeightInfo::set_royalty())]
+		pub fn set_royalty(
+			origin: OriginFor<T>,
+			class_id: T::ClassId,
+			basis_points: u16,
+			beneficiary: <T::Lookup as StaticLookup>::Source,
+		) -> DispatchResultWithPostInfo {
+			let who = ensure_signed(origin)?;
+			let beneficiary = T::Lookup::lookup(beneficiary)?;
+			ensure!(basis_points <= T::MaxRoyaltyBasisPoints::get(), Error::<T>::RoyaltyTooHigh);
+
+			let class = orml_nft::Pallet::<T>::classes(class_id).ok_or(Error::<T>::ClassNotFound)?;
+			ensure!(class.owner == who, Error::<T>::NoPermission);
+			ensure!(class.total_issuance.is_zero(), Error::<T>::ClassNotEmpty);
+			ensure!(!Royalties::<T>::contains_key(class_id), Error::<T>::RoyaltyAlreadySet);
+
+			Self::insert_royalty(class_id, beneficiary, basis_points);
+			Ok(().into())
+		}
+
+		/// Retry returning a token that could not be returned from escrow.
+		///
+		/// The dispatch origin must be the account the token belongs to.
+		#[pallet::weight(T::WeightInfo::recover_token())]
+		pub fn recover_token(origin: OriginFor<T>, token: TokenIdOf<T>) -> DispatchResultWithPostInfo {
+			let who = ensure_signed(origin)?;
+			let owner = StrandedTokens::<T>::get(token).ok_or(Error::<T>::NothingStranded)?;
+			ensure!(owner == who, Error::<T>::NothingStranded);
+
+			orml_nft::Pallet::<T>::transfer(&Self::account_id(), &who, token)?;
+			StrandedTokens::<T>::remove(token);
+
+			Self::deposit_event(Event::TokenRecovered(token.0, token.1, who));
+			Ok(().into())
+		}
+
+		/// Retry returning a balance of `asset` (`None` for the native currency) that could not
+		/// be returned from escrow.
+		#[pallet::weight(T::WeightInfo::recover_asset())]
+		pub fn recover_asset(
+			origin: OriginFor<T>,
+			asset: Option<T::AssetId>,
+		) -> DispatchResultWithPostInfo {
+			let who = ensure_signed(origin)?;
+			let amount = StrandedAssets::<T>::get(&who, asset);
+			ensure!(!amount.is_zero(), Error::<T>::NothingStranded);
+
+			T::Assets::transfer(
+				asset,
+				&Self::account_id(),
+				&who,
+				amount,
+				ExistenceRequirement::AllowDeath,
+			)?;
+			StrandedAssets::<T>::remove(&who, asset);
+
+			Self::deposit_event(Event::AssetRecovered(who, asset, amount));
+			Ok(().into())
+		}
+	}
+
+	impl<T: Config> Pallet<T> {
+		/// The account that escrows tokens and assets offered through atomic swaps.
+		pub fn account_id() -> T::AccountId {
+			T::PalletId::get().into_account()
+		}
+
+		/// Return an escrowed token to `owner`, or record it for `recover_token` if that fails.
+		pub(crate) fn release_token(token: TokenIdOf<T>, owner: &T::AccountId) {
+			if orml_nft::Pallet::<T>::transfer(&Self::account_id(), owner, token).is_err() {
+				StrandedTokens::<T>::insert(token, owner.clone());
+				Self::deposit_event(Event::TokenStranded(token.0, token.1, owner.clone()));
+			}
+		}
+
+		/// Return an escrowed balance to `owner`, or record it for `recover_asset` if that
+		/// fails.
+		pub(crate) fn release_asset(asset: Option<T::AssetId>, owner: &T::AccountId, amount: BalanceOf<T>) {
+			if amount.is_zero() {
+				return
+			}
+			let escrow = Self::account_id();
+			if T::Assets::transfer(asset, &escrow, owner, amount, ExistenceRequirement::AllowDeath).is_err() {
+				StrandedAssets::<T>::mutate(owner, asset, |stranded| *stranded = stranded.saturating_add(amount));
+				Self::deposit_event(Event::AssetStranded(owner.clone(), asset, amount));
+			}
+		}
+
+		/// Sell an escrowed token to `buyer`, who pays `price` of `asset`: the royalty to the
+		/// class beneficiary and the rest to `seller`. Nothing changes if any step fails or the
+		/// class is frozen.
+		#[transactional]
+		pub(crate) fn complete_sale(
+			seller: &T::AccountId,
+			buyer: &T::AccountId,
+			token: TokenIdOf<T>,
+			asset: Option<T::AssetId>,
+			price: BalanceOf<T>,
+		) -> DispatchResult {
+			ensure!(!T::Moderation::is_frozen(&token.0), Error::<T>::ClassFrozen);
+			let royalty = <Self as RoyaltyHandler<_, _, _, _>>::pay_royalty(buyer, token, asset, price)?;
+			T::Assets::transfer(
+				asset,
+				buyer,
+				seller,
+				price.saturating_sub(royalty),
+				ExistenceRequirement::KeepAlive,
+			)?;
+			orml_nft::Pallet::<T>::transfer(&Self::account_id(), buyer, token)
+		}
+
+		/// Attach a royalty to `class_id`. A royalty of 0 basis points is not stored, so that the
+		/// class is not treated as a royalty class.
+		fn insert_royalty(class_id: T::ClassId, beneficiary: T::AccountId, basis_points: u16) {
+			if basis_points == 0 {
+				return
+			}
+			Royalties::<T>::insert(class_id, RoyaltyInfo {
+				beneficiary: beneficiary.clone(),
+				basis_points,
+			});
+			Self::deposit_event(Event::RoyaltySet(class_id, beneficiary, basis_points));
+		}
+	}
+}
+
+impl<T: Config> RoyaltyHandler<T::AccountId, T::ClassId, T::TokenId, BalanceOf<T>> for Pallet<T> {
+	type AssetId = T::AssetId;
+
+	fn royalty(class_id: T::ClassId, price: BalanceOf<T>) -> BalanceOf<T> {
+		Royalties::<T>::get(class_id)
+			.map(|royalty| royalty.amount(price))
+			.unwrap_or_else(Zero::zero)
+	}
+
+	fn pay_royalty(
+		payer: &T::AccountId,
+		(class_id, token_id): TokenIdOf<T>,
+		asset: Option<T::AssetId>,
+		price: BalanceOf<T>,
+	) -> Result<BalanceOf<T>, DispatchError> {
+		let royalty = match Royalties::<T>::get(class_id) {
+			Some(royalty) => royalty,
+			None => return Ok(Zero::zero()),
+		};
+		let amount = royalty.amount(price);
+		if amount.is_zero() {
+			return Ok(amount)
+		}
+
+		T::Assets::transfer(asset, payer, &royalty.beneficiary, amount, ExistenceRequirement::KeepAlive)?;
+		Self::deposit_event(Event::RoyaltyPaid(
+			class_id,
+			token_id,
+			payer.clone(),
+			royalty.beneficiary,
+			asset,
+			amount,
+		));
+		Ok(amount)
+	}
+}
+
+impl<T: Config> RoyaltyClasses<T::ClassId> for Pallet<T> {
+	fn has_royalty(class_id: &T::ClassId) -> bool {
+		Royalties::<T>::get(class_id).map_or(false, |royalty| royalty.basis_points > 0)
+	}
+}
+
+/// A swap action for `pallet_atomic_swap` that settles royalties on NFT sales.
+///
+/// A token is swapped together with its price. The seller escrows the token when creating the
+/// swap, and the buyer pays the price when claiming it: the class royalty to the beneficiary and
+/// the rest to the seller. The royalty is therefore always charged on what the buyer pays. If
+/// the buyer cannot pay, the claim fails and the token goes back to the seller.
+///
+/// Balances and assets can also be swapped on their own, for instance against a token on
+/// another chain. Native balances are reserved; assets cannot be reserved, so they are escrowed
+/// by this pallet instead.
+#[derive(Clone, Eq, PartialEq, Encode, Decode, RuntimeDebug)]
+pub enum RoyaltySwapAction<T: Config> {
+	/// Swap a balance, reserved from the source until claimed.
+	Balance(BalanceOf<T>),
+	/// Sell a token for a price in the native currency, paid by the claimer.
+	Token {
+		/// The class and token ID of the swapped token.
+		token: TokenIdOf<T>,
+		/// The price the claimer pays for the token.
+		price: BalanceOf<T>,
+	},
+	/// Swap an amount of an asset, escrowed by this pallet until claimed.
+	Asset {
+		/// The swapped asset.
+		asset_id: T::AssetId,
+		/// The amount swapped.
+		amount: BalanceOf<T>,
+	},
+	/// Sell a token for a price in an asset, paid by the claimer.
+	TokenForAsset {
+		/// The class and token ID of the swapped token.
+		token: TokenIdOf<T>,
+		/// The asset the price is paid in.
+		asset_id: T::AssetId,
+		/// The price the claimer pays for the token.
+		price: BalanceOf<T>,
+	},
+}
+
+impl<T> pallet_atomic_swap::SwapAction<<T as frame_system::Config>::AccountId, T>
+	for RoyaltySwapAction<T>
+where
+	T: Config + pallet_atomic_swap::Config,
+{
+	fn reserve(&self, source: &T::AccountId) -> DispatchResult {
+		match self {
+			Self::Balance(value) => T::Currency::reserve(source, *value),
+			Self::Token { token, .. } | Self::TokenForAsset { token, .. } => {
+				register_swap_weight::<T>(<T as Config>::WeightInfo::create_token_swap());
+				frame_support::ensure!(
+					orml_nft::Pallet::<T>::is_owner(source, *token),
+					Error::<T>::NotTokenOwner
+				);
+				frame_support::ensure!(!T::Moderation::is_frozen(&token.0), Error::<T>::ClassFrozen);
+				orml_nft::Pallet::<T>::transfer(source, &Pallet::<T>::account_id(), *token)
+			},
+			Self::Asset { asset_id, amount } => {
+				register_swap_weight::<T>(<T as Config>::WeightInfo::create_token_swap());
+				T::Assets::transfer(
+					Some(*asset_id),
+					source,
+					&Pallet::<T>::account_id(),
+					*amount,
+					ExistenceRequirement::KeepAlive,
+				)
+			},
+		}
+	}
+
+	/// `pallet_atomic_swap` removes the swap whatever the outcome, so whatever a failed claim
+	/// leaves in escrow is returned to the source.
+	fn claim(&self, source: &T::AccountId, target: &T::AccountId) -> bool {
+		match self {
+			Self::Balance(value) => {
+				let claimed =
+					T::Currency::repatriate_reserved(source, target, *value, BalanceStatus::Free).is_ok();
+				if !claimed {
+					T::Currency::unreserve(source, *value);
+				}
+				claimed
+			},
+			Self::Token { token, price } => {
+				let sold = Pallet::<T>::complete_sale(source, target, *token, None, *price).is_ok();
+				if !sold {
+					Pallet::<T>::release_token(*token, source);
+				}
+				sold
+			},
+			Self::Asset { asset_id, amount } => {
+				let escrow = Pallet::<T>::account_id();
+				let existence = ExistenceRequirement::AllowDeath;
+				let claimed = T::Assets::transfer(Some(*asset_id), &escrow, target, *amount, existence).is_ok();
+				if !claimed {
+					Pallet::<T>::release_asset(Some(*asset_id), source, *amount);
+				}
+				claimed
+			},
+			Self::TokenForAsset { token, asset_id, price } => {
+				let sold = Pallet::<T>::complete_sale(source, target, *token, Some(*asset_id), *price).is_ok();
+				if !sold {
+					Pallet::<T>::release_token(*token, source);
+				}
+				sold
+			},
+		}
+	}
+
+	/// The benchmarked cost of the whole `claim_swap` call, which slightly overestimates since
+	/// `claim_swap` adds its own base weight on top.
+	fn weight(&self) -> Weight {
+		match self {
+			Self::Balance(_) | Self::Asset { .. } => <T as Config>::WeightInfo::claim_balance_swap(),
+			Self::Token { .. } | Self::TokenForAsset { .. } => {
+				<T as Config>::WeightInfo::claim_token_swap()
+			},
+		}
+	}
+
+	fn cancel(&self, source: &T::AccountId) {
+		match self {
+			Self::Balance(value) => {
+				T::Currency::unreserve(source, *value);
+			},
+			Self::Token { token, .. } | Self::TokenForAsset { token, .. } => {
+				register_swap_weight::<T>(<T as Config>::WeightInfo::cancel_token_swap());
+				Pallet::<T>::release_token(*token, source);
+			},
+			Self::Asset { asset_id, amount } => {
+				register_swap_weight::<T>(<T as Config>::WeightInfo::cancel_token_swap());
+				Pallet::<T>::release_asset(Some(*asset_id), source, *amount);
+			},
+		}
+	}
+}
+
+/// `pallet_atomic_swap` charges `create_swap` and `cancel_swap` a fixed weight that does not
+/// cover escrowing a token or an asset. The benchmarked cost of the whole call is added to the
+/// block's weight on top, which overestimates slightly but keeps blocks within their limits.
+fn register_swap_weight<T: Config>(weight: Weight) {
+	frame_system::Pallet::<T>::register_extra_weight_unchecked(weight, DispatchClass::Normal);
+}
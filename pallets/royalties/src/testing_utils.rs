@@ -0,0 +1,86 @@
+//! Helpers for the benchmarks and tests of this pallet and of the pallets that sell tokens
+//! through it.
+
+use super::*;
+use chiba_nft_metadata::{
+	Attribute, Metadata, MAX_ATTRIBUTES, MAX_ATTRIBUTE_KEY_LEN, MAX_ATTRIBUTE_VALUE_LEN,
+	MAX_DESCRIPTION_LEN, MAX_NAME_LEN,
+};
+#[cfg(feature = "runtime-benchmarks")]
+use frame_benchmarking::account;
+use sp_runtime::traits::{Bounded, TrailingZeroInput};
+use sp_std::{vec, vec::Vec};
+
+#[cfg(feature = "runtime-benchmarks")]
+const SEED: u32 = 0;
+
+/// A value of a type the caller cannot construct, such as the runtime's class and token data or
+/// asset IDs, decoded from zeroes.
+pub fn zeroed<D: Decode>() -> D {
+	D::decode(&mut TrailingZeroInput::new(&[][..])).expect("input is infinite; qed")
+}
+
+/// Metadata of the largest size the standard allows, which is the slowest to validate.
+pub fn largest_metadata() -> Vec<u8> {
+	Metadata {
+		name: vec![b'n'; MAX_NAME_LEN],
+		description: vec![b'd'; MAX_DESCRIPTION_LEN],
+		media: b"bafybeigdyrzt5sfp7udm7hu76uh7y26nf3efuylqabf3oclgtqy55fbzdi".to_vec(),
+		mime_type: b"image/png".to_vec(),
+		attributes: (0..MAX_ATTRIBUTES as u8)
+			.map(|i| Attribute {
+				key: vec![b'a' + i; MAX_ATTRIBUTE_KEY_LEN],
+				value: vec![b'v'; MAX_ATTRIBUTE_VALUE_LEN],
+			})
+			.collect(),
+	}.encode()
+}
+
+/// Gives `who` half the largest balance of `C`, enough for any price and royalty.
+pub fn fund<AccountId, C: Currency<AccountId>>(who: &AccountId) {
+	C::make_free_balance_be(who, C::Balance::max_value() / 2u32.into());
+}
+
+/// Mints a token of a new class without a royalty to `owner`.
+pub fn token<T: orml_nft::Config>(owner: &T::AccountId) -> Result<TokenIdOf<T>, &'static str> {
+	let class_id = orml_nft::Pallet::<T>::create_class(owner, vec![0; 64], zeroed())?;
+	let token_id = orml_nft::Pallet::<T>::mint(owner, class_id, vec![0; 64], zeroed())?;
+	Ok((class_id, token_id))
+}
+
+/// Mints a token of a new class to `owner`, with a royalty of `basis_points` paid to
+/// `beneficiary` unless `basis_points` is zero.
+pub fn token_with_royalty<T: Config>(
+	owner: &T::AccountId,
+	basis_points: u16,
+	beneficiary: T::AccountId,
+) -> Result<TokenIdOf<T>, &'static str> {
+	let class_id = orml_nft::Pallet::<T>::create_class(owner, vec![0; 64], zeroed())?;
+	if basis_points > 0 {
+		Royalties::<T>::insert(class_id, RoyaltyInfo { beneficiary, basis_points });
+	}
+	let token_id = orml_nft::Pallet::<T>::mint(owner, class_id, vec![0; 64], zeroed())?;
+	Ok((class_id, token_id))
+}
+
+/// Creates a class that charges the highest allowed royalty, so sales take the most expensive
+/// path when the runtime charges royalties through this pallet.
+#[cfg(feature = "runtime-benchmarks")]
+pub fn royalty_class<T: Config>(owner: &T::AccountId) -> Result<T::ClassId, &'static str> {
+	let beneficiary = account("beneficiary", 0, SEED);
+	fund::<_, T::Currency>(&beneficiary);
+	let class_id = orml_nft::Pallet::<T>::create_class(owner, vec![0; 64], zeroed())?;
+	Royalties::<T>::insert(class_id, RoyaltyInfo {
+		beneficiary,
+		basis_points: T::MaxRoyaltyBasisPoints::get(),
+	});
+	Ok(class_id)
+}
+
+/// Mints a token of a new class that charges the highest allowed royalty to `owner`.
+#[cfg(feature = "runtime-benchmarks")]
+pub fn royalty_token<T: Config>(owner: &T::AccountId) -> Result<TokenIdOf<T>, &'static str> {
+	let class_id = royalty_class::<T>(owner)?;
+	let token_id = orml_nft::Pallet::<T>::mint(owner, class_id, vec![0; 64], zeroed())?;
+	Ok((class_id, token_id))
+}
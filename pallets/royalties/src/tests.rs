@@ -0,0 +1,237 @@
+//! Tests for the royalties pallet.
+
+use super::{Error, RoyaltyClasses, RoyaltyInfo, RoyaltySwapAction, StrandedTokens, TokenIdOf};
+use crate::mock::*;
+use crate::testing_utils::{largest_metadata, token, token_with_royalty};
+use frame_support::{assert_err, assert_noop, assert_ok};
+use pallet_atomic_swap::SwapAction;
+
+fn owner_of(token: TokenIdOf<Test>) -> u64 {
+	Nft::tokens(token.0, token.1).unwrap().owner
+}
+
+/// Puts a token of Alice's with a 10% royalty to Charlie in escrow.
+fn escrowed() -> TokenIdOf<Test> {
+	let token = token_with_royalty::<Test>(&ALICE, 1_000, CHARLIE).unwrap();
+	assert_ok!(Nft::transfer(&ALICE, &Royalties::account_id(), token));
+	token
+}
+
+#[test]
+fn create_class_sets_the_royalty() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Royalties::create_class(Origin::signed(ALICE), largest_metadata(), (), 1_000, CHARLIE));
+		assert_eq!(Royalties::royalties(0), Some(RoyaltyInfo { beneficiary: CHARLIE, basis_points: 1_000 }));
+		assert!(Royalties::has_royalty(&0));
+
+		assert_noop!(
+			Royalties::create_class(Origin::signed(ALICE), largest_metadata(), (), 5_001, CHARLIE),
+			Error::<Test>::RoyaltyTooHigh
+		);
+		assert_noop!(
+			Royalties::create_class(Origin::signed(ALICE), vec![], (), 1_000, CHARLIE),
+			Error::<Test>::InvalidMetadata
+		);
+	});
+}
+
+#[test]
+fn zero_royalties_are_not_recorded() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Royalties::create_class(Origin::signed(ALICE), largest_metadata(), (), 0, CHARLIE));
+		assert_eq!(Royalties::royalties(0), None);
+		assert!(!Royalties::has_royalty(&0));
+
+		let class_id = Nft::create_class(&ALICE, vec![], ()).unwrap();
+		assert_ok!(Royalties::set_royalty(Origin::signed(ALICE), class_id, 0, CHARLIE));
+		assert_eq!(Royalties::royalties(class_id), None);
+	});
+}
+
+#[test]
+fn royalties_are_set_on_empty_classes_by_their_owner() {
+	new_test_ext().execute_with(|| {
+		let class_id = Nft::create_class(&ALICE, vec![], ()).unwrap();
+		assert_noop!(
+			Royalties::set_royalty(Origin::signed(ALICE), class_id + 1, 1_000, CHARLIE),
+			Error::<Test>::ClassNotFound
+		);
+		assert_noop!(
+			Royalties::set_royalty(Origin::signed(BOB), class_id, 1_000, CHARLIE),
+			Error::<Test>::NoPermission
+		);
+		assert_noop!(
+			Royalties::set_royalty(Origin::signed(ALICE), class_id, 5_001, CHARLIE),
+			Error::<Test>::RoyaltyTooHigh
+		);
+
+		assert_ok!(Royalties::set_royalty(Origin::signed(ALICE), class_id, 1_000, CHARLIE));
+		assert_noop!(
+			Royalties::set_royalty(Origin::signed(ALICE), class_id, 500, CHARLIE),
+			Error::<Test>::RoyaltyAlreadySet
+		);
+	});
+}
+
+#[test]
+fn royalties_cannot_be_set_once_tokens_exist() {
+	new_test_ext().execute_with(|| {
+		let (class_id, _) = token::<Test>(&ALICE).unwrap();
+		assert_noop!(
+			Royalties::set_royalty(Origin::signed(ALICE), class_id, 1_000, CHARLIE),
+			Error::<Test>::ClassNotEmpty
+		);
+	});
+}
+
+#[test]
+fn sales_pay_the_royalty_and_the_seller() {
+	new_test_ext().execute_with(|| {
+		let token = escrowed();
+		assert_ok!(Royalties::complete_sale(&ALICE, &BOB, token, None, 100));
+		assert_eq!(owner_of(token), BOB);
+		assert_eq!(Balances::free_balance(BOB), 900);
+		assert_eq!(Balances::free_balance(CHARLIE), 1_010);
+		assert_eq!(Balances::free_balance(ALICE), 1_090);
+
+		let token = escrowed();
+		assert_ok!(Royalties::complete_sale(&ALICE, &BOB, token, Some(ASSET), 100));
+		assert_eq!(owner_of(token), BOB);
+		assert_eq!(Assets::balance(ASSET, BOB), 900);
+		assert_eq!(Assets::balance(ASSET, CHARLIE), 1_010);
+		assert_eq!(Assets::balance(ASSET, ALICE), 1_090);
+	});
+}
+
+#[test]
+fn failed_sales_change_nothing() {
+	new_test_ext().execute_with(|| {
+		let token = escrowed();
+		// Bob can pay the royalty but not the rest of the price.
+		assert_noop!(
+			Royalties::complete_sale(&ALICE, &BOB, token, None, 2_000),
+			pallet_balances::Error::<Test, _>::InsufficientBalance
+		);
+	});
+}
+
+#[test]
+fn token_swaps_escrow_the_token_and_sell_it_on_claim() {
+	new_test_ext().execute_with(|| {
+		let token = token_with_royalty::<Test>(&ALICE, 1_000, CHARLIE).unwrap();
+		let action = RoyaltySwapAction::<Test>::Token { token, price: 100 };
+		assert_err!(action.reserve(&BOB), Error::<Test>::NotTokenOwner);
+
+		assert_ok!(action.reserve(&ALICE));
+		assert_eq!(owner_of(token), Royalties::account_id());
+
+		assert!(action.claim(&ALICE, &BOB));
+		assert_eq!(owner_of(token), BOB);
+		assert_eq!(Balances::free_balance(BOB), 900);
+		assert_eq!(Balances::free_balance(CHARLIE), 1_010);
+		assert_eq!(Balances::free_balance(ALICE), 1_090);
+	});
+}
+
+#[test]
+fn failed_and_cancelled_token_swaps_return_the_token() {
+	new_test_ext().execute_with(|| {
+		let token = token::<Test>(&ALICE).unwrap();
+		let action = RoyaltySwapAction::<Test>::TokenForAsset { token, asset_id: ASSET, price: 2_000 };
+		assert_ok!(action.reserve(&ALICE));
+		assert!(!action.claim(&ALICE, &BOB));
+		assert_eq!(owner_of(token), ALICE);
+		assert_eq!(Assets::balance(ASSET, BOB), 1_000);
+
+		assert_ok!(action.reserve(&ALICE));
+		action.cancel(&ALICE);
+		assert_eq!(owner_of(token), ALICE);
+	});
+}
+
+#[test]
+fn asset_swaps_escrow_the_asset_until_claimed() {
+	new_test_ext().execute_with(|| {
+		let action = RoyaltySwapAction::<Test>::Asset { asset_id: ASSET, amount: 100 };
+		assert_ok!(action.reserve(&ALICE));
+		assert_eq!(Assets::balance(ASSET, ALICE), 900);
+		assert_eq!(Assets::balance(ASSET, Royalties::account_id()), 100);
+
+		assert!(action.claim(&ALICE, &BOB));
+		assert_eq!(Assets::balance(ASSET, BOB), 1_100);
+		assert_eq!(Assets::balance(ASSET, Royalties::account_id()), 0);
+	});
+}
+
+#[test]
+fn stranded_assets_can_be_recovered() {
+	new_test_ext().execute_with(|| {
+		let escrow = Royalties::account_id();
+		let action = RoyaltySwapAction::<Test>::Asset { asset_id: ASSET, amount: 100 };
+		assert_ok!(action.reserve(&ALICE));
+		assert_ok!(Assets::freeze(Origin::signed(ALICE), ASSET, escrow));
+
+		action.cancel(&ALICE);
+		assert_eq!(Royalties::stranded_assets(ALICE, Some(ASSET)), 100);
+		assert_eq!(Assets::balance(ASSET, ALICE), 900);
+		assert_noop!(
+			Royalties::recover_asset(Origin::signed(ALICE), Some(ASSET)),
+			pallet_assets::Error::<Test>::Frozen
+		);
+
+		assert_ok!(Assets::thaw(Origin::signed(ALICE), ASSET, escrow));
+		assert_noop!(
+			Royalties::recover_asset(Origin::signed(BOB), Some(ASSET)),
+			Error::<Test>::NothingStranded
+		);
+		assert_ok!(Royalties::recover_asset(Origin::signed(ALICE), Some(ASSET)));
+		assert_eq!(Assets::balance(ASSET, ALICE), 1_000);
+		assert_eq!(Royalties::stranded_assets(ALICE, Some(ASSET)), 0);
+	});
+}
+
+#[test]
+fn stranded_tokens_can_be_recovered_by_their_owner() {
+	new_test_ext().execute_with(|| {
+		let token = token::<Test>(&ALICE).unwrap();
+		let action = RoyaltySwapAction::<Test>::Token { token, price: 100 };
+		assert_ok!(action.reserve(&ALICE));
+		// Returning tokens cannot fail in this runtime, so the token is stranded by hand.
+		StrandedTokens::<Test>::insert(token, ALICE);
+
+		assert_noop!(
+			Royalties::recover_token(Origin::signed(BOB), token),
+			Error::<Test>::NothingStranded
+		);
+		assert_ok!(Royalties::recover_token(Origin::signed(ALICE), token));
+		assert_eq!(owner_of(token), ALICE);
+		assert_eq!(Royalties::stranded_tokens(token), None);
+		assert_noop!(
+			Royalties::recover_token(Origin::signed(ALICE), token),
+			Error::<Test>::NothingStranded
+		);
+	});
+}
+
+#[test]
+fn tokens_of_frozen_classes_cannot_be_swapped() {
+	new_test_ext().execute_with(|| {
+		let offered = token::<Test>(&ALICE).unwrap();
+		let unoffered = (offered.0, Nft::mint(&ALICE, offered.0, vec![], ()).unwrap());
+		let action = RoyaltySwapAction::<Test>::Token { token: offered, price: 100 };
+		assert_ok!(action.reserve(&ALICE));
+		assert_ok!(Moderation::freeze_class(Origin::root(), offered.0));
+
+		let unoffered_action = RoyaltySwapAction::<Test>::TokenForAsset {
+			token: unoffered,
+			asset_id: ASSET,
+			price: 100,
+		};
+		assert_err!(unoffered_action.reserve(&ALICE), Error::<Test>::ClassFrozen);
+
+		// The claim fails and the token goes back to the seller.
+		assert!(!action.claim(&ALICE, &BOB));
+		assert_eq!(owner_of(offered), ALICE);
+		assert_eq!(Balances::free_balance(BOB), 1_000);
+	});
+}
@@ -0,0 +1,49 @@
+//! # Storage Versions Pallet
+//!
+//! Some storage encodings are versioned by the runtime rather than by the pallet that stores
+//! them, for instance the NFT data of `orml_nft`. Runtime migrations read those versions to
+//! decide what to translate, and a missing version means the data predates versioning.
+//!
+//! A chain launched from a runtime already stores its data in the current encoding, so this
+//! pallet writes the current versions into its genesis. Without them, the first runtime upgrade
+//! of the chain would migrate data that needs no migration.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+pub use pallet::*;
+
+#[frame_support::pallet]
+pub mod pallet {
+	use frame_support::{pallet_prelude::*, storage::migration::put_storage_value};
+	use frame_system::pallet_prelude::*;
+	use sp_std::vec::Vec;
+
+	#[pallet::config]
+	pub trait Config: frame_system::Config {
+		/// The versions to record at genesis, as the module prefix and item name of the storage
+		/// value that holds each version, and the version itself.
+		type GenesisVersions: Get<Vec<(&'static [u8], &'static [u8], u16)>>;
+	}
+
+	#[pallet::pallet]
+	pub struct Pallet<T>(_);
+
+	#[pallet::genesis_config]
+	#[derive(Default)]
+	pub struct GenesisConfig {}
+
+	#[pallet::genesis_build]
+	impl<T: Config> GenesisBuild<T> for GenesisConfig {
+		fn build(&self) {
+			for (module, item, version) in T::GenesisVersions::get() {
+				put_storage_value(module, item, &[], version);
+			}
+		}
+	}
+
+	#[pallet::hooks]
+	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {}
+
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {}
+}
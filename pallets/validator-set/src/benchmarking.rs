@@ -0,0 +1,40 @@
+//! Benchmarks for the validator set pallet.
+
+#![cfg(feature = "runtime-benchmarks")]
+
+use super::*;
+use frame_benchmarking::{account, benchmarks, impl_benchmark_test_suite};
+use frame_support::traits::EnsureOrigin;
+
+const SEED: u32 = 0;
+
+/// Approves `count` validators.
+fn validators<T: Config>(count: u32) {
+	let validators: Vec<T::AccountId> = (0..count).map(|i| account("validator", i, SEED)).collect();
+	Validators::<T>::put(validators);
+}
+
+benchmarks! {
+	add_validator {
+		let v in 1 .. T::MaxValidators::get() - 1;
+		validators::<T>(v);
+		let validator: T::AccountId = account("validator", v, SEED);
+		let origin = T::AddRemoveOrigin::successful_origin();
+	}: _<T::Origin>(origin, validator.clone())
+	verify {
+		assert!(Validators::<T>::get().contains(&validator));
+	}
+
+	remove_validator {
+		let v in (T::MinValidators::get() + 1) .. T::MaxValidators::get();
+		validators::<T>(v);
+		// The last validator, so that the whole set is searched.
+		let validator: T::AccountId = account("validator", v - 1, SEED);
+		let origin = T::AddRemoveOrigin::successful_origin();
+	}: _<T::Origin>(origin, validator.clone())
+	verify {
+		assert!(!Validators::<T>::get().contains(&validator));
+	}
+}
+
+impl_benchmark_test_suite!(Pallet, crate::mock::new_test_ext(), crate::mock::Test);
@@ -0,0 +1,236 @@
+//! # Validator Set Pallet
+//!
+//! Keeps the list of accounts allowed to author blocks and vote on finality, and feeds it to
+//! `pallet_session` at every session boundary.
+//!
+//! Governance approves validators with `add_validator` and removes them with
+//! `remove_validator`. An approved validator joins the active set at the start of the session
+//! after it has registered its session keys with `Session::set_keys`, so a validator can be
+//! approved before its node is ready. The set holds at most `MaxValidators` accounts, which
+//! bounds the weight of both calls.
+//!
+//! The pallet also handles offences reported through `pallet_offences`, such as GRANDPA
+//! equivocations: an offending validator is disabled for the rest of the session and removed
+//! from the set.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use frame_support::{traits::Get, weights::Weight};
+use sp_runtime::Perbill;
+use sp_staking::{offence::{OffenceDetails, OnOffenceHandler}, SessionIndex};
+use sp_std::prelude::*;
+
+pub use pallet::*;
+pub use weights::WeightInfo;
+
+mod benchmarking;
+#[cfg(test)]
+mod mock;
+#[cfg(test)]
+mod tests;
+pub mod weights;
+
+#[frame_support::pallet]
+pub mod pallet {
+	use frame_support::{dispatch::DispatchResultWithPostInfo, pallet_prelude::*};
+	use frame_system::pallet_prelude::*;
+	use super::*;
+
+	#[pallet::config]
+	pub trait Config:
+		frame_system::Config + pallet_session::Config<ValidatorId = <Self as frame_system::Config>::AccountId>
+	{
+		/// Because this pallet emits events, it depends on the runtime's definition of an event.
+		type Event: From<Event<Self>> + IsType<<Self as frame_system::Config>::Event>;
+
+		/// The origin allowed to add and remove validators.
+		type AddRemoveOrigin: EnsureOrigin<Self::Origin>;
+
+		/// Validators cannot be removed below this count.
+		#[pallet::constant]
+		type MinValidators: Get<u32>;
+
+		/// Validators cannot be added above this count.
+		#[pallet::constant]
+		type MaxValidators: Get<u32>;
+
+		/// Weight information for extrinsics in this pallet.
+		type WeightInfo: WeightInfo;
+	}
+
+	#[pallet::pallet]
+	#[pallet::generate_store(pub(super) trait Store)]
+	pub struct Pallet<T>(_);
+
+	/// The accounts approved to validate.
+	#[pallet::storage]
+	#[pallet::getter(fn validators)]
+	pub type Validators<T: Config> = StorageValue<_, Vec<T::AccountId>, ValueQuery>;
+
+	#[pallet::event]
+	#[pallet::metadata(T::AccountId = "AccountId")]
+	#[pallet::generate_deposit(pub(super) fn deposit_event)]
+	pub enum Event<T: Config> {
+		/// A validator was approved. \[validator\]
+		ValidatorAdded(T::AccountId),
+		/// A validator was removed. \[validator\]
+		ValidatorRemoved(T::AccountId),
+		/// A validator was reported for an offence and removed. \[validator\]
+		OffenderRemoved(T::AccountId),
+	}
+
+	#[pallet::error]
+	pub enum Error<T> {
+		/// The account is already a validator.
+		AlreadyValidator,
+		/// The account is not a validator.
+		NotValidator,
+		/// Removing the validator would leave too few validators.
+		TooFewValidators,
+		/// Adding the validator would exceed `MaxValidators`.
+		TooManyValidators,
+	}
+
+	#[pallet::genesis_config]
+	pub struct GenesisConfig<T: Config> {
+		pub validators: Vec<T::AccountId>,
+	}
+
+	#[cfg(feature = "std")]
+	impl<T: Config> Default for GenesisConfig<T> {
+		fn default() -> Self {
+			Self { validators: Vec::new() }
+		}
+	}
+
+	#[pallet::genesis_build]
+	impl<T: Config> GenesisBuild<T> for GenesisConfig<T> {
+		fn build(&self) {
+			assert!(
+				self.validators.len() as u32 <= T::MaxValidators::get(),
+				"Genesis validators exceed `MaxValidators`",
+			);
+			Validators::<T>::put(&self.validators);
+		}
+	}
+
+	#[pallet::hooks]
+	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {}
+
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {
+		/// Approve `validator`. It joins the active set in the session after it has registered
+		/// its session keys.
+		///
+		/// The dispatch origin must be `AddRemoveOrigin`.
+		#[pallet::weight(T::WeightInfo::add_validator(T::MaxValidators::get()))]
+		pub fn add_validator(
+			origin: OriginFor<T>,
+			validator: T::AccountId,
+		) -> DispatchResultWithPostInfo {
+			T::AddRemoveOrigin::ensure_origin(origin)?;
+
+			let count = Validators::<T>::try_mutate(|validators| -> Result<u32, DispatchError> {
+				let count = validators.len() as u32;
+				ensure!(!validators.contains(&validator), Error::<T>::AlreadyValidator);
+				ensure!(count < T::MaxValidators::get(), Error::<T>::TooManyValidators);
+				validators.push(validator.clone());
+				Ok(count)
+			})?;
+
+			Self::deposit_event(Event::ValidatorAdded(validator));
+			Ok(Some(T::WeightInfo::add_validator(count)).into())
+		}
+
+		/// Remove `validator`. It leaves the active set at the next session rotation.
+		///
+		/// The dispatch origin must be `AddRemoveOrigin`.
+		#[pallet::weight(T::WeightInfo::remove_validator(T::MaxValidators::get()))]
+		pub fn remove_validator(
+			origin: OriginFor<T>,
+			validator: T::AccountId,
+		) -> DispatchResultWithPostInfo {
+			T::AddRemoveOrigin::ensure_origin(origin)?;
+
+			let count = Validators::<T>::try_mutate(|validators| -> Result<u32, DispatchError> {
+				let count = validators.len() as u32;
+				let index = validators.iter().position(|v| v == &validator)
+					.ok_or(Error::<T>::NotValidator)?;
+				ensure!(count > T::MinValidators::get(), Error::<T>::TooFewValidators);
+				validators.remove(index);
+				Ok(count)
+			})?;
+
+			Self::deposit_event(Event::ValidatorRemoved(validator));
+			Ok(Some(T::WeightInfo::remove_validator(count)).into())
+		}
+	}
+
+	impl<T: Config> Pallet<T> {
+		/// Whether `who` has registered session keys with `pallet_session`.
+		pub fn has_session_keys(who: &T::AccountId) -> bool {
+			pallet_session::NextKeys::<T>::contains_key(who)
+		}
+	}
+}
+
+/// Validators are identified in historical session proofs by their account alone.
+impl<T: Config> pallet_session::historical::SessionManager<T::AccountId, T::AccountId> for Pallet<T> {
+	fn new_session(_new_index: SessionIndex) -> Option<Vec<(T::AccountId, T::AccountId)>> {
+		let validators: Vec<_> = Validators::<T>::get()
+			.into_iter()
+			.filter(Self::has_session_keys)
+			.map(|v| (v.clone(), v))
+			.collect();
+
+		// Never hand over an empty set: the chain would stop producing blocks.
+		if validators.is_empty() {
+			None
+		} else {
+			Some(validators)
+		}
+	}
+
+	fn end_session(_end_index: SessionIndex) {}
+
+	fn start_session(_start_index: SessionIndex) {}
+}
+
+/// There is no staking on this chain, so offenders are punished by losing their seat instead of
+/// being slashed. `MinValidators` still applies: the last validators are only disabled for the
+/// rest of the session.
+impl<T: Config> OnOffenceHandler<T::AccountId, (T::AccountId, T::AccountId), Weight> for Pallet<T> {
+	fn on_offence(
+		offenders: &[OffenceDetails<T::AccountId, (T::AccountId, T::AccountId)>],
+		_slash_fraction: &[Perbill],
+		_session: SessionIndex,
+	) -> Result<Weight, ()> {
+		let mut weight: Weight = 0;
+
+		for details in offenders {
+			let (offender, _) = &details.offender;
+			let _ = pallet_session::Module::<T>::disable(offender);
+
+			let removed = Validators::<T>::mutate(|validators| {
+				match validators.iter().position(|v| v == offender) {
+					Some(index) if validators.len() as u32 > T::MinValidators::get() => {
+						validators.remove(index);
+						true
+					},
+					_ => false,
+				}
+			});
+			if removed {
+				Self::deposit_event(Event::OffenderRemoved(offender.clone()));
+			}
+
+			weight = weight.saturating_add(T::DbWeight::get().reads_writes(3, 3));
+		}
+
+		Ok(weight)
+	}
+
+	fn can_report() -> bool {
+		true
+	}
+}
@@ -0,0 +1,105 @@
+//! Tests for the validator set pallet.
+
+use super::*;
+use crate::mock::*;
+use frame_support::{assert_noop, assert_ok};
+use pallet_session::historical::SessionManager;
+use sp_runtime::{testing::UintAuthorityId, DispatchError};
+
+fn set_keys(who: u64) {
+	assert_ok!(Session::set_keys(Origin::signed(who), UintAuthorityId(who), vec![]));
+}
+
+fn offence(offender: u64) -> Result<Weight, ()> {
+	ValidatorSet::on_offence(
+		&[OffenceDetails { offender: (offender, offender), reporters: vec![] }],
+		&[Perbill::zero()],
+		0,
+	)
+}
+
+#[test]
+fn validators_are_added_up_to_the_maximum() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			ValidatorSet::add_validator(Origin::signed(ALICE), DAVE),
+			DispatchError::BadOrigin
+		);
+		assert_noop!(
+			ValidatorSet::add_validator(Origin::root(), ALICE),
+			Error::<Test>::AlreadyValidator
+		);
+
+		assert_ok!(ValidatorSet::add_validator(Origin::root(), DAVE));
+		assert_ok!(ValidatorSet::add_validator(Origin::root(), 5));
+		assert_eq!(ValidatorSet::validators(), vec![ALICE, BOB, CHARLIE, DAVE, 5]);
+		assert_noop!(
+			ValidatorSet::add_validator(Origin::root(), 6),
+			Error::<Test>::TooManyValidators
+		);
+	});
+}
+
+#[test]
+fn validators_are_removed_down_to_the_minimum() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			ValidatorSet::remove_validator(Origin::signed(ALICE), BOB),
+			DispatchError::BadOrigin
+		);
+		assert_noop!(
+			ValidatorSet::remove_validator(Origin::root(), DAVE),
+			Error::<Test>::NotValidator
+		);
+
+		assert_ok!(ValidatorSet::remove_validator(Origin::root(), BOB));
+		assert_eq!(ValidatorSet::validators(), vec![ALICE, CHARLIE]);
+		assert_noop!(
+			ValidatorSet::remove_validator(Origin::root(), ALICE),
+			Error::<Test>::TooFewValidators
+		);
+	});
+}
+
+#[test]
+fn new_sessions_skip_validators_without_keys() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(ValidatorSet::add_validator(Origin::root(), DAVE));
+		assert!(!ValidatorSet::has_session_keys(&DAVE));
+		assert_eq!(
+			ValidatorSet::new_session(1),
+			Some(vec![(ALICE, ALICE), (BOB, BOB), (CHARLIE, CHARLIE)])
+		);
+
+		set_keys(DAVE);
+		assert!(ValidatorSet::has_session_keys(&DAVE));
+		assert_eq!(
+			ValidatorSet::new_session(2),
+			Some(vec![(ALICE, ALICE), (BOB, BOB), (CHARLIE, CHARLIE), (DAVE, DAVE)])
+		);
+	});
+}
+
+#[test]
+fn a_set_without_keys_keeps_the_current_validators() {
+	new_test_ext().execute_with(|| {
+		Validators::<Test>::put(vec![DAVE]);
+		assert_eq!(ValidatorSet::new_session(1), None);
+	});
+}
+
+#[test]
+fn offenders_are_disabled_and_removed_down_to_the_minimum() {
+	new_test_ext().execute_with(|| {
+		assert_eq!(Session::validators(), vec![ALICE, BOB, CHARLIE]);
+
+		assert!(offence(ALICE).is_ok());
+		assert_eq!(Session::disabled_validators(), vec![0]);
+		assert_eq!(ValidatorSet::validators(), vec![BOB, CHARLIE]);
+
+		// Bob is only disabled, since removing him would leave too few validators.
+		assert!(offence(BOB).is_ok());
+		assert_eq!(Session::disabled_validators(), vec![0, 1]);
+		assert_eq!(ValidatorSet::validators(), vec![BOB, CHARLIE]);
+	});
+}
@@ -0,0 +1,46 @@
+//! Weights for pallet_validator_set
+
+#![allow(unused_parens)]
+#![allow(unused_imports)]
+
+use frame_support::{traits::Get, weights::{Weight, constants::RocksDbWeight}};
+use sp_std::marker::PhantomData;
+
+/// Weight functions needed for pallet_validator_set.
+pub trait WeightInfo {
+	fn add_validator(v: u32, ) -> Weight;
+	fn remove_validator(v: u32, ) -> Weight;
+}
+
+/// Weights for pallet_validator_set.
+pub struct SubstrateWeight<T>(PhantomData<T>);
+impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
+	fn add_validator(v: u32, ) -> Weight {
+		(18_000_000 as Weight)
+			.saturating_add((150_000 as Weight).saturating_mul(v as Weight))
+			.saturating_add(T::DbWeight::get().reads(1 as Weight))
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+	}
+	fn remove_validator(v: u32, ) -> Weight {
+		(19_000_000 as Weight)
+			.saturating_add((170_000 as Weight).saturating_mul(v as Weight))
+			.saturating_add(T::DbWeight::get().reads(1 as Weight))
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+	}
+}
+
+// For backwards compatibility and tests
+impl WeightInfo for () {
+	fn add_validator(v: u32, ) -> Weight {
+		(18_000_000 as Weight)
+			.saturating_add((150_000 as Weight).saturating_mul(v as Weight))
+			.saturating_add(RocksDbWeight::get().reads(1 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
+	}
+	fn remove_validator(v: u32, ) -> Weight {
+		(19_000_000 as Weight)
+			.saturating_add((170_000 as Weight).saturating_mul(v as Weight))
+			.saturating_add(RocksDbWeight::get().reads(1 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
+	}
+}
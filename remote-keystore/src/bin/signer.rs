@@ -0,0 +1,143 @@
+//! Reference signer for the Chiba Studio remote keystore protocol.
+//!
+//! Serves a local keystore over a Unix socket. It is meant for testing validator setups;
+//! production signers should keep keys in an HSM or similar and only reuse the protocol.
+
+use std::fs;
+use std::io::{BufRead, BufReader, Write};
+use std::os::unix::fs::{FileTypeExt, PermissionsExt};
+use std::os::unix::net::{UnixListener, UnixStream};
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::thread;
+use structopt::StructOpt;
+use sc_keystore::LocalKeystore;
+use sp_core::crypto::SecretString;
+use sp_keystore::SyncCryptoStore;
+use chiba_remote_keystore::protocol::{Crypto, PublicKey, Request, Response};
+
+#[derive(Debug, StructOpt)]
+#[structopt(name = "chiba-signer", about = "Reference signer for the Chiba Studio remote keystore.")]
+struct Opt {
+	/// Path of the Unix socket to listen on.
+	#[structopt(long, parse(from_os_str))]
+	socket: PathBuf,
+
+	/// Directory of the keystore. Keys are kept in memory if omitted.
+	#[structopt(long, parse(from_os_str))]
+	keystore: Option<PathBuf>,
+
+	/// Password used to encrypt the keys in the keystore directory.
+	#[structopt(long)]
+	password: Option<String>,
+}
+
+fn main() -> Result<(), String> {
+	env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("info")).init();
+	let opt = Opt::from_args();
+
+	let keystore = match &opt.keystore {
+		Some(path) => LocalKeystore::open(path, opt.password.clone().map(SecretString::new))
+			.map_err(|e| format!("Cannot open keystore: {}", e))?,
+		None => LocalKeystore::in_memory(),
+	};
+	let keystore = Arc::new(keystore);
+
+	// Only replace a stale socket, never an unrelated file.
+	if let Ok(metadata) = fs::symlink_metadata(&opt.socket) {
+		if !metadata.file_type().is_socket() {
+			return Err(format!("{} exists and is not a socket", opt.socket.display()))
+		}
+		fs::remove_file(&opt.socket).map_err(|e| e.to_string())?;
+	}
+
+	let listener = UnixListener::bind(&opt.socket).map_err(|e| e.to_string())?;
+	fs::set_permissions(&opt.socket, fs::Permissions::from_mode(0o600))
+		.map_err(|e| e.to_string())?;
+	log::info!("Listening on {}", opt.socket.display());
+
+	for stream in listener.incoming() {
+		match stream {
+			Ok(stream) => {
+				let keystore = keystore.clone();
+				thread::spawn(move || {
+					if let Err(e) = serve(stream, &keystore) {
+						log::warn!("Connection closed: {}", e);
+					}
+				});
+			},
+			Err(e) => log::warn!("Failed to accept connection: {}", e),
+		}
+	}
+
+	Ok(())
+}
+
+/// Answer requests on `stream` until the node closes it.
+fn serve(stream: UnixStream, keystore: &LocalKeystore) -> std::io::Result<()> {
+	let mut writer = stream.try_clone()?;
+	for line in BufReader::new(stream).lines() {
+		let response = match serde_json::from_str(&line?) {
+			Ok(request) => handle(request, keystore),
+			Err(e) => Response::Error(format!("Invalid request: {}", e)),
+		};
+
+		let mut answer = serde_json::to_string(&response)?;
+		answer.push('\n');
+		writer.write_all(answer.as_bytes())?;
+	}
+	Ok(())
+}
+
+fn handle(request: Request, keystore: &LocalKeystore) -> Response {
+	log::debug!("{:?}", request);
+
+	match request {
+		Request::PublicKeys { key_type, crypto } => {
+			let id = key_type.0;
+			Response::PublicKeys(match crypto {
+				Crypto::Sr25519 => SyncCryptoStore::sr25519_public_keys(keystore, id)
+					.into_iter().map(|k| k.0.to_vec().into()).collect(),
+				Crypto::Ed25519 => SyncCryptoStore::ed25519_public_keys(keystore, id)
+					.into_iter().map(|k| k.0.to_vec().into()).collect(),
+				Crypto::Ecdsa => SyncCryptoStore::ecdsa_public_keys(keystore, id)
+					.into_iter().map(|k| k.0.to_vec().into()).collect(),
+			})
+		},
+		Request::GenerateNew { key_type, crypto, seed } => {
+			let (id, seed) = (key_type.0, seed.as_deref());
+			let key = match crypto {
+				Crypto::Sr25519 => SyncCryptoStore::sr25519_generate_new(keystore, id, seed)
+					.map(|k| k.0.to_vec()),
+				Crypto::Ed25519 => SyncCryptoStore::ed25519_generate_new(keystore, id, seed)
+					.map(|k| k.0.to_vec()),
+				Crypto::Ecdsa => SyncCryptoStore::ecdsa_generate_new(keystore, id, seed)
+					.map(|k| k.0.to_vec()),
+			};
+			match key {
+				Ok(key) => Response::PublicKey(key.into()),
+				Err(e) => Response::Error(e.to_string()),
+			}
+		},
+		Request::InsertUnknown { key_type, suri, public } => {
+			match SyncCryptoStore::insert_unknown(keystore, key_type.0, &suri, &public) {
+				Ok(()) => Response::Done,
+				Err(()) => Response::Error("Failed to insert key".into()),
+			}
+		},
+		Request::Keys { key_type } => match SyncCryptoStore::keys(keystore, key_type.0) {
+			Ok(keys) => Response::Keys(keys.iter().filter_map(PublicKey::from_pair).collect()),
+			Err(e) => Response::Error(e.to_string()),
+		},
+		Request::HasKeys { keys } => {
+			let keys: Vec<_> = keys.into_iter().map(|(public, key_type)| (public.0, key_type.0)).collect();
+			Response::HasKeys(SyncCryptoStore::has_keys(keystore, &keys))
+		},
+		Request::SignWith { key_type, key, message } => {
+			match SyncCryptoStore::sign_with(keystore, key_type.0, &key.into_pair(), &message) {
+				Ok(signature) => Response::Signature(signature.into()),
+				Err(e) => Response::Error(e.to_string()),
+			}
+		},
+	}
+}
@@ -0,0 +1,313 @@
+//! A keystore that keeps validator keys in a separate signer process.
+//!
+//! The node connects to the signer over a Unix socket and speaks the line-delimited JSON
+//! protocol described in [`protocol`] and in `doc/remote-keystore.md`. The signer holds the
+//! secret keys; the node only ever sees public keys and signatures.
+//!
+//! `chiba-signer`, built from this crate, is a reference signer backed by a local keystore.
+
+pub mod protocol;
+
+use std::convert::TryFrom;
+use std::time::Duration;
+use async_std::{
+	io::{self, prelude::*, BufReader},
+	os::unix::net::UnixStream,
+	path::PathBuf,
+};
+use async_trait::async_trait;
+use futures::executor::block_on;
+use sp_core::{crypto::{CryptoTypePublicPair, KeyTypeId}, ecdsa, ed25519, sr25519};
+use sp_keystore::{
+	vrf::{VRFTranscriptData, VRFSignature},
+	CryptoStore, Error, SyncCryptoStore,
+};
+use protocol::{Crypto, KeyType, PublicKey, Request, Response};
+
+/// How long to wait for the signer to answer a request.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// A `CryptoStore` that forwards every operation to a signer listening on a Unix socket.
+///
+/// A new connection is opened for each request, so the signer may be restarted while the node
+/// is running. Requests use non-blocking I/O, so waiting for the signer does not hold up the
+/// executor running the node's tasks; the `SyncCryptoStore` methods block the calling thread
+/// until the answer arrives.
+#[derive(Debug)]
+pub struct RemoteKeystore {
+	socket: PathBuf,
+}
+
+impl RemoteKeystore {
+	/// Connect to the signer at `url`, which is either `unix://<path>` or a plain path.
+	///
+	/// Fails if no signer is listening, so that a misconfigured validator does not start
+	/// without its keys.
+	pub fn open(url: &str) -> Result<Self, String> {
+		let path = match url.find("://") {
+			Some(_) => url.strip_prefix("unix://")
+				.ok_or_else(|| format!("Unsupported remote keystore URL: {}", url))?,
+			None => url,
+		};
+
+		let keystore = RemoteKeystore { socket: PathBuf::from(path) };
+		block_on(io::timeout(REQUEST_TIMEOUT, UnixStream::connect(&keystore.socket)))
+			.map_err(|e| format!("Cannot connect to signer: {}", e))?;
+		Ok(keystore)
+	}
+
+	/// Send `request` on a new connection and read the answer.
+	async fn exchange(&self, request: &Request) -> io::Result<Response> {
+		let mut stream = UnixStream::connect(&self.socket).await?;
+		let mut line = serde_json::to_string(request)?;
+		line.push('\n');
+		stream.write_all(line.as_bytes()).await?;
+
+		let mut answer = String::new();
+		BufReader::new(stream).read_line(&mut answer).await?;
+		Ok(serde_json::from_str(&answer)?)
+	}
+
+	/// Send `request` and wait for the answer. Transport failures are reported as
+	/// `Error::Unavailable` and signer-side failures as `Error::Other`.
+	async fn call(&self, request: Request) -> Result<Response, Error> {
+		match io::timeout(REQUEST_TIMEOUT, self.exchange(&request)).await {
+			Ok(Response::Error(e)) => Err(Error::Other(e)),
+			Ok(response) => Ok(response),
+			Err(e) => {
+				log::warn!(target: "remote-keystore", "Signer request failed: {}", e);
+				Err(Error::Unavailable)
+			},
+		}
+	}
+
+	fn unexpected(response: Response) -> Error {
+		Error::Other(format!("Unexpected signer response: {:?}", response))
+	}
+
+	async fn public_keys<P>(&self, id: KeyTypeId, crypto: Crypto) -> Vec<P> where
+		P: for<'a> TryFrom<&'a [u8]>,
+	{
+		match self.call(Request::PublicKeys { key_type: KeyType(id), crypto }).await {
+			Ok(Response::PublicKeys(keys)) => keys.iter()
+				.filter_map(|k| P::try_from(&k[..]).ok())
+				.collect(),
+			_ => Vec::new(),
+		}
+	}
+
+	async fn generate_new<P>(
+		&self,
+		id: KeyTypeId,
+		crypto: Crypto,
+		seed: Option<&str>,
+	) -> Result<P, Error> where
+		P: for<'a> TryFrom<&'a [u8]>,
+	{
+		let request = Request::GenerateNew {
+			key_type: KeyType(id),
+			crypto,
+			seed: seed.map(Into::into),
+		};
+		match self.call(request).await? {
+			Response::PublicKey(key) => P::try_from(&key[..])
+				.map_err(|_| Error::ValidationError("Invalid public key from signer".into())),
+			other => Err(Self::unexpected(other)),
+		}
+	}
+
+	async fn insert(&self, key_type: KeyTypeId, suri: &str, public: &[u8]) -> Result<(), ()> {
+		let request = Request::InsertUnknown {
+			key_type: KeyType(key_type),
+			suri: suri.into(),
+			public: public.to_vec().into(),
+		};
+		match self.call(request).await {
+			Ok(Response::Done) => Ok(()),
+			_ => Err(()),
+		}
+	}
+
+	async fn all_keys(&self, id: KeyTypeId) -> Result<Vec<CryptoTypePublicPair>, Error> {
+		match self.call(Request::Keys { key_type: KeyType(id) }).await? {
+			Response::Keys(keys) => Ok(keys.into_iter().map(PublicKey::into_pair).collect()),
+			other => Err(Self::unexpected(other)),
+		}
+	}
+
+	async fn supported(
+		&self,
+		id: KeyTypeId,
+		keys: Vec<CryptoTypePublicPair>,
+	) -> Result<Vec<CryptoTypePublicPair>, Error> {
+		let all = self.all_keys(id).await?;
+		Ok(keys.into_iter().filter(|k| all.contains(k)).collect())
+	}
+
+	async fn holds(&self, public_keys: &[(Vec<u8>, KeyTypeId)]) -> bool {
+		let keys = public_keys.iter()
+			.map(|(public, id)| (public.clone().into(), KeyType(*id)))
+			.collect();
+		matches!(self.call(Request::HasKeys { keys }).await, Ok(Response::HasKeys(true)))
+	}
+
+	async fn sign(
+		&self,
+		id: KeyTypeId,
+		key: &CryptoTypePublicPair,
+		msg: &[u8],
+	) -> Result<Vec<u8>, Error> {
+		let key = PublicKey::from_pair(key).ok_or(Error::KeyNotSupported(id))?;
+		let request = Request::SignWith { key_type: KeyType(id), key, message: msg.to_vec().into() };
+		match self.call(request).await? {
+			Response::Signature(signature) => Ok(signature.0),
+			other => Err(Self::unexpected(other)),
+		}
+	}
+
+	fn vrf_sign(&self) -> Result<Option<VRFSignature>, Error> {
+		// Aura does not use VRFs, so the protocol does not carry transcripts.
+		Err(Error::Other("VRF signing is not supported by the remote keystore".into()))
+	}
+}
+
+impl SyncCryptoStore for RemoteKeystore {
+	fn sr25519_public_keys(&self, id: KeyTypeId) -> Vec<sr25519::Public> {
+		block_on(self.public_keys(id, Crypto::Sr25519))
+	}
+
+	fn sr25519_generate_new(&self, id: KeyTypeId, seed: Option<&str>) -> Result<sr25519::Public, Error> {
+		block_on(self.generate_new(id, Crypto::Sr25519, seed))
+	}
+
+	fn ed25519_public_keys(&self, id: KeyTypeId) -> Vec<ed25519::Public> {
+		block_on(self.public_keys(id, Crypto::Ed25519))
+	}
+
+	fn ed25519_generate_new(&self, id: KeyTypeId, seed: Option<&str>) -> Result<ed25519::Public, Error> {
+		block_on(self.generate_new(id, Crypto::Ed25519, seed))
+	}
+
+	fn ecdsa_public_keys(&self, id: KeyTypeId) -> Vec<ecdsa::Public> {
+		block_on(self.public_keys(id, Crypto::Ecdsa))
+	}
+
+	fn ecdsa_generate_new(&self, id: KeyTypeId, seed: Option<&str>) -> Result<ecdsa::Public, Error> {
+		block_on(self.generate_new(id, Crypto::Ecdsa, seed))
+	}
+
+	fn insert_unknown(&self, key_type: KeyTypeId, suri: &str, public: &[u8]) -> Result<(), ()> {
+		block_on(self.insert(key_type, suri, public))
+	}
+
+	fn supported_keys(
+		&self,
+		id: KeyTypeId,
+		keys: Vec<CryptoTypePublicPair>,
+	) -> Result<Vec<CryptoTypePublicPair>, Error> {
+		block_on(self.supported(id, keys))
+	}
+
+	fn keys(&self, id: KeyTypeId) -> Result<Vec<CryptoTypePublicPair>, Error> {
+		block_on(self.all_keys(id))
+	}
+
+	fn has_keys(&self, public_keys: &[(Vec<u8>, KeyTypeId)]) -> bool {
+		block_on(self.holds(public_keys))
+	}
+
+	fn sign_with(
+		&self,
+		id: KeyTypeId,
+		key: &CryptoTypePublicPair,
+		msg: &[u8],
+	) -> Result<Vec<u8>, Error> {
+		block_on(self.sign(id, key, msg))
+	}
+
+	fn sr25519_vrf_sign(
+		&self,
+		_key_type: KeyTypeId,
+		_public: &sr25519::Public,
+		_transcript_data: VRFTranscriptData,
+	) -> Result<Option<VRFSignature>, Error> {
+		self.vrf_sign()
+	}
+}
+
+#[async_trait]
+impl CryptoStore for RemoteKeystore {
+	async fn sr25519_public_keys(&self, id: KeyTypeId) -> Vec<sr25519::Public> {
+		self.public_keys(id, Crypto::Sr25519).await
+	}
+
+	async fn sr25519_generate_new(
+		&self,
+		id: KeyTypeId,
+		seed: Option<&str>,
+	) -> Result<sr25519::Public, Error> {
+		self.generate_new(id, Crypto::Sr25519, seed).await
+	}
+
+	async fn ed25519_public_keys(&self, id: KeyTypeId) -> Vec<ed25519::Public> {
+		self.public_keys(id, Crypto::Ed25519).await
+	}
+
+	async fn ed25519_generate_new(
+		&self,
+		id: KeyTypeId,
+		seed: Option<&str>,
+	) -> Result<ed25519::Public, Error> {
+		self.generate_new(id, Crypto::Ed25519, seed).await
+	}
+
+	async fn ecdsa_public_keys(&self, id: KeyTypeId) -> Vec<ecdsa::Public> {
+		self.public_keys(id, Crypto::Ecdsa).await
+	}
+
+	async fn ecdsa_generate_new(
+		&self,
+		id: KeyTypeId,
+		seed: Option<&str>,
+	) -> Result<ecdsa::Public, Error> {
+		self.generate_new(id, Crypto::Ecdsa, seed).await
+	}
+
+	async fn insert_unknown(&self, id: KeyTypeId, suri: &str, public: &[u8]) -> Result<(), ()> {
+		self.insert(id, suri, public).await
+	}
+
+	async fn supported_keys(
+		&self,
+		id: KeyTypeId,
+		keys: Vec<CryptoTypePublicPair>,
+	) -> Result<Vec<CryptoTypePublicPair>, Error> {
+		self.supported(id, keys).await
+	}
+
+	async fn keys(&self, id: KeyTypeId) -> Result<Vec<CryptoTypePublicPair>, Error> {
+		self.all_keys(id).await
+	}
+
+	async fn has_keys(&self, public_keys: &[(Vec<u8>, KeyTypeId)]) -> bool {
+		self.holds(public_keys).await
+	}
+
+	async fn sign_with(
+		&self,
+		id: KeyTypeId,
+		key: &CryptoTypePublicPair,
+		msg: &[u8],
+	) -> Result<Vec<u8>, Error> {
+		self.sign(id, key, msg).await
+	}
+
+	async fn sr25519_vrf_sign(
+		&self,
+		_key_type: KeyTypeId,
+		_public: &sr25519::Public,
+		_transcript_data: VRFTranscriptData,
+	) -> Result<Option<VRFSignature>, Error> {
+		self.vrf_sign()
+	}
+}
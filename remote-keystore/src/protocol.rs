@@ -0,0 +1,109 @@
+//! Messages exchanged between the node and a signer.
+//!
+//! Each request and each response is a single JSON object followed by a newline. Key types are
+//! their four-character identifiers (for example `"aura"` or `"gran"`), crypto schemes are
+//! `"sr25519"`, `"ed25519"` or `"ecdsa"`, and byte strings are `0x`-prefixed hex.
+
+use std::convert::TryFrom;
+use serde::{Deserialize, Serialize};
+use sp_core::{crypto::{CryptoTypeId, CryptoTypePublicPair, KeyTypeId}, ecdsa, ed25519, sr25519, Bytes};
+
+/// A signature scheme supported by the protocol.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Crypto {
+	Sr25519,
+	Ed25519,
+	Ecdsa,
+}
+
+impl Crypto {
+	pub fn id(self) -> CryptoTypeId {
+		match self {
+			Crypto::Sr25519 => sr25519::CRYPTO_ID,
+			Crypto::Ed25519 => ed25519::CRYPTO_ID,
+			Crypto::Ecdsa => ecdsa::CRYPTO_ID,
+		}
+	}
+
+	pub fn from_id(id: CryptoTypeId) -> Option<Self> {
+		[Crypto::Sr25519, Crypto::Ed25519, Crypto::Ecdsa].iter().copied().find(|c| c.id() == id)
+	}
+}
+
+/// A key type identifier, serialized as its four-character name.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct KeyType(pub KeyTypeId);
+
+impl TryFrom<String> for KeyType {
+	type Error = String;
+
+	fn try_from(s: String) -> Result<Self, Self::Error> {
+		KeyTypeId::try_from(s.as_str())
+			.map(KeyType)
+			.map_err(|_| format!("Invalid key type: {}", s))
+	}
+}
+
+impl From<KeyType> for String {
+	fn from(key_type: KeyType) -> Self {
+		String::from_utf8_lossy(&(key_type.0).0).into_owned()
+	}
+}
+
+/// A public key tagged with its signature scheme.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PublicKey {
+	pub crypto: Crypto,
+	pub public: Bytes,
+}
+
+impl PublicKey {
+	pub fn from_pair(pair: &CryptoTypePublicPair) -> Option<Self> {
+		Crypto::from_id(pair.0).map(|crypto| PublicKey { crypto, public: pair.1.clone().into() })
+	}
+
+	pub fn into_pair(self) -> CryptoTypePublicPair {
+		CryptoTypePublicPair(self.crypto.id(), self.public.0)
+	}
+}
+
+/// A request from the node.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "method", content = "params", rename_all = "camelCase")]
+pub enum Request {
+	/// List the public keys of one scheme for a key type.
+	#[serde(rename_all = "camelCase")]
+	PublicKeys { key_type: KeyType, crypto: Crypto },
+	/// Generate a key, from `seed` if given. Answered with `Response::PublicKey`.
+	#[serde(rename_all = "camelCase")]
+	GenerateNew { key_type: KeyType, crypto: Crypto, seed: Option<String> },
+	/// Store the key derived from `suri`. Answered with `Response::Done`.
+	#[serde(rename_all = "camelCase")]
+	InsertUnknown { key_type: KeyType, suri: String, public: Bytes },
+	/// List every key of a key type, of any scheme.
+	#[serde(rename_all = "camelCase")]
+	Keys { key_type: KeyType },
+	/// Whether the signer holds all of the given keys. Answered with `Response::HasKeys`.
+	#[serde(rename_all = "camelCase")]
+	HasKeys { keys: Vec<(Bytes, KeyType)> },
+	/// Sign `message` with the given key. Answered with `Response::Signature`.
+	#[serde(rename_all = "camelCase")]
+	SignWith { key_type: KeyType, key: PublicKey, message: Bytes },
+}
+
+/// The signer's answer to a `Request`.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "result", content = "value", rename_all = "camelCase")]
+pub enum Response {
+	PublicKeys(Vec<Bytes>),
+	PublicKey(Bytes),
+	Keys(Vec<PublicKey>),
+	HasKeys(bool),
+	Signature(Bytes),
+	Done,
+	/// The request failed; the value describes why.
+	Error(String),
+}
@@ -0,0 +1,118 @@
+//! Round trips between `RemoteKeystore` and the `chiba-signer` reference signer.
+
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command};
+use std::thread;
+use std::time::{Duration, Instant};
+use chiba_remote_keystore::RemoteKeystore;
+use futures::executor::block_on;
+use sp_core::{crypto::{CryptoTypePublicPair, KeyTypeId, Pair, Public}, ed25519, sr25519};
+use sp_keystore::{CryptoStore, Error, SyncCryptoStore};
+use tempfile::TempDir;
+
+const KEY_TYPE: KeyTypeId = KeyTypeId(*b"aura");
+
+/// A signer with an in-memory keystore, killed when dropped.
+struct Signer {
+	process: Child,
+	socket: PathBuf,
+	_dir: TempDir,
+}
+
+impl Signer {
+	fn start() -> Self {
+		let dir = tempfile::tempdir().expect("temporary directory");
+		let socket = dir.path().join("signer.sock");
+		let process = Command::new(env!("CARGO_BIN_EXE_chiba-signer"))
+			.arg("--socket")
+			.arg(&socket)
+			.spawn()
+			.expect("chiba-signer starts");
+		wait_for(&socket);
+		Signer { process, socket, _dir: dir }
+	}
+
+	fn url(&self) -> String {
+		format!("unix://{}", self.socket.display())
+	}
+}
+
+impl Drop for Signer {
+	fn drop(&mut self) {
+		let _ = self.process.kill();
+		let _ = self.process.wait();
+	}
+}
+
+fn wait_for(socket: &Path) {
+	let deadline = Instant::now() + Duration::from_secs(10);
+	while !socket.exists() {
+		assert!(Instant::now() < deadline, "chiba-signer did not create its socket");
+		thread::sleep(Duration::from_millis(20));
+	}
+}
+
+#[test]
+fn async_calls_round_trip() {
+	let signer = Signer::start();
+	let keystore = RemoteKeystore::open(&signer.url()).unwrap();
+
+	block_on(async {
+		let public = CryptoStore::sr25519_generate_new(&keystore, KEY_TYPE, None).await.unwrap();
+		assert_eq!(CryptoStore::sr25519_public_keys(&keystore, KEY_TYPE).await, vec![public]);
+		assert!(CryptoStore::has_keys(&keystore, &[(public.to_raw_vec(), KEY_TYPE)]).await);
+
+		let pair = CryptoTypePublicPair::from(public);
+		assert!(CryptoStore::keys(&keystore, KEY_TYPE).await.unwrap().contains(&pair));
+		let signature = CryptoStore::sign_with(&keystore, KEY_TYPE, &pair, b"chiba").await.unwrap();
+		let signature = sr25519::Signature::from_slice(&signature);
+		assert!(sr25519::Pair::verify(&signature, b"chiba", &public));
+	});
+}
+
+#[test]
+fn sync_calls_round_trip() {
+	let signer = Signer::start();
+	let keystore = RemoteKeystore::open(&signer.url()).unwrap();
+
+	let alice = ed25519::Pair::from_string("//Alice", None).unwrap().public();
+	SyncCryptoStore::insert_unknown(&keystore, KEY_TYPE, "//Alice", alice.as_ref()).unwrap();
+	assert_eq!(SyncCryptoStore::ed25519_public_keys(&keystore, KEY_TYPE), vec![alice]);
+
+	let pair = CryptoTypePublicPair::from(alice);
+	let unknown = CryptoTypePublicPair::from(sr25519::Public::from_raw([7; 32]));
+	assert_eq!(
+		SyncCryptoStore::supported_keys(&keystore, KEY_TYPE, vec![pair.clone(), unknown]).unwrap(),
+		vec![pair.clone()],
+	);
+	let signature = SyncCryptoStore::sign_with(&keystore, KEY_TYPE, &pair, b"chiba").unwrap();
+	let signature = ed25519::Signature::from_slice(&signature);
+	assert!(ed25519::Pair::verify(&signature, b"chiba", &alice));
+}
+
+#[test]
+fn signer_errors_and_outages_are_reported() {
+	let signer = Signer::start();
+	let keystore = RemoteKeystore::open(&signer.url()).unwrap();
+
+	let unknown = CryptoTypePublicPair::from(sr25519::Public::from_raw([7; 32]));
+	assert!(matches!(
+		block_on(CryptoStore::sign_with(&keystore, KEY_TYPE, &unknown, b"chiba")),
+		Err(Error::Other(_)),
+	));
+
+	drop(signer);
+	assert!(matches!(
+		block_on(CryptoStore::sign_with(&keystore, KEY_TYPE, &unknown, b"chiba")),
+		Err(Error::Unavailable),
+	));
+	assert!(block_on(CryptoStore::sr25519_public_keys(&keystore, KEY_TYPE)).is_empty());
+}
+
+#[test]
+fn open_requires_a_listening_signer() {
+	let dir = tempfile::tempdir().unwrap();
+	let missing = dir.path().join("missing.sock");
+	assert!(RemoteKeystore::open(&missing.display().to_string()).is_err());
+	assert!(RemoteKeystore::open("tcp://127.0.0.1:9944").is_err());
+}
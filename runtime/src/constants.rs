@@ -0,0 +1,46 @@
+//! Constants used throughout the runtime.
+
+/// Denominations of the Chiba token.
+pub mod currency {
+	use crate::Balance;
+
+	/// The token symbol shown by wallets.
+	pub const TOKEN_SYMBOL: &str = "CHB";
+	/// The number of decimal places of one unit.
+	pub const TOKEN_DECIMALS: u8 = 12;
+
+	pub const UNITS: Balance = 1_000_000_000_000;
+	pub const CENTS: Balance = UNITS / 100;
+	pub const MILLICENTS: Balance = CENTS / 1_000;
+}
+
+/// Fee-related.
+pub mod fee {
+	use frame_support::weights::{
+		constants::ExtrinsicBaseWeight, WeightToFeeCoefficient, WeightToFeeCoefficients,
+		WeightToFeePolynomial,
+	};
+	use smallvec::smallvec;
+	use sp_runtime::Perbill;
+	use super::currency::CENTS;
+	use crate::Balance;
+
+	/// Maps weight to fee, so that the base weight of an extrinsic costs a tenth of a cent and
+	/// heavier calls cost proportionally more.
+	pub struct WeightToFee;
+	impl WeightToFeePolynomial for WeightToFee {
+		type Balance = Balance;
+
+		fn polynomial() -> WeightToFeeCoefficients<Self::Balance> {
+			// `ExtrinsicBaseWeight` maps to 1/10 CENT.
+			let p = CENTS;
+			let q = 10 * Balance::from(ExtrinsicBaseWeight::get());
+			smallvec![WeightToFeeCoefficient {
+				degree: 1,
+				negative: false,
+				coeff_frac: Perbill::from_rational_approximation(p % q, q),
+				coeff_integer: p / q,
+			}]
+		}
+	}
+}
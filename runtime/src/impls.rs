@@ -0,0 +1,248 @@
+//! Some configurable implementations as associated type for the runtime.
+
+use frame_support::{
+	dispatch::DispatchResult,
+	traits::{Currency, ExistenceRequirement, Filter, Get, Imbalance, OnUnbalanced},
+	weights::{
+		constants::{RocksDbWeight, WEIGHT_PER_MICROS, WEIGHT_PER_NANOS},
+		Weight,
+	},
+};
+use pallet_asset_tx_payment::OnChargeAssetTransaction;
+use pallet_fractions::ShareAssets;
+use pallet_royalties::MultiAsset;
+use sp_runtime::{
+	traits::{Saturating, Zero},
+	transaction_validity::{InvalidTransaction, TransactionValidityError},
+	Percent,
+};
+use sp_std::vec::Vec;
+use crate::{
+	AccountId, AssetId, AssetTxPayment, Assets, Authorship, Balance, Balances, Call, Origin,
+	Session, Treasury,
+};
+
+type NegativeImbalance = <Balances as Currency<AccountId>>::NegativeImbalance;
+
+/// Rejects the calls of `pallet_chiba`. They create, mint and transfer NFTs without checking
+/// metadata or charging royalties, and cannot be told apart by class, so all of them are
+/// rejected. `Royalties`, `NftBatch` and the trading pallets take their place; `doc/royalties.md`
+/// lists the replacement of each call for integrators.
+pub struct BaseFilter;
+impl Filter<Call> for BaseFilter {
+	fn filter(call: &Call) -> bool {
+		!matches!(call, Call::Chiba(_))
+	}
+}
+
+/// Credits the block author.
+pub struct Author;
+impl OnUnbalanced<NegativeImbalance> for Author {
+	fn on_nonzero_unbalanced(amount: NegativeImbalance) {
+		if let Some(author) = Authorship::author() {
+			Balances::resolve_creating(&author, amount);
+		}
+	}
+}
+
+/// Splits transaction fees: 80% to the treasury and 20% to the block author. Tips go to the
+/// block author in full.
+pub struct DealWithFees;
+impl OnUnbalanced<NegativeImbalance> for DealWithFees {
+	fn on_unbalanceds<B>(mut fees_then_tips: impl Iterator<Item = NegativeImbalance>) {
+		if let Some(fees) = fees_then_tips.next() {
+			let mut split = fees.ration(80, 20);
+			if let Some(tips) = fees_then_tips.next() {
+				tips.merge_into(&mut split.1);
+			}
+			Treasury::on_unbalanced(split.0);
+			Author::on_unbalanced(split.1);
+		}
+	}
+}
+
+/// Issues the shares of fractionalized NFTs as `pallet_assets` assets owned by the fractions
+/// pallet. Shares are not sufficient: holders need a CHB balance to hold them.
+///
+/// Assets are created without `pallet_assets`' deposit, since `Fractions` reserves the same
+/// deposit from the curator itself.
+pub struct FractionShares;
+impl ShareAssets<AccountId, AssetId, Balance> for FractionShares {
+	fn create(id: AssetId, admin: &AccountId) -> DispatchResult {
+		Assets::force_create(Origin::root(), id, admin.clone().into(), false, 1)
+	}
+
+	fn mint(id: AssetId, admin: &AccountId, who: &AccountId, amount: Balance) -> DispatchResult {
+		Assets::mint(Origin::signed(admin.clone()), id, who.clone().into(), amount)
+	}
+
+	fn burn(id: AssetId, admin: &AccountId, who: &AccountId, amount: Balance) -> DispatchResult {
+		Assets::burn(Origin::signed(admin.clone()), id, who.clone().into(), amount)
+	}
+
+	fn destroy(id: AssetId) -> DispatchResult {
+		// No account holds shares once the supply is burned. Approvals may outlive the shares
+		// they were given for, so any number is accepted.
+		let witness = pallet_assets::DestroyWitness {
+			accounts: 0,
+			sufficients: 0,
+			approvals: u32::max_value(),
+		};
+		Assets::destroy(Origin::root(), id, witness).map(|_| ()).map_err(|e| e.error)
+	}
+
+	fn balance(id: AssetId, who: &AccountId) -> Balance {
+		Assets::balance(id, who.clone())
+	}
+
+	fn total_supply(id: AssetId) -> Balance {
+		Assets::total_supply(id)
+	}
+}
+
+/// Pays in CHB through `Balances`, or in a `pallet_assets` asset. Asset transfers ignore the
+/// existence requirement: an account whose balance would drop below the asset's minimum
+/// balance transfers the rest as well.
+pub struct MultiAssets;
+impl MultiAsset<AccountId, AssetId, Balance> for MultiAssets {
+	fn transfer(
+		asset: Option<AssetId>,
+		from: &AccountId,
+		to: &AccountId,
+		amount: Balance,
+		existence: ExistenceRequirement,
+	) -> DispatchResult {
+		match asset {
+			None => <Balances as Currency<AccountId>>::transfer(from, to, amount, existence),
+			Some(id) => Assets::transfer(Origin::signed(from.clone()), id, to.clone().into(), amount),
+		}
+	}
+}
+
+/// Collects fees paid in `pallet_assets` assets in the `AssetTxPayment` pot and splits them
+/// like [`DealWithFees`]: 80% to the treasury and 20% to the block author, who also receives
+/// the tip in full. The author's share goes to the treasury when there is no author or it
+/// cannot be delivered, for instance because it is below the asset's minimum balance. Refunds
+/// and amounts the treasury cannot receive either stay in the pot, recorded as stranded for
+/// governance to sweep.
+pub struct AssetFees;
+impl AssetFees {
+	/// Transfers `amount` of `asset_id` from the pot to the treasury, recording a failure.
+	fn pay_treasury(asset_id: AssetId, amount: Balance) {
+		if let Err(e) = Self::pay_out(asset_id, &Treasury::account_id(), amount) {
+			log::error!(
+				target: "runtime::chiba",
+				"Fee of {} in asset {} could not be paid to the treasury and stays in the pot: {:?}",
+				amount,
+				asset_id,
+				e,
+			);
+			AssetTxPayment::note_stranded(asset_id, amount);
+		}
+	}
+}
+
+impl OnChargeAssetTransaction<AccountId, AssetId, Balance> for AssetFees {
+	fn withdraw_fee(
+		who: &AccountId,
+		asset_id: AssetId,
+		fee: Balance,
+	) -> Result<(), TransactionValidityError> {
+		if fee.is_zero() {
+			return Ok(())
+		}
+		let pot = AssetTxPayment::account_id();
+		Assets::transfer(Origin::signed(who.clone()), asset_id, pot.into(), fee)
+			.map_err(|_| InvalidTransaction::Payment.into())
+	}
+
+	fn correct_and_deposit_fee(
+		who: &AccountId,
+		asset_id: AssetId,
+		paid: Balance,
+		corrected_fee: Balance,
+		tip: Balance,
+	) {
+		let refund = paid.saturating_sub(corrected_fee);
+		if let Err(e) = Self::pay_out(asset_id, who, refund) {
+			log::error!(
+				target: "runtime::chiba",
+				"Refund of {} in asset {} could not be paid and stays in the pot: {:?}",
+				refund,
+				asset_id,
+				e,
+			);
+			AssetTxPayment::note_stranded(asset_id, refund);
+		}
+
+		let fee = paid.saturating_sub(refund);
+		let tip = tip.min(fee);
+		let treasury_share = Percent::from_percent(80) * fee.saturating_sub(tip);
+		let author_share = fee.saturating_sub(treasury_share);
+		let author_paid = match Authorship::author() {
+			Some(author) => Self::pay_out(asset_id, &author, author_share).is_ok(),
+			None => false,
+		};
+		if author_paid {
+			Self::pay_treasury(asset_id, treasury_share);
+		} else {
+			Self::pay_treasury(asset_id, fee);
+		}
+	}
+
+	fn pay_out(asset_id: AssetId, to: &AccountId, amount: Balance) -> DispatchResult {
+		if amount.is_zero() {
+			return Ok(())
+		}
+		let pot = AssetTxPayment::account_id();
+		Assets::transfer(Origin::signed(pot), asset_id, to.clone().into(), amount)
+	}
+
+	#[cfg(feature = "runtime-benchmarks")]
+	fn fund_pot(asset_id: AssetId, amount: Balance) {
+		let pot = AssetTxPayment::account_id();
+		let _ = Assets::force_create(Origin::root(), asset_id, pot.clone().into(), true, 1);
+		Assets::mint(Origin::signed(pot.clone()), asset_id, pot.into(), amount)
+			.expect("the pot issues the asset; qed");
+	}
+}
+
+/// The weights of GRANDPA's calls in this runtime.
+///
+/// `pallet_grandpa` only ships weights for a runtime with staking, where reporting an offence
+/// touches up to 200 nominators of the offender. Here offences are handled by `ValidatorSet`,
+/// which only disables and removes the offender. These weights are put together by hand rather
+/// than benchmarked: the execution times are the ones of `pallet_grandpa`'s weights, measured by
+/// its `check_equivocation_proof` and `note_stalled` benchmarks, and the storage accesses are
+/// counted from this runtime's handling of offences.
+pub struct GrandpaWeights;
+impl pallet_grandpa::WeightInfo for GrandpaWeights {
+	fn report_equivocation(validator_count: u32) -> Weight {
+		// Proofs are checked against a set of at least 100 validators.
+		let validator_count = validator_count.max(100) as Weight;
+		// Checking the membership proof.
+		(35 * WEIGHT_PER_MICROS)
+			.saturating_add((175 * WEIGHT_PER_NANOS).saturating_mul(validator_count))
+			.saturating_add(RocksDbWeight::get().reads(5))
+			// Checking the equivocation proof.
+			.saturating_add(95 * WEIGHT_PER_MICROS)
+			// Reporting the offence: `Offences` records it, and `ValidatorSet` disables the
+			// offender in `Session` and removes it from the validators.
+			.saturating_add(110 * WEIGHT_PER_MICROS)
+			.saturating_add(RocksDbWeight::get().reads_writes(8, 6))
+			// Mapping the set ID to a session index.
+			.saturating_add(RocksDbWeight::get().reads(2))
+	}
+
+	fn note_stalled() -> Weight {
+		(3 * WEIGHT_PER_MICROS).saturating_add(RocksDbWeight::get().writes(1))
+	}
+}
+
+/// The validators of the current session, whose keys may sign media check reports.
+pub struct SessionValidators;
+impl Get<Vec<AccountId>> for SessionValidators {
+	fn get() -> Vec<AccountId> {
+		Session::validators()
+	}
+}
@@ -7,18 +7,28 @@
 include!(concat!(env!("OUT_DIR"), "/wasm_binary.rs"));
 
 use sp_std::prelude::*;
-use sp_core::{crypto::KeyTypeId, OpaqueMetadata};
+use sp_core::{
+	crypto::KeyTypeId,
+	u32_trait::{_1, _2, _3, _4},
+	OpaqueMetadata,
+};
 use sp_runtime::{
 	ApplyExtrinsicResult, generic, create_runtime_str, impl_opaque_keys, MultiSignature,
-	transaction_validity::{TransactionValidity, TransactionSource},
+	transaction_validity::{TransactionPriority, TransactionValidity, TransactionSource},
 };
 use sp_runtime::traits::{
-	AccountIdLookup, BlakeTwo256, Block as BlockT, Verify, IdentifyAccount, NumberFor,
+	AccountIdLookup, BlakeTwo256, Block as BlockT, ConvertInto, Verify, IdentifyAccount, NumberFor,
+	OpaqueKeys,
 };
 use sp_api::impl_runtime_apis;
+use codec::Encode;
+use chiba_nft_rpc_runtime_api::{
+	ClassDetails, Metadata, MetadataError, TokenDetails, TokenPage, TokenUser, TOKEN_SCAN_FACTOR,
+};
 use sp_consensus_aura::sr25519::AuthorityId as AuraId;
 use pallet_grandpa::{AuthorityId as GrandpaId, AuthorityList as GrandpaAuthorityList};
 use pallet_grandpa::fg_primitives;
+use pallet_session::historical as pallet_session_historical;
 use sp_version::RuntimeVersion;
 #[cfg(feature = "std")]
 use sp_version::NativeVersion;
@@ -28,16 +38,27 @@ use sp_version::NativeVersion;
 pub use sp_runtime::BuildStorage;
 pub use pallet_timestamp::Call as TimestampCall;
 pub use pallet_balances::Call as BalancesCall;
-pub use sp_runtime::{Permill, Perbill};
+pub use pallet_media_check::{GATEWAY_KEY as MEDIA_GATEWAY_KEY, KEY_TYPE as MEDIA_CHECK_KEY_TYPE};
+pub use sp_runtime::{FixedPointNumber, Permill, Perbill, Perquintill};
 pub use frame_support::{
-	construct_runtime, parameter_types, StorageValue,
+	construct_runtime, parameter_types, PalletId, StorageValue,
 	traits::{KeyOwnerProofSystem, Randomness},
 	weights::{
 		Weight, IdentityFee,
 		constants::{BlockExecutionWeight, ExtrinsicBaseWeight, RocksDbWeight, WEIGHT_PER_SECOND},
 	},
 };
-use pallet_transaction_payment::CurrencyAdapter;
+use pallet_transaction_payment::{CurrencyAdapter, Multiplier, TargetedFeeAdjustment};
+use frame_system::{EnsureOneOf, EnsureRoot};
+
+pub mod constants;
+use constants::{currency::*, fee::WeightToFee};
+mod impls;
+use impls::{
+	AssetFees, BaseFilter, DealWithFees, FractionShares, GrandpaWeights, MultiAssets,
+	SessionValidators,
+};
+pub mod migrations;
 
 /// An index to a block.
 pub type BlockNumber = u32;
@@ -65,6 +86,24 @@ pub type Hash = sp_core::H256;
 /// Digest item type.
 pub type DigestItem = generic::DigestItem<Hash>;
 
+/// Identifier of a fungible asset.
+pub type AssetId = u32;
+
+/// Identifier of an NFT class.
+pub type ClassId = u64;
+
+/// Identifier of an NFT within its class.
+pub type TokenId = u64;
+
+/// Chiba-specific data attached to every NFT class.
+pub type ClassData = pallet_chiba::ClassData;
+
+/// Chiba-specific data attached to every NFT.
+pub type TokenData = pallet_chiba::TokenData;
+
+/// An NFT class minted at genesis: owner, metadata, data and its tokens.
+pub type GenesisNftClass = orml_nft::GenesisTokens<AccountId, ClassData, TokenData>;
+
 /// Opaque types. These are used by the CLI to instantiate machinery that don't need to know
 /// the specifics of the runtime. They can then be made to be agnostic over specific formats
 /// of data like extrinsics, allowing for them to continue syncing the network through upgrades
@@ -93,10 +132,10 @@ pub const VERSION: RuntimeVersion = RuntimeVersion {
 	spec_name: create_runtime_str!("chiba"),
 	impl_name: create_runtime_str!("chiba"),
 	authoring_version: 1,
-	spec_version: 100,
+	spec_version: 113,
 	impl_version: 1,
 	apis: RUNTIME_API_VERSIONS,
-	transaction_version: 1,
+	transaction_version: 4,
 };
 
 
@@ -134,14 +173,15 @@ parameter_types! {
 		::with_sensible_defaults(2 * WEIGHT_PER_SECOND, NORMAL_DISPATCH_RATIO);
 	pub BlockLength: frame_system::limits::BlockLength = frame_system::limits::BlockLength
 		::max_with_normal_ratio(5 * 1024 * 1024, NORMAL_DISPATCH_RATIO);
-	pub const SS58Prefix: u8 = 42;
+	/// The Chiba address format. 42 is the generic substrate prefix.
+	pub const SS58Prefix: u8 = 57;
 }
 
 // Configure FRAME pallets to include in runtime.
 
 impl frame_system::Config for Runtime {
 	/// The basic call filter to use in dispatchable.
-	type BaseCallFilter = ();
+	type BaseCallFilter = BaseFilter;
 	/// Block & extrinsics weights: base values and limits.
 	type BlockWeights = BlockWeights;
 	/// The maximum length of a block (in bytes).
@@ -183,11 +223,49 @@ impl frame_system::Config for Runtime {
 	/// The data to be stored in an account.
 	type AccountData = pallet_balances::AccountData<Balance>;
 	/// Weight information for the extrinsics of this pallet.
-	type SystemWeightInfo = ();
-	/// This is used as an identifier of the chain. 42 is the generic substrate prefix.
+	type SystemWeightInfo = frame_system::weights::SubstrateWeight<Runtime>;
+	/// This is used as an identifier of the chain.
 	type SS58Prefix = SS58Prefix;
 }
 
+parameter_types! {
+	pub const SessionPeriod: BlockNumber = HOURS;
+	pub const SessionOffset: BlockNumber = 0;
+	pub const DisabledValidatorsThreshold: Perbill = Perbill::from_percent(17);
+}
+
+impl pallet_session::Config for Runtime {
+	type Event = Event;
+	type ValidatorId = AccountId;
+	type ValidatorIdOf = ConvertInto;
+	type ShouldEndSession = pallet_session::PeriodicSessions<SessionPeriod, SessionOffset>;
+	type NextSessionRotation = pallet_session::PeriodicSessions<SessionPeriod, SessionOffset>;
+	type SessionManager = pallet_session::historical::NoteHistoricalRoot<Self, ValidatorSet>;
+	type SessionHandler = <opaque::SessionKeys as OpaqueKeys>::KeyTypeIdProviders;
+	type Keys = opaque::SessionKeys;
+	type DisabledValidatorsThreshold = DisabledValidatorsThreshold;
+	type WeightInfo = pallet_session::weights::SubstrateWeight<Runtime>;
+}
+
+impl pallet_session::historical::Config for Runtime {
+	type FullIdentification = AccountId;
+	/// Every validator is fully identified by its account.
+	type FullIdentificationOf = ConvertInto;
+}
+
+parameter_types! {
+	pub const MinValidators: u32 = 1;
+	pub const MaxValidators: u32 = 100;
+}
+
+impl pallet_validator_set::Config for Runtime {
+	type Event = Event;
+	type AddRemoveOrigin = EnsureRootOrHalfCouncil;
+	type MinValidators = MinValidators;
+	type MaxValidators = MaxValidators;
+	type WeightInfo = pallet_validator_set::weights::SubstrateWeight<Runtime>;
+}
+
 impl pallet_aura::Config for Runtime {
 	type AuthorityId = AuraId;
 }
@@ -196,7 +274,7 @@ impl pallet_grandpa::Config for Runtime {
 	type Event = Event;
 	type Call = Call;
 
-	type KeyOwnerProofSystem = ();
+	type KeyOwnerProofSystem = Historical;
 
 	type KeyOwnerProof =
 		<Self::KeyOwnerProofSystem as KeyOwnerProofSystem<(KeyTypeId, GrandpaId)>>::Proof;
@@ -206,9 +284,36 @@ impl pallet_grandpa::Config for Runtime {
 		GrandpaId,
 	)>>::IdentificationTuple;
 
-	type HandleEquivocation = ();
+	type HandleEquivocation =
+		pallet_grandpa::EquivocationHandler<Self::KeyOwnerIdentification, Offences, ReportLongevity>;
+
+	type WeightInfo = GrandpaWeights;
+}
+
+parameter_types! {
+	/// Equivocation reports older than a day of sessions are rejected.
+	pub ReportLongevity: u64 = 24 * SessionPeriod::get() as u64;
+	pub OffencesWeightSoftLimit: Weight = Perbill::from_percent(60) *
+		BlockWeights::get().max_block;
+}
+
+impl pallet_offences::Config for Runtime {
+	type Event = Event;
+	type IdentificationTuple = pallet_session::historical::IdentificationTuple<Self>;
+	type OnOffenceHandler = ValidatorSet;
+	type WeightSoftLimit = OffencesWeightSoftLimit;
+}
+
+impl<C> frame_system::offchain::SendTransactionTypes<C> for Runtime where
+	Call: From<C>,
+{
+	type OverarchingCall = Call;
+	type Extrinsic = UncheckedExtrinsic;
+}
 
-	type WeightInfo = ();
+impl frame_system::offchain::SigningTypes for Runtime {
+	type Public = <Signature as Verify>::Signer;
+	type Signature = Signature;
 }
 
 parameter_types! {
@@ -220,11 +325,11 @@ impl pallet_timestamp::Config for Runtime {
 	type Moment = u64;
 	type OnTimestampSet = Aura;
 	type MinimumPeriod = MinimumPeriod;
-	type WeightInfo = ();
+	type WeightInfo = pallet_timestamp::weights::SubstrateWeight<Runtime>;
 }
 
 parameter_types! {
-	pub const ExistentialDeposit: u128 = 500;
+	pub const ExistentialDeposit: Balance = 1 * CENTS;
 	pub const MaxLocks: u32 = 50;
 }
 
@@ -234,26 +339,221 @@ impl pallet_balances::Config for Runtime {
 	type Balance = Balance;
 	/// The ubiquitous event type.
 	type Event = Event;
-	type DustRemoval = ();
+	type DustRemoval = Treasury;
 	type ExistentialDeposit = ExistentialDeposit;
 	type AccountStore = System;
 	type WeightInfo = pallet_balances::weights::SubstrateWeight<Runtime>;
 }
 
 parameter_types! {
-	pub const TransactionByteFee: Balance = 1;
+	pub const TransactionByteFee: Balance = 10 * MILLICENTS;
+	/// Fees rise while blocks are more than a quarter full and fall while they are emptier.
+	pub const TargetBlockFullness: Perquintill = Perquintill::from_percent(25);
+	pub AdjustmentVariable: Multiplier = Multiplier::saturating_from_rational(3, 100_000);
+	pub MinimumMultiplier: Multiplier = Multiplier::saturating_from_rational(1, 1_000_000u128);
 }
 
 impl pallet_transaction_payment::Config for Runtime {
-	type OnChargeTransaction = CurrencyAdapter<Balances, ()>;
+	type OnChargeTransaction = CurrencyAdapter<Balances, DealWithFees>;
 	type TransactionByteFee = TransactionByteFee;
-	type WeightToFee = IdentityFee<Balance>;
-	type FeeMultiplierUpdate = ();
+	type WeightToFee = WeightToFee;
+	type FeeMultiplierUpdate =
+		TargetedFeeAdjustment<Self, TargetBlockFullness, AdjustmentVariable, MinimumMultiplier>;
+}
+
+parameter_types! {
+	pub const UncleGenerations: BlockNumber = 0;
+}
+
+impl pallet_authorship::Config for Runtime {
+	type FindAuthor = pallet_session::FindAccountFromAuthorIndex<Self, Aura>;
+	type UncleGenerations = UncleGenerations;
+	type FilterUncle = ();
+	type EventHandler = ();
+}
+
+parameter_types! {
+	pub MaximumSchedulerWeight: Weight = Perbill::from_percent(80) *
+		BlockWeights::get().max_block;
+	pub const MaxScheduledPerBlock: u32 = 50;
+}
+
+impl pallet_scheduler::Config for Runtime {
+	type Event = Event;
+	type Origin = Origin;
+	type PalletsOrigin = OriginCaller;
+	type Call = Call;
+	type MaximumWeight = MaximumSchedulerWeight;
+	type ScheduleOrigin = EnsureRoot<AccountId>;
+	type MaxScheduledPerBlock = MaxScheduledPerBlock;
+	type WeightInfo = pallet_scheduler::weights::SubstrateWeight<Runtime>;
 }
 
-impl pallet_sudo::Config for Runtime {
+impl pallet_utility::Config for Runtime {
 	type Event = Event;
 	type Call = Call;
+	type WeightInfo = pallet_utility::weights::SubstrateWeight<Runtime>;
+}
+
+parameter_types! {
+	pub const CouncilMotionDuration: BlockNumber = 3 * DAYS;
+	pub const CouncilMaxProposals: u32 = 100;
+	pub const CouncilMaxMembers: u32 = 100;
+}
+
+type CouncilCollective = pallet_collective::Instance1;
+impl pallet_collective::Config<CouncilCollective> for Runtime {
+	type Origin = Origin;
+	type Proposal = Call;
+	type Event = Event;
+	type MotionDuration = CouncilMotionDuration;
+	type MaxProposals = CouncilMaxProposals;
+	type MaxMembers = CouncilMaxMembers;
+	type DefaultVote = pallet_collective::PrimeDefaultVote;
+	type WeightInfo = pallet_collective::weights::SubstrateWeight<Runtime>;
+}
+
+parameter_types! {
+	pub const TechnicalMotionDuration: BlockNumber = 3 * DAYS;
+	pub const TechnicalMaxProposals: u32 = 100;
+	pub const TechnicalMaxMembers: u32 = 100;
+}
+
+type TechnicalCollective = pallet_collective::Instance2;
+impl pallet_collective::Config<TechnicalCollective> for Runtime {
+	type Origin = Origin;
+	type Proposal = Call;
+	type Event = Event;
+	type MotionDuration = TechnicalMotionDuration;
+	type MaxProposals = TechnicalMaxProposals;
+	type MaxMembers = TechnicalMaxMembers;
+	type DefaultVote = pallet_collective::PrimeDefaultVote;
+	type WeightInfo = pallet_collective::weights::SubstrateWeight<Runtime>;
+}
+
+/// Root, or at least half of the council.
+pub type EnsureRootOrHalfCouncil = EnsureOneOf<
+	AccountId,
+	EnsureRoot<AccountId>,
+	pallet_collective::EnsureProportionAtLeast<_1, _2, AccountId, CouncilCollective>,
+>;
+
+parameter_types! {
+	pub const LaunchPeriod: BlockNumber = 7 * DAYS;
+	pub const VotingPeriod: BlockNumber = 7 * DAYS;
+	pub const FastTrackVotingPeriod: BlockNumber = 3 * HOURS;
+	pub const InstantAllowed: bool = true;
+	pub const MinimumDeposit: Balance = 100 * UNITS;
+	pub const EnactmentPeriod: BlockNumber = 2 * DAYS;
+	pub const CooloffPeriod: BlockNumber = 7 * DAYS;
+	pub const PreimageByteDeposit: Balance = 1 * CENTS;
+	pub const MaxVotes: u32 = 100;
+	pub const MaxProposals: u32 = 100;
+}
+
+impl pallet_democracy::Config for Runtime {
+	type Proposal = Call;
+	type Event = Event;
+	type Currency = Balances;
+	type EnactmentPeriod = EnactmentPeriod;
+	type LaunchPeriod = LaunchPeriod;
+	type VotingPeriod = VotingPeriod;
+	type MinimumDeposit = MinimumDeposit;
+	/// A straight majority of the council can decide what their next motion is.
+	type ExternalOrigin =
+		pallet_collective::EnsureProportionAtLeast<_1, _2, AccountId, CouncilCollective>;
+	/// A super-majority can have the next scheduled referendum be a straight majority-carries vote.
+	type ExternalMajorityOrigin =
+		pallet_collective::EnsureProportionAtLeast<_3, _4, AccountId, CouncilCollective>;
+	/// A unanimous council can have the next scheduled referendum be a straight default-carries
+	/// (NTB) vote.
+	type ExternalDefaultOrigin =
+		pallet_collective::EnsureProportionAtLeast<_1, _1, AccountId, CouncilCollective>;
+	/// Two thirds of the technical committee can have an ExternalMajority/ExternalDefault vote
+	/// be tabled immediately and with a shorter voting/enactment period.
+	type FastTrackOrigin =
+		pallet_collective::EnsureProportionAtLeast<_2, _3, AccountId, TechnicalCollective>;
+	type InstantOrigin =
+		pallet_collective::EnsureProportionAtLeast<_1, _1, AccountId, TechnicalCollective>;
+	type InstantAllowed = InstantAllowed;
+	type FastTrackVotingPeriod = FastTrackVotingPeriod;
+	/// To cancel a proposal which has been passed, 2/3 of the council must agree to it.
+	type CancellationOrigin =
+		pallet_collective::EnsureProportionAtLeast<_2, _3, AccountId, CouncilCollective>;
+	/// To cancel a proposal before it has been passed, the technical committee must be unanimous
+	/// or Root must agree.
+	type CancelProposalOrigin = EnsureOneOf<
+		AccountId,
+		EnsureRoot<AccountId>,
+		pallet_collective::EnsureProportionAtLeast<_1, _1, AccountId, TechnicalCollective>,
+	>;
+	type BlacklistOrigin = EnsureRoot<AccountId>;
+	/// Any single technical committee member may veto a coming council proposal, however they
+	/// can only do it once and it lasts only for the cool-off period.
+	type VetoOrigin = pallet_collective::EnsureMember<AccountId, TechnicalCollective>;
+	type CooloffPeriod = CooloffPeriod;
+	type PreimageByteDeposit = PreimageByteDeposit;
+	type OperationalPreimageOrigin = pallet_collective::EnsureMember<AccountId, CouncilCollective>;
+	type Slash = Treasury;
+	type Scheduler = Scheduler;
+	type PalletsOrigin = OriginCaller;
+	type MaxVotes = MaxVotes;
+	type WeightInfo = pallet_democracy::weights::SubstrateWeight<Runtime>;
+	type MaxProposals = MaxProposals;
+}
+
+parameter_types! {
+	pub const TreasuryPalletId: PalletId = PalletId(*b"py/trsry");
+	pub const ProposalBond: Permill = Permill::from_percent(5);
+	pub const ProposalBondMinimum: Balance = 1 * UNITS;
+	pub const SpendPeriod: BlockNumber = 6 * DAYS;
+	pub const Burn: Permill = Permill::from_percent(0);
+	pub const MaxApprovals: u32 = 100;
+}
+
+impl pallet_treasury::Config for Runtime {
+	type PalletId = TreasuryPalletId;
+	type Currency = Balances;
+	/// Three fifths of the council must approve a spend.
+	type ApproveOrigin = EnsureOneOf<
+		AccountId,
+		EnsureRoot<AccountId>,
+		pallet_collective::EnsureProportionAtLeast<_3, _5, AccountId, CouncilCollective>,
+	>;
+	type RejectOrigin = EnsureRootOrHalfCouncil;
+	type Event = Event;
+	type OnSlash = Treasury;
+	type ProposalBond = ProposalBond;
+	type ProposalBondMinimum = ProposalBondMinimum;
+	type SpendPeriod = SpendPeriod;
+	/// Unspent funds stay in the treasury for future grants.
+	type Burn = Burn;
+	type BurnDestination = ();
+	type SpendFunds = Bounties;
+	type WeightInfo = pallet_treasury::weights::SubstrateWeight<Runtime>;
+	type MaxApprovals = MaxApprovals;
+}
+
+parameter_types! {
+	pub const BountyDepositBase: Balance = 1 * UNITS;
+	pub const BountyDepositPayoutDelay: BlockNumber = 4 * DAYS;
+	pub const BountyUpdatePeriod: BlockNumber = 30 * DAYS;
+	pub const BountyCuratorDeposit: Permill = Permill::from_percent(50);
+	pub const BountyValueMinimum: Balance = 10 * UNITS;
+	pub const DataDepositPerByte: Balance = 1 * CENTS;
+	pub const MaximumReasonLength: u32 = 16384;
+}
+
+impl pallet_bounties::Config for Runtime {
+	type Event = Event;
+	type BountyDepositBase = BountyDepositBase;
+	type BountyDepositPayoutDelay = BountyDepositPayoutDelay;
+	type BountyUpdatePeriod = BountyUpdatePeriod;
+	type BountyCuratorDeposit = BountyCuratorDeposit;
+	type BountyValueMinimum = BountyValueMinimum;
+	type DataDepositPerByte = DataDepositPerByte;
+	type MaximumReasonLength = MaximumReasonLength;
+	type WeightInfo = pallet_bounties::weights::SubstrateWeight<Runtime>;
 }
 
 parameter_types! {
@@ -262,15 +562,15 @@ parameter_types! {
 
 impl pallet_atomic_swap::Config for Runtime {
 	type Event = Event;
-	type SwapAction = pallet_chiba::ChibaSwapAction<Runtime>;
+	type SwapAction = pallet_royalties::RoyaltySwapAction<Runtime>;
 	type ProofLimit = ProofLimit;
 }
 
 impl orml_nft::Config for Runtime {
-	type ClassId = u64;
-	type TokenId = u64;
-	type ClassData = pallet_chiba::ClassData;
-	type TokenData = pallet_chiba::TokenData;
+	type ClassId = ClassId;
+	type TokenId = TokenId;
+	type ClassData = ClassData;
+	type TokenData = TokenData;
 }
 
 impl pallet_chiba::Config for Runtime {
@@ -278,6 +578,168 @@ impl pallet_chiba::Config for Runtime {
 	type Currency = Balances;
 }
 
+parameter_types! {
+	pub const RoyaltiesPalletId: PalletId = PalletId(*b"chb/roya");
+	/// Creators may claim at most half of a sale.
+	pub const MaxRoyaltyBasisPoints: u16 = 5_000;
+}
+
+impl pallet_royalties::Config for Runtime {
+	type Event = Event;
+	type Currency = Balances;
+	type AssetId = AssetId;
+	type Assets = MultiAssets;
+	type Moderation = Moderation;
+	type PalletId = RoyaltiesPalletId;
+	type MaxRoyaltyBasisPoints = MaxRoyaltyBasisPoints;
+	type WeightInfo = pallet_royalties::weights::SubstrateWeight<Runtime>;
+}
+
+parameter_types! {
+	pub const MarketplacePalletId: PalletId = PalletId(*b"chb/mrkt");
+}
+
+impl pallet_marketplace::Config for Runtime {
+	type Event = Event;
+	type Currency = Balances;
+	type AssetId = AssetId;
+	type Assets = MultiAssets;
+	type Royalty = Royalties;
+	type Moderation = Moderation;
+	type PalletId = MarketplacePalletId;
+	type WeightInfo = pallet_marketplace::weights::SubstrateWeight<Runtime>;
+}
+
+parameter_types! {
+	pub const AuctionPalletId: PalletId = PalletId(*b"chb/auct");
+	pub const MinAuctionDuration: BlockNumber = 10 * MINUTES;
+	pub const MaxAuctionDuration: BlockNumber = 30 * DAYS;
+	pub const AntiSnipeDuration: BlockNumber = 5 * MINUTES;
+	pub const MaxAuctionsPerBlock: u32 = 100;
+}
+
+impl pallet_auction::Config for Runtime {
+	type Event = Event;
+	type Currency = Balances;
+	type Royalty = Royalties;
+	type Moderation = Moderation;
+	type PalletId = AuctionPalletId;
+	type MinDuration = MinAuctionDuration;
+	type MaxDuration = MaxAuctionDuration;
+	type AntiSnipeDuration = AntiSnipeDuration;
+	type MaxAuctionsPerBlock = MaxAuctionsPerBlock;
+	type WeightInfo = pallet_auction::weights::SubstrateWeight<Runtime>;
+}
+
+parameter_types! {
+	pub const RentalsPalletId: PalletId = PalletId(*b"chb/rent");
+	pub const MaxRentalDuration: BlockNumber = 365 * DAYS;
+	pub const MaxRentalsPerBlock: u32 = 100;
+}
+
+impl pallet_rentals::Config for Runtime {
+	type Event = Event;
+	type Currency = Balances;
+	type Moderation = Moderation;
+	type PalletId = RentalsPalletId;
+	type MaxDuration = MaxRentalDuration;
+	type MaxRentalsPerBlock = MaxRentalsPerBlock;
+	type WeightInfo = pallet_rentals::weights::SubstrateWeight<Runtime>;
+}
+
+parameter_types! {
+	pub const AssetDeposit: Balance = 100 * UNITS;
+	pub const ApprovalDeposit: Balance = UNITS;
+	pub const AssetStringLimit: u32 = 50;
+	pub const MetadataDepositBase: Balance = 10 * UNITS;
+	pub const MetadataDepositPerByte: Balance = UNITS;
+}
+
+impl pallet_assets::Config for Runtime {
+	type Event = Event;
+	type Balance = Balance;
+	type AssetId = AssetId;
+	type Currency = Balances;
+	type ForceOrigin = EnsureRootOrHalfCouncil;
+	type AssetDeposit = AssetDeposit;
+	type MetadataDepositBase = MetadataDepositBase;
+	type MetadataDepositPerByte = MetadataDepositPerByte;
+	type ApprovalDeposit = ApprovalDeposit;
+	type StringLimit = AssetStringLimit;
+	type Freezer = ();
+	type Extra = ();
+	type WeightInfo = pallet_assets::weights::SubstrateWeight<Runtime>;
+}
+
+parameter_types! {
+	pub const FractionsPalletId: PalletId = PalletId(*b"chb/frac");
+}
+
+impl pallet_fractions::Config for Runtime {
+	type Event = Event;
+	type Currency = Balances;
+	type AssetId = AssetId;
+	type ShareBalance = Balance;
+	type Assets = FractionShares;
+	type Royalty = Royalties;
+	type Moderation = Moderation;
+	type ShareDeposit = AssetDeposit;
+	type PalletId = FractionsPalletId;
+	type WeightInfo = pallet_fractions::weights::SubstrateWeight<Runtime>;
+}
+
+parameter_types! {
+	pub const AssetTxPaymentPalletId: PalletId = PalletId(*b"chb/atxp");
+}
+
+impl pallet_asset_tx_payment::Config for Runtime {
+	type Event = Event;
+	type AssetId = AssetId;
+	type OnChargeAssetTransaction = AssetFees;
+	type RateOrigin = EnsureRootOrHalfCouncil;
+	type SweepOrigin = EnsureRootOrHalfCouncil;
+	type PalletId = AssetTxPaymentPalletId;
+	type WeightInfo = pallet_asset_tx_payment::weights::SubstrateWeight<Runtime>;
+}
+
+parameter_types! {
+	pub const MaxNftBatchSize: u32 = 500;
+}
+
+impl pallet_nft_batch::Config for Runtime {
+	type Event = Event;
+	type Moderation = Moderation;
+	type Royalties = Royalties;
+	type MaxBatchSize = MaxNftBatchSize;
+	type WeightInfo = pallet_nft_batch::weights::SubstrateWeight<Runtime>;
+}
+
+impl pallet_moderation::Config for Runtime {
+	type Event = Event;
+	type ModeratorOrigin = EnsureRootOrHalfCouncil;
+	type WeightInfo = pallet_moderation::weights::SubstrateWeight<Runtime>;
+}
+
+parameter_types! {
+	pub const MediaCheckInterval: BlockNumber = 10 * MINUTES;
+	pub const MaxMediaChecksPerRun: u32 = 20;
+	pub const MediaCheckUnsignedPriority: TransactionPriority = TransactionPriority::max_value() / 2;
+}
+
+impl pallet_media_check::Config for Runtime {
+	type Event = Event;
+	type AuthorityId = pallet_media_check::crypto::MediaCheckAuthId;
+	type Validators = SessionValidators;
+	type CheckInterval = MediaCheckInterval;
+	type MaxChecksPerRun = MaxMediaChecksPerRun;
+	type UnsignedPriority = MediaCheckUnsignedPriority;
+	type WeightInfo = pallet_media_check::weights::SubstrateWeight<Runtime>;
+}
+
+impl pallet_storage_versions::Config for Runtime {
+	type GenesisVersions = migrations::GenesisVersions;
+}
+
 // Create the runtime by composing the FRAME pallets that were previously configured.
 construct_runtime!(
 	pub enum Runtime where
@@ -285,17 +747,46 @@ construct_runtime!(
 		NodeBlock = opaque::Block,
 		UncheckedExtrinsic = UncheckedExtrinsic
 	{
-		System: frame_system::{Pallet, Call, Config, Storage, Event<T>},
-		RandomnessCollectiveFlip: pallet_randomness_collective_flip::{Pallet, Call, Storage},
-		Timestamp: pallet_timestamp::{Pallet, Call, Storage, Inherent},
-		Aura: pallet_aura::{Pallet, Config<T>},
-		Grandpa: pallet_grandpa::{Pallet, Call, Storage, Config, Event},
-		Balances: pallet_balances::{Pallet, Call, Storage, Config<T>, Event<T>},
-		TransactionPayment: pallet_transaction_payment::{Pallet, Storage},
-		Sudo: pallet_sudo::{Pallet, Call, Config<T>, Storage, Event<T>},
-		AtomicSwap: pallet_atomic_swap::{Pallet, Call, Storage, Event<T>},
-		Nft: orml_nft::{Pallet, Call, Storage},
-		Chiba: pallet_chiba::{Pallet, Call, Storage, Event<T>},
+		// Each pallet keeps its index for good, since it is part of the encoding of calls and
+		// events. New pallets take the next free index, whatever their position in this list,
+		// and the index of a removed pallet is never reused: 7 was `Sudo`. The position only
+		// decides the order in which genesis is built and hooks run.
+		System: frame_system::{Pallet, Call, Config, Storage, Event<T>} = 0,
+		RandomnessCollectiveFlip: pallet_randomness_collective_flip::{Pallet, Call, Storage} = 1,
+		Timestamp: pallet_timestamp::{Pallet, Call, Storage, Inherent} = 2,
+		Balances: pallet_balances::{Pallet, Call, Storage, Config<T>, Event<T>} = 5,
+		TransactionPayment: pallet_transaction_payment::{Pallet, Storage} = 6,
+		// `Session` must come after `Balances`, since its genesis takes a reference on each
+		// validator account, which must exist by then. `ValidatorSet` and `Session` must come
+		// before the consensus pallets, whose authorities they initialise at genesis.
+		ValidatorSet: pallet_validator_set::{Pallet, Call, Storage, Config<T>, Event<T>} = 11,
+		Session: pallet_session::{Pallet, Call, Storage, Config<T>, Event} = 12,
+		Historical: pallet_session_historical::{Pallet} = 13,
+		Offences: pallet_offences::{Pallet, Call, Storage, Event} = 14,
+		Aura: pallet_aura::{Pallet, Config<T>} = 3,
+		Grandpa: pallet_grandpa::{Pallet, Call, Storage, Config, Event, ValidateUnsigned} = 4,
+		Authorship: pallet_authorship::{Pallet, Call, Storage} = 15,
+		Scheduler: pallet_scheduler::{Pallet, Call, Storage, Event<T>} = 16,
+		Utility: pallet_utility::{Pallet, Call, Event} = 17,
+		Council: pallet_collective::<Instance1>::{Pallet, Call, Storage, Origin<T>, Event<T>, Config<T>} = 18,
+		TechnicalCommittee: pallet_collective::<Instance2>::{Pallet, Call, Storage, Origin<T>, Event<T>, Config<T>} = 19,
+		Democracy: pallet_democracy::{Pallet, Call, Storage, Config, Event<T>} = 20,
+		Treasury: pallet_treasury::{Pallet, Call, Storage, Config, Event<T>} = 21,
+		Bounties: pallet_bounties::{Pallet, Call, Storage, Event<T>} = 22,
+		AtomicSwap: pallet_atomic_swap::{Pallet, Call, Storage, Event<T>} = 8,
+		Nft: orml_nft::{Pallet, Call, Storage, Config<T>} = 9,
+		Chiba: pallet_chiba::{Pallet, Call, Storage, Event<T>} = 10,
+		NftBatch: pallet_nft_batch::{Pallet, Call, Event<T>} = 23,
+		Royalties: pallet_royalties::{Pallet, Call, Storage, Event<T>} = 24,
+		Marketplace: pallet_marketplace::{Pallet, Call, Storage, Event<T>} = 25,
+		Auction: pallet_auction::{Pallet, Call, Storage, Event<T>} = 26,
+		Rentals: pallet_rentals::{Pallet, Call, Storage, Event<T>} = 27,
+		Assets: pallet_assets::{Pallet, Call, Storage, Event<T>} = 28,
+		Fractions: pallet_fractions::{Pallet, Call, Storage, Event<T>} = 29,
+		AssetTxPayment: pallet_asset_tx_payment::{Pallet, Call, Storage, Config, Event<T>} = 30,
+		Moderation: pallet_moderation::{Pallet, Call, Storage, Event<T>} = 31,
+		MediaCheck: pallet_media_check::{Pallet, Call, Storage, Event<T>, ValidateUnsigned} = 32,
+		StorageVersions: pallet_storage_versions::{Pallet, Config} = 33,
 	}
 );
 
@@ -317,7 +808,7 @@ pub type SignedExtra = (
 	frame_system::CheckEra<Runtime>,
 	frame_system::CheckNonce<Runtime>,
 	frame_system::CheckWeight<Runtime>,
-	pallet_transaction_payment::ChargeTransactionPayment<Runtime>
+	pallet_asset_tx_payment::ChargeAssetTxPayment<Runtime>
 );
 /// Unchecked extrinsic type as expected by this runtime.
 pub type UncheckedExtrinsic = generic::UncheckedExtrinsic<Address, Call, Signature, SignedExtra>;
@@ -330,6 +821,7 @@ pub type Executive = frame_executive::Executive<
 	frame_system::ChainContext<Runtime>,
 	Runtime,
 	AllPallets,
+	migrations::Migrations,
 >;
 
 impl_runtime_apis! {
@@ -422,23 +914,27 @@ impl_runtime_apis! {
 		}
 
 		fn submit_report_equivocation_unsigned_extrinsic(
-			_equivocation_proof: fg_primitives::EquivocationProof<
+			equivocation_proof: fg_primitives::EquivocationProof<
 				<Block as BlockT>::Hash,
 				NumberFor<Block>,
 			>,
-			_key_owner_proof: fg_primitives::OpaqueKeyOwnershipProof,
+			key_owner_proof: fg_primitives::OpaqueKeyOwnershipProof,
 		) -> Option<()> {
-			None
+			let key_owner_proof = key_owner_proof.decode()?;
+
+			Grandpa::submit_unsigned_equivocation_report(
+				equivocation_proof,
+				key_owner_proof,
+			)
 		}
 
 		fn generate_key_ownership_proof(
 			_set_id: fg_primitives::SetId,
-			_authority_id: GrandpaId,
+			authority_id: GrandpaId,
 		) -> Option<fg_primitives::OpaqueKeyOwnershipProof> {
-			// NOTE: this is the only implementation possible since we've
-			// defined our key owner proof type as a bottom type (i.e. a type
-			// with no values).
-			None
+			Historical::prove((fg_primitives::KEY_TYPE, authority_id))
+				.map(|p| p.encode())
+				.map(fg_primitives::OpaqueKeyOwnershipProof::new)
 		}
 	}
 
@@ -464,6 +960,117 @@ impl_runtime_apis! {
 		}
 	}
 
+	impl chiba_nft_rpc_runtime_api::ChibaNftApi<
+		Block,
+		AccountId,
+		ClassId,
+		TokenId,
+		ClassData,
+		TokenData,
+	> for Runtime {
+		fn class(
+			class_id: ClassId,
+		) -> Option<ClassDetails<AccountId, TokenId, ClassData>> {
+			Nft::classes(class_id).map(|class| ClassDetails {
+				owner: class.owner,
+				metadata: class.metadata,
+				total_issuance: class.total_issuance,
+				data: class.data,
+			})
+		}
+
+		fn token(
+			class_id: ClassId,
+			token_id: TokenId,
+		) -> Option<TokenDetails<AccountId, TokenData>> {
+			Nft::tokens(class_id, token_id).map(|token| TokenDetails {
+				owner: token.owner,
+				metadata: token.metadata,
+				data: token.data,
+			})
+		}
+
+		fn tokens_of(owner: AccountId) -> Vec<(ClassId, TokenId)> {
+			let mut tokens: Vec<_> = orml_nft::TokensByOwner::<Runtime>::iter_prefix(&owner)
+				.map(|(token, _)| token)
+				.collect();
+			tokens.sort();
+			tokens
+		}
+
+		fn classes_of(owner: AccountId) -> Vec<ClassId> {
+			let mut classes: Vec<_> = orml_nft::Classes::<Runtime>::iter()
+				.filter(|(_, class)| class.owner == owner)
+				.map(|(class_id, _)| class_id)
+				.collect();
+			classes.sort();
+			classes
+		}
+
+		fn class_token_count(class_id: ClassId) -> Option<TokenId> {
+			Nft::classes(class_id).map(|class| class.total_issuance)
+		}
+
+		fn tokens(
+			class_id: ClassId,
+			start: TokenId,
+			limit: u32,
+		) -> TokenPage<AccountId, TokenId, TokenData> {
+			// Token IDs are handed out sequentially, so walking them in order gives a stable
+			// page boundary. Burned tokens leave gaps, so the walk is capped instead of running
+			// until `limit` tokens are found, and the caller resumes from `next`.
+			let end = Nft::next_token_id(class_id);
+			let scan_end = start
+				.saturating_add(limit.saturating_mul(TOKEN_SCAN_FACTOR).into())
+				.min(end);
+			let mut tokens = Vec::new();
+			let mut token_id = start;
+			while token_id < scan_end && tokens.len() < limit as usize {
+				if let Some(token) = Nft::tokens(class_id, token_id) {
+					tokens.push((
+						token_id,
+						TokenDetails { owner: token.owner, metadata: token.metadata, data: token.data },
+					));
+				}
+				token_id += 1;
+			}
+			TokenPage { tokens, next: if token_id < end { Some(token_id) } else { None } }
+		}
+
+		fn class_metadata(class_id: ClassId) -> Option<Result<Metadata, MetadataError>> {
+			Nft::classes(class_id).map(|class| chiba_nft_metadata::parse(&class.metadata))
+		}
+
+		fn token_metadata(
+			class_id: ClassId,
+			token_id: TokenId,
+		) -> Option<Result<Metadata, MetadataError>> {
+			Nft::tokens(class_id, token_id).map(|token| chiba_nft_metadata::parse(&token.metadata))
+		}
+
+		fn token_user(
+			class_id: ClassId,
+			token_id: TokenId,
+		) -> Option<TokenUser<AccountId, BlockNumber>> {
+			let token = (class_id, token_id);
+			let owner = Rentals::offers(token)
+				.map(|offer| offer.owner)
+				.or_else(|| Nft::tokens(class_id, token_id).map(|token| token.owner))?;
+			Some(match Rentals::rentals(token) {
+				Some(rental) => TokenUser { user: rental.user, owner, expires: Some(rental.expires) },
+				None => TokenUser { user: owner.clone(), owner, expires: None },
+			})
+		}
+	}
+
+	#[cfg(feature = "try-runtime")]
+	impl frame_try_runtime::TryRuntime<Block> for Runtime {
+		fn on_runtime_upgrade() -> Result<(Weight, Weight), sp_runtime::RuntimeString> {
+			let weight = Executive::try_runtime_upgrade()?;
+			Ok((weight, BlockWeights::get().max_block))
+		}
+	}
+
 	#[cfg(feature = "runtime-benchmarks")]
 	impl frame_benchmarking::Benchmark<Block> for Runtime {
 		fn dispatch_benchmark(
@@ -472,7 +1079,10 @@ impl_runtime_apis! {
 			use frame_benchmarking::{Benchmarking, BenchmarkBatch, add_benchmark, TrackedStorageKey};
 
 			use frame_system_benchmarking::Module as SystemBench;
+			use pallet_nft_benchmarking::{atomic_swap::Pallet as AtomicSwapBench, nft::Pallet as NftBench};
 			impl frame_system_benchmarking::Config for Runtime {}
+			impl pallet_nft_benchmarking::atomic_swap::Config for Runtime {}
+			impl pallet_nft_benchmarking::nft::Config for Runtime {}
 
 			let whitelist: Vec<TrackedStorageKey> = vec![
 				// Block Number
@@ -498,6 +1108,25 @@ impl_runtime_apis! {
 			add_benchmark!(params, batches, frame_system, SystemBench::<Runtime>);
 			add_benchmark!(params, batches, pallet_balances, Balances);
 			add_benchmark!(params, batches, pallet_timestamp, Timestamp);
+			add_benchmark!(params, batches, pallet_scheduler, Scheduler);
+			add_benchmark!(params, batches, pallet_collective, Council);
+			add_benchmark!(params, batches, pallet_democracy, Democracy);
+			add_benchmark!(params, batches, pallet_treasury, Treasury);
+			add_benchmark!(params, batches, pallet_bounties, Bounties);
+			add_benchmark!(params, batches, orml_nft, NftBench::<Runtime>);
+			add_benchmark!(params, batches, pallet_atomic_swap, AtomicSwapBench::<Runtime>);
+			add_benchmark!(params, batches, pallet_royalties, Royalties);
+			add_benchmark!(params, batches, pallet_moderation, Moderation);
+			add_benchmark!(params, batches, pallet_marketplace, Marketplace);
+			add_benchmark!(params, batches, pallet_auction, Auction);
+			add_benchmark!(params, batches, pallet_rentals, Rentals);
+			add_benchmark!(params, batches, pallet_assets, Assets);
+			add_benchmark!(params, batches, pallet_fractions, Fractions);
+			add_benchmark!(params, batches, pallet_nft_batch, NftBatch);
+			add_benchmark!(params, batches, pallet_media_check, MediaCheck);
+			add_benchmark!(params, batches, pallet_asset_tx_payment, AssetTxPayment);
+			add_benchmark!(params, batches, pallet_validator_set, ValidatorSet);
+			add_benchmark!(params, batches, pallet_utility, Utility);
 
 			if batches.is_empty() { return Err("Benchmark not found for this pallet.".into()) }
 			Ok(batches)
@@ -0,0 +1,533 @@
+//! Storage migrations run by `Executive` when the runtime is upgraded.
+//!
+//! New migrations are appended to [`Migrations`]. A change to the encoding of `ClassData` or
+//! `TokenData` must bump [`NFT_DATA_VERSION`] and add a step to [`MigrateNftData`] that
+//! translates the values stored by `orml_nft` from the previous version.
+//!
+//! A new chain records the current versions at genesis through `pallet_storage_versions`, from
+//! [`GenesisVersions`], so its first upgrade does not migrate data that is already current.
+//!
+//! With the `try-runtime` feature, each migration also checks the state before and after it
+//! runs; see `doc/upgrades.md`.
+
+use codec::{Decode, DecodeAll};
+use frame_support::{
+	storage::{
+		migration::{get_storage_value, put_storage_value, take_storage_value},
+		unhashed, StoragePrefixedMap,
+	},
+	traits::{Get, InitializeMembers, OnRuntimeUpgrade},
+	weights::{constants::RocksDbWeight, Weight},
+};
+#[cfg(feature = "try-runtime")]
+use frame_support::ensure;
+use pallet_atomic_swap::SwapAction;
+use pallet_marketplace::{Listing, Listings};
+use sp_std::prelude::*;
+use crate::{AccountId, Balance, BlockNumber, Council, Runtime, TechnicalCommittee};
+
+/// The migrations run on every runtime upgrade, in order.
+pub type Migrations = (RemoveSudo, MigrateNftData, MigrateListings, MigratePendingSwaps);
+
+/// The version of the encoding of `ClassData` and `TokenData` this runtime expects.
+pub const NFT_DATA_VERSION: u16 = 1;
+
+/// The version of the encoding of marketplace listings this runtime expects.
+pub const LISTING_VERSION: u16 = 1;
+
+/// The version of the encoding of pending atomic swaps this runtime expects.
+pub const SWAP_VERSION: u16 = 1;
+
+const CHIBA: &[u8] = b"Chiba";
+const NFT_DATA_VERSION_KEY: &[u8] = b"NftDataVersion";
+const LISTING_VERSION_KEY: &[u8] = b"ListingVersion";
+const SWAP_VERSION_KEY: &[u8] = b"SwapVersion";
+const UNDECODABLE_SWAPS_KEY: &[u8] = b"UndecodableSwaps";
+
+/// The versions a new chain starts at, written to its genesis by `pallet_storage_versions`.
+pub struct GenesisVersions;
+
+impl Get<Vec<(&'static [u8], &'static [u8], u16)>> for GenesisVersions {
+	fn get() -> Vec<(&'static [u8], &'static [u8], u16)> {
+		sp_std::vec![
+			(CHIBA, NFT_DATA_VERSION_KEY, NFT_DATA_VERSION),
+			(CHIBA, LISTING_VERSION_KEY, LISTING_VERSION),
+			(CHIBA, SWAP_VERSION_KEY, SWAP_VERSION),
+		]
+	}
+}
+
+/// The version of the NFT data in storage. Chains launched before versioning was introduced
+/// are at version 0.
+pub fn nft_data_version() -> u16 {
+	get_storage_value(CHIBA, NFT_DATA_VERSION_KEY, &[]).unwrap_or_default()
+}
+
+/// Hands control of a chain launched with `pallet_sudo` over to governance.
+///
+/// The former sudo key becomes the sole initial member of the council and the technical
+/// committee, from where further members can be added through motions. The sudo key is then
+/// removed from storage.
+pub struct RemoveSudo;
+
+impl OnRuntimeUpgrade for RemoveSudo {
+	fn on_runtime_upgrade() -> Weight {
+		let key = match take_storage_value::<AccountId>(b"Sudo", b"Key", &[]) {
+			Some(key) => key,
+			None => return RocksDbWeight::get().reads(1),
+		};
+
+		if Council::members().is_empty() {
+			<Council as InitializeMembers<AccountId>>::initialize_members(&[key.clone()]);
+		}
+		if TechnicalCommittee::members().is_empty() {
+			<TechnicalCommittee as InitializeMembers<AccountId>>::initialize_members(&[key]);
+		}
+
+		RocksDbWeight::get().reads_writes(3, 3)
+	}
+
+	#[cfg(feature = "try-runtime")]
+	fn post_upgrade() -> Result<(), &'static str> {
+		ensure!(
+			get_storage_value::<AccountId>(b"Sudo", b"Key", &[]).is_none(),
+			"sudo key is still set",
+		);
+		Ok(())
+	}
+}
+
+/// Brings the NFT classes and tokens stored by `orml_nft` up to [`NFT_DATA_VERSION`].
+pub struct MigrateNftData;
+
+impl OnRuntimeUpgrade for MigrateNftData {
+	fn on_runtime_upgrade() -> Weight {
+		let stored = nft_data_version();
+		if stored >= NFT_DATA_VERSION {
+			return RocksDbWeight::get().reads(1)
+		}
+
+		// Version 1 only starts tracking the version; the encoding is the one chains launched
+		// with. Later versions add their translation steps here, e.g.
+		// `if stored < 2 { weight = weight.saturating_add(v2::migrate()) }`.
+		let weight: Weight = 0;
+
+		put_storage_value(CHIBA, NFT_DATA_VERSION_KEY, &[], NFT_DATA_VERSION);
+		log::info!(
+			target: "runtime::chiba",
+			"Migrated NFT data from version {} to {}",
+			stored,
+			NFT_DATA_VERSION,
+		);
+		weight.saturating_add(RocksDbWeight::get().reads_writes(1, 1))
+	}
+
+	#[cfg(feature = "try-runtime")]
+	fn pre_upgrade() -> Result<(), &'static str> {
+		let stored = nft_data_version();
+		ensure!(stored <= NFT_DATA_VERSION, "stored NFT data is newer than this runtime");
+
+		// Values of an older version are expected not to decode until they are migrated.
+		log::info!(
+			target: "runtime::chiba",
+			"NFT data version {}: {} classes and {} tokens do not decode as version {}",
+			stored,
+			undecodable::<orml_nft::ClassInfoOf<Runtime>>(&orml_nft::Classes::<Runtime>::final_prefix()),
+			undecodable::<orml_nft::TokenInfoOf<Runtime>>(&orml_nft::Tokens::<Runtime>::final_prefix()),
+			NFT_DATA_VERSION,
+		);
+		Ok(())
+	}
+
+	#[cfg(feature = "try-runtime")]
+	fn post_upgrade() -> Result<(), &'static str> {
+		ensure!(nft_data_version() == NFT_DATA_VERSION, "NFT data version was not updated");
+		ensure!(
+			undecodable::<orml_nft::ClassInfoOf<Runtime>>(&orml_nft::Classes::<Runtime>::final_prefix()) == 0,
+			"some NFT classes do not decode",
+		);
+		ensure!(
+			undecodable::<orml_nft::TokenInfoOf<Runtime>>(&orml_nft::Tokens::<Runtime>::final_prefix()) == 0,
+			"some NFT tokens do not decode",
+		);
+		Ok(())
+	}
+}
+
+/// A marketplace listing of the current version.
+type CurrentListing = Listing<AccountId, Balance, crate::AssetId>;
+
+/// The version of the marketplace listings in storage. Listings from before asset prices were
+/// introduced are at version 0.
+pub fn listing_version() -> u16 {
+	get_storage_value(CHIBA, LISTING_VERSION_KEY, &[]).unwrap_or_default()
+}
+
+/// Brings the marketplace listings up to [`LISTING_VERSION`].
+///
+/// Version 1 adds the asset a token is priced in. Listings of version 0 were all priced in the
+/// native currency.
+///
+/// Only values that decode exactly as a version 0 listing are translated. Values that already
+/// decode exactly as the current version are left as they are, and so is anything else, which
+/// is logged rather than truncated into a listing it never was.
+pub struct MigrateListings;
+
+impl OnRuntimeUpgrade for MigrateListings {
+	fn on_runtime_upgrade() -> Weight {
+		let stored = listing_version();
+		if stored >= LISTING_VERSION {
+			return RocksDbWeight::get().reads(1)
+		}
+
+		let prefix = Listings::<Runtime>::final_prefix();
+		let (mut read, mut translated, mut rejected): (Weight, Weight, Weight) = (0, 0, 0);
+		let mut key = prefix.to_vec();
+		while let Some(next) = sp_io::storage::next_key(&key).filter(|k| k.starts_with(&prefix)) {
+			read = read.saturating_add(1);
+			let value = unhashed::get_raw(&next).unwrap_or_default();
+			if CurrentListing::decode_all(&value).is_err() {
+				match <(AccountId, Balance)>::decode_all(&value) {
+					Ok((seller, price)) => {
+						unhashed::put(&next, &CurrentListing { seller, price, asset: None });
+						translated = translated.saturating_add(1);
+					},
+					Err(_) => {
+						log::error!(
+							target: "runtime::chiba",
+							"Listing under key 0x{} does not decode as version {} or {}; left as is",
+							sp_core::hexdisplay::HexDisplay::from(&next),
+							stored,
+							LISTING_VERSION,
+						);
+						rejected = rejected.saturating_add(1);
+					},
+				}
+			}
+			key = next;
+		}
+
+		put_storage_value(CHIBA, LISTING_VERSION_KEY, &[], LISTING_VERSION);
+		log::info!(
+			target: "runtime::chiba",
+			"Migrated {} listings from version {} to {}, {} did not decode",
+			translated,
+			stored,
+			LISTING_VERSION,
+			rejected,
+		);
+		RocksDbWeight::get().reads_writes(read.saturating_add(1), translated.saturating_add(1))
+	}
+
+	#[cfg(feature = "try-runtime")]
+	fn pre_upgrade() -> Result<(), &'static str> {
+		ensure!(listing_version() <= LISTING_VERSION, "stored listings are newer than this runtime");
+		Ok(())
+	}
+
+	#[cfg(feature = "try-runtime")]
+	fn post_upgrade() -> Result<(), &'static str> {
+		ensure!(listing_version() == LISTING_VERSION, "listing version was not updated");
+		ensure!(
+			undecodable::<CurrentListing>(&Listings::<Runtime>::final_prefix()) == 0,
+			"some listings do not decode",
+		);
+		Ok(())
+	}
+}
+
+/// The version of the pending atomic swaps in storage. Swaps from before royalties were charged
+/// on swaps are at version 0.
+pub fn swap_version() -> u16 {
+	get_storage_value(CHIBA, SWAP_VERSION_KEY, &[]).unwrap_or_default()
+}
+
+/// A pending swap of version 0, whose action is a `pallet_chiba::ChibaSwapAction`.
+#[derive(Decode)]
+struct ChibaPendingSwap {
+	source: AccountId,
+	action: pallet_chiba::ChibaSwapAction<Runtime>,
+	#[allow(dead_code)]
+	end_block: BlockNumber,
+}
+
+/// A pending swap of the current version.
+type CurrentPendingSwap = pallet_atomic_swap::PendingSwap<Runtime>;
+
+/// Brings the pending atomic swaps up to [`SWAP_VERSION`].
+///
+/// Version 1 replaces `pallet_chiba::ChibaSwapAction` with
+/// `pallet_royalties::RoyaltySwapAction`. The two cannot be translated into each other: a token
+/// swap of version 1 escrows the token and has the claimer pay its price, which version 0 swaps
+/// do not carry. Swaps of version 0 are therefore cancelled, which returns what they hold to
+/// their source, and removed. Their owners can create them again.
+///
+/// Swaps that already decode exactly as the current version are left as they are. Values that
+/// decode exactly as neither version cannot be refunded by the runtime, so their raw bytes are
+/// moved to `Chiba UndecodableSwaps` under the same key suffix, where governance can find them
+/// and refund their source.
+pub struct MigratePendingSwaps;
+
+impl OnRuntimeUpgrade for MigratePendingSwaps {
+	fn on_runtime_upgrade() -> Weight {
+		let stored = swap_version();
+		if stored >= SWAP_VERSION {
+			return RocksDbWeight::get().reads(1)
+		}
+
+		let prefix = pending_swaps_prefix();
+		let (mut read, mut cancelled, mut recorded): (Weight, Weight, Weight) = (0, 0, 0);
+		let mut key = prefix.to_vec();
+		while let Some(next) = sp_io::storage::next_key(&key).filter(|k| k.starts_with(&prefix)) {
+			read = read.saturating_add(1);
+			let value = unhashed::get_raw(&next).unwrap_or_default();
+			if CurrentPendingSwap::decode_all(&value).is_err() {
+				match ChibaPendingSwap::decode_all(&value) {
+					Ok(swap) => {
+						swap.action.cancel(&swap.source);
+						cancelled = cancelled.saturating_add(1);
+					},
+					Err(_) => {
+						log::error!(
+							target: "runtime::chiba",
+							"Pending swap under key 0x{} does not decode as version {} or {}; \
+							recorded under `Chiba UndecodableSwaps` for a manual refund",
+							sp_core::hexdisplay::HexDisplay::from(&next),
+							stored,
+							SWAP_VERSION,
+						);
+						put_storage_value(CHIBA, UNDECODABLE_SWAPS_KEY, &next[prefix.len()..], value);
+						recorded = recorded.saturating_add(1);
+					},
+				}
+				unhashed::kill(&next);
+			}
+			key = next;
+		}
+
+		put_storage_value(CHIBA, SWAP_VERSION_KEY, &[], SWAP_VERSION);
+		log::info!(
+			target: "runtime::chiba",
+			"Cancelled {} pending swaps of version {} to migrate to {}, recorded {} that did not decode",
+			cancelled,
+			stored,
+			SWAP_VERSION,
+			recorded,
+		);
+		// Cancelling a swap reads and writes the swap, and the source's balance or token.
+		// Recording one writes it under its new key and removes it.
+		RocksDbWeight::get().reads_writes(
+			read.saturating_add(cancelled.saturating_mul(2)).saturating_add(1),
+			cancelled.saturating_mul(3).saturating_add(recorded.saturating_mul(2)).saturating_add(1),
+		)
+	}
+
+	#[cfg(feature = "try-runtime")]
+	fn pre_upgrade() -> Result<(), &'static str> {
+		let stored = swap_version();
+		ensure!(stored <= SWAP_VERSION, "stored swaps are newer than this runtime");
+		if stored < SWAP_VERSION {
+			log::info!(
+				target: "runtime::chiba",
+				"Swap version {}: {} pending swaps decode as neither version and will be recorded \
+				under `Chiba UndecodableSwaps`",
+				stored,
+				count_values(&pending_swaps_prefix(), |value| {
+					CurrentPendingSwap::decode_all(value).is_err() &&
+						ChibaPendingSwap::decode_all(value).is_err()
+				}),
+			);
+		}
+		Ok(())
+	}
+
+	#[cfg(feature = "try-runtime")]
+	fn post_upgrade() -> Result<(), &'static str> {
+		ensure!(swap_version() == SWAP_VERSION, "swap version was not updated");
+		ensure!(
+			undecodable::<CurrentPendingSwap>(&pending_swaps_prefix()) == 0,
+			"some pending swaps do not decode",
+		);
+		Ok(())
+	}
+}
+
+/// The prefix of `pallet_atomic_swap`'s `PendingSwaps`, which the pallet does not expose.
+fn pending_swaps_prefix() -> [u8; 32] {
+	let mut prefix = [0; 32];
+	prefix[..16].copy_from_slice(&sp_io::hashing::twox_128(b"AtomicSwap"));
+	prefix[16..].copy_from_slice(&sp_io::hashing::twox_128(b"PendingSwaps"));
+	prefix
+}
+
+/// Counts the values under `prefix` that do not decode exactly as `V`.
+///
+/// Typed storage iterators skip such values, so they cannot be used to find them.
+#[cfg(feature = "try-runtime")]
+fn undecodable<V: DecodeAll>(prefix: &[u8]) -> u32 {
+	count_values(prefix, |value| V::decode_all(value).is_err())
+}
+
+/// Counts the values under `prefix` for which `matches` holds.
+#[cfg(feature = "try-runtime")]
+fn count_values(prefix: &[u8], matches: impl Fn(&[u8]) -> bool) -> u32 {
+	let mut count = 0;
+	let mut key = prefix.to_vec();
+	while let Some(next) = sp_io::storage::next_key(&key).filter(|k| k.starts_with(prefix)) {
+		if matches(&unhashed::get_raw(&next).unwrap_or_default()) {
+			count += 1;
+		}
+		key = next;
+	}
+	count
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use frame_support::traits::GenesisBuild;
+
+	fn seller() -> AccountId {
+		AccountId::new([1; 32])
+	}
+
+	/// The state of a chain just launched from this runtime.
+	fn fresh_genesis() -> sp_io::TestExternalities {
+		let mut storage = frame_system::GenesisConfig::default().build_storage::<Runtime>().unwrap();
+		<pallet_storage_versions::GenesisConfig as GenesisBuild<Runtime>>::assimilate_storage(
+			&Default::default(),
+			&mut storage,
+		).unwrap();
+		storage.into()
+	}
+
+	fn sudo_key() -> AccountId {
+		AccountId::new([2; 32])
+	}
+
+	#[test]
+	fn the_sudo_key_becomes_the_first_member_of_governance() {
+		fresh_genesis().execute_with(|| {
+			put_storage_value(b"Sudo", b"Key", &[], sudo_key());
+
+			RemoveSudo::on_runtime_upgrade();
+
+			assert_eq!(get_storage_value::<AccountId>(b"Sudo", b"Key", &[]), None);
+			assert_eq!(Council::members(), vec![sudo_key()]);
+			assert_eq!(TechnicalCommittee::members(), vec![sudo_key()]);
+		});
+	}
+
+	#[test]
+	fn removing_sudo_keeps_existing_members() {
+		fresh_genesis().execute_with(|| {
+			<Council as InitializeMembers<AccountId>>::initialize_members(&[seller()]);
+			put_storage_value(b"Sudo", b"Key", &[], sudo_key());
+
+			RemoveSudo::on_runtime_upgrade();
+
+			assert_eq!(get_storage_value::<AccountId>(b"Sudo", b"Key", &[]), None);
+			assert_eq!(Council::members(), vec![seller()]);
+			assert_eq!(TechnicalCommittee::members(), vec![sudo_key()]);
+		});
+	}
+
+	#[test]
+	fn removing_sudo_without_a_key_changes_nothing() {
+		fresh_genesis().execute_with(|| {
+			RemoveSudo::on_runtime_upgrade();
+
+			assert!(Council::members().is_empty());
+			assert!(TechnicalCommittee::members().is_empty());
+		});
+	}
+
+	#[test]
+	fn genesis_records_current_versions() {
+		fresh_genesis().execute_with(|| {
+			assert_eq!(nft_data_version(), NFT_DATA_VERSION);
+			assert_eq!(listing_version(), LISTING_VERSION);
+			assert_eq!(swap_version(), SWAP_VERSION);
+		});
+	}
+
+	#[test]
+	fn upgrading_fresh_genesis_leaves_current_data_alone() {
+		fresh_genesis().execute_with(|| {
+			let listing = Listing { seller: seller(), price: 100, asset: Some(7) };
+			Listings::<Runtime>::insert((0, 0), listing.clone());
+			let swap = pallet_atomic_swap::PendingSwap::<Runtime> {
+				source: seller(),
+				action: pallet_royalties::RoyaltySwapAction::Balance(100),
+				end_block: 10,
+			};
+			put_storage_value(b"AtomicSwap", b"PendingSwaps", b"swap", swap.clone());
+
+			Migrations::on_runtime_upgrade();
+
+			assert_eq!(Listings::<Runtime>::get((0, 0)), Some(listing));
+			assert_eq!(
+				get_storage_value(b"AtomicSwap", b"PendingSwaps", b"swap"),
+				Some(swap),
+			);
+			assert_eq!(listing_version(), LISTING_VERSION);
+			assert_eq!(swap_version(), SWAP_VERSION);
+		});
+	}
+
+	#[test]
+	fn listings_of_version_0_are_translated_only_when_they_decode_exactly() {
+		fresh_genesis().execute_with(|| {
+			unhashed::kill(&storage_key(LISTING_VERSION_KEY));
+			let old = Listings::<Runtime>::hashed_key_for((0, 0));
+			unhashed::put(&old, &(seller(), 100 as Balance));
+			let current = CurrentListing { seller: seller(), price: 100, asset: Some(7) };
+			Listings::<Runtime>::insert((0, 1), current.clone());
+			let garbage = Listings::<Runtime>::hashed_key_for((0, 2));
+			unhashed::put_raw(&garbage, &[1, 2, 3]);
+
+			MigrateListings::on_runtime_upgrade();
+
+			assert_eq!(
+				Listings::<Runtime>::get((0, 0)),
+				Some(CurrentListing { seller: seller(), price: 100, asset: None }),
+			);
+			assert_eq!(Listings::<Runtime>::get((0, 1)), Some(current));
+			assert_eq!(unhashed::get_raw(&garbage), Some(vec![1, 2, 3]));
+			assert_eq!(listing_version(), LISTING_VERSION);
+		});
+	}
+
+	/// The key of a version stored under `Chiba`.
+	fn storage_key(item: &[u8]) -> Vec<u8> {
+		[sp_io::hashing::twox_128(CHIBA), sp_io::hashing::twox_128(item)].concat()
+	}
+
+	#[test]
+	fn swaps_that_decode_as_neither_version_are_recorded() {
+		fresh_genesis().execute_with(|| {
+			unhashed::kill(&storage_key(SWAP_VERSION_KEY));
+			let current = CurrentPendingSwap {
+				source: seller(),
+				action: pallet_royalties::RoyaltySwapAction::Balance(100),
+				end_block: 10,
+			};
+			put_storage_value(b"AtomicSwap", b"PendingSwaps", b"current", current.clone());
+			let garbage = [pending_swaps_prefix().to_vec(), b"garbage".to_vec()].concat();
+			unhashed::put_raw(&garbage, &[1, 2, 3]);
+
+			MigratePendingSwaps::on_runtime_upgrade();
+
+			assert_eq!(
+				get_storage_value(b"AtomicSwap", b"PendingSwaps", b"current"),
+				Some(current),
+			);
+			assert_eq!(unhashed::get_raw(&garbage), None);
+			assert_eq!(
+				get_storage_value::<Vec<u8>>(CHIBA, UNDECODABLE_SWAPS_KEY, b"garbage"),
+				Some(vec![1, 2, 3]),
+			);
+			assert_eq!(swap_version(), SWAP_VERSION);
+		});
+	}
+}
@@ -0,0 +1,40 @@
+#!/usr/bin/env bash
+
+set -e
+
+echo "*** Regenerating the weights of the Chiba Studio pallets ***"
+
+cd $(dirname ${BASH_SOURCE[0]})/..
+
+cargo build --release --features runtime-benchmarks
+
+for pallet in royalties marketplace auction rentals fractions nft-batch media-check moderation \
+	asset-tx-payment validator-set; do
+	./target/release/chiba-node benchmark \
+		--chain dev \
+		--execution wasm \
+		--wasm-execution compiled \
+		--pallet pallet_${pallet//-/_} \
+		--extrinsic '*' \
+		--steps 50 \
+		--repeat 20 \
+		--heap-pages 4096 \
+		--template .maintain/frame-weight-template.hbs \
+		--output pallets/$pallet/src/weights.rs
+done
+
+# `orml_nft` and `pallet_atomic_swap` take no `WeightInfo`. Their results are written out for
+# checking the weights that cover them; see the README.
+mkdir -p target/benchmarks
+for pallet in orml_nft pallet_atomic_swap; do
+	./target/release/chiba-node benchmark \
+		--chain dev \
+		--execution wasm \
+		--wasm-execution compiled \
+		--pallet $pallet \
+		--extrinsic '*' \
+		--steps 50 \
+		--repeat 20 \
+		--heap-pages 4096 \
+		> target/benchmarks/$pallet.txt
+done
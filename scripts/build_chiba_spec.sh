@@ -0,0 +1,12 @@
+#!/usr/bin/env bash
+
+set -e
+
+echo "*** Generating the raw Chiba Studio chain spec ***"
+
+cd $(dirname ${BASH_SOURCE[0]})/..
+
+mkdir -p node/res
+cargo run --release -- build-spec --chain chiba-genesis --raw --disable-default-bootnode > node/res/chiba.json
+
+echo "*** Rebuild the node to embed node/res/chiba.json ***"